@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE audit_events(
+    id uuid PRIMARY KEY,
+    occurred_at timestamptz NOT NULL,
+    actor TEXT,
+    action TEXT NOT NULL,
+    target TEXT,
+    details jsonb NOT NULL DEFAULT '{}'::jsonb
+);
+CREATE INDEX audit_events_occurred_at_idx ON audit_events (occurred_at DESC);
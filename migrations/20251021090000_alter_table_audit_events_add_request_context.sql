@@ -0,0 +1,18 @@
+-- Add migration script here
+-- 요청 정보(IP, User-Agent, request id)를 함께 남긴다.
+ALTER TABLE audit_events ADD COLUMN ip TEXT;
+ALTER TABLE audit_events ADD COLUMN user_agent TEXT;
+ALTER TABLE audit_events ADD COLUMN request_id TEXT;
+CREATE INDEX audit_events_actor_idx ON audit_events (actor);
+CREATE INDEX audit_events_action_idx ON audit_events (action);
+
+-- 감사 로그는 추가만 가능하다. (UPDATE / DELETE 금지)
+CREATE FUNCTION audit_events_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_events_append_only
+BEFORE UPDATE OR DELETE ON audit_events
+FOR EACH ROW EXECUTE FUNCTION audit_events_append_only();
@@ -0,0 +1,8 @@
+-- Add migration script here
+CREATE TABLE user_identities(
+    provider TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE,
+    created_at timestamptz NOT NULL,
+    PRIMARY KEY (provider, subject)
+);
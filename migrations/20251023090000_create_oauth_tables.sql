@@ -0,0 +1,23 @@
+-- Add migration script here
+CREATE TABLE oauth_clients(
+    client_id TEXT PRIMARY KEY,
+    -- public client(PKCE 전용)는 secret이 없다.
+    client_secret_hash TEXT,
+    name TEXT NOT NULL,
+    redirect_uris TEXT[] NOT NULL,
+    grant_types TEXT[] NOT NULL,
+    scopes TEXT[] NOT NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE TABLE oauth_authorization_codes(
+    -- 코드 원문 대신 SHA-256 해시를 저장한다.
+    code_hash TEXT PRIMARY KEY,
+    client_id TEXT NOT NULL REFERENCES oauth_clients (client_id) ON DELETE CASCADE,
+    email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE,
+    redirect_uri TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    code_challenge TEXT NOT NULL,
+    expires_at timestamptz NOT NULL,
+    created_at timestamptz NOT NULL
+);
@@ -0,0 +1,14 @@
+-- Add migration script here
+-- WebAuthn user handle : email 대신 인증기에 저장되는 고정 식별자
+ALTER TABLE users ADD COLUMN user_handle uuid NOT NULL DEFAULT gen_random_uuid();
+
+CREATE TABLE webauthn_credentials(
+    credential_id TEXT PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- webauthn-rs Passkey 직렬화 값 (공개키, 서명 카운터 등)
+    passkey jsonb NOT NULL,
+    created_at timestamptz NOT NULL,
+    last_used_at timestamptz
+);
+CREATE INDEX webauthn_credentials_email_idx ON webauthn_credentials (email);
@@ -0,0 +1,15 @@
+-- Add migration script here
+-- Redis 대신 Postgres를 토큰/세션 저장소로 사용할 때 (store.backend = postgres)
+CREATE TABLE token_store(
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    expires_at timestamptz NOT NULL
+);
+CREATE INDEX token_store_expires_at_idx ON token_store (expires_at);
+
+CREATE TABLE sessions(
+    session_key TEXT PRIMARY KEY,
+    state jsonb NOT NULL,
+    expires_at timestamptz NOT NULL
+);
+CREATE INDEX sessions_expires_at_idx ON sessions (expires_at);
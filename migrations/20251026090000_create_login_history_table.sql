@@ -0,0 +1,15 @@
+-- Add migration script here
+CREATE TABLE login_history(
+    id uuid PRIMARY KEY,
+    email TEXT NOT NULL,
+    method TEXT NOT NULL,
+    ip TEXT,
+    user_agent TEXT,
+    -- 브라우저 / OS / 기기 종류만으로 만든 대략적인 기기 식별값 (새 기기 알림에 사용)
+    device_fingerprint TEXT NOT NULL,
+    device TEXT NOT NULL,
+    new_device BOOLEAN NOT NULL,
+    logged_in_at timestamptz NOT NULL
+);
+CREATE INDEX login_history_email_idx ON login_history (email, logged_in_at DESC);
+CREATE INDEX login_history_device_idx ON login_history (email, device_fingerprint);
@@ -0,0 +1,11 @@
+-- Add migration script here
+CREATE TABLE invitations(
+    code TEXT PRIMARY KEY,
+    -- 지정하면 이 이메일로만 가입할 수 있다.
+    email TEXT,
+    max_uses INTEGER NOT NULL CHECK (max_uses > 0),
+    uses INTEGER NOT NULL DEFAULT 0 CHECK (uses <= max_uses),
+    expires_at timestamptz,
+    created_by TEXT NOT NULL,
+    created_at timestamptz NOT NULL
+);
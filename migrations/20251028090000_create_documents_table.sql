@@ -0,0 +1,66 @@
+-- Add migration script here
+CREATE TABLE documents(
+    id uuid PRIMARY KEY,
+    -- 챕터 식별자 (ex. ch1_1) / URL의 첫 번째 경로
+    chapter TEXT NOT NULL,
+    -- 챕터가 속한 목차 구역 제목 (ex. Tracing)
+    section TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    -- 챕터 안에서의 순서 / 0이 챕터의 대표 문서
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at timestamptz NOT NULL,
+    updated_at timestamptz NOT NULL,
+    UNIQUE (chapter, slug)
+);
+CREATE INDEX documents_order_idx ON documents (chapter, position);
+
+-- 기존 templates/ch1_1, ch1_2의 학습 문서
+INSERT INTO documents (id, chapter, section, slug, title, body, position, created_at, updated_at) VALUES
+(gen_random_uuid(), 'ch1_1', 'Tracing', 'n1_basic', 'Tracing 기초',
+'<p>1. 구조화된 로깅과 분산 추적을 위한 강력한 프레임워크이다. 표준 로깅보다 더 풍부한 진단 정보를 제공한다.</p>
+<p>2. 복잡한 비동기 애플리케이션이나 마이크로서비스에서 요청 흐름을 추적하는 데 매우 유용하다. async/await와 완벽하게 통합</p>
+<p>3. 성능 : 비활성화된 로그는 거의 오버헤드가 없다.</p>
+<p>4. 구조화 : JSON,텍스트 등 다양한 형식으로 출력 가능</p>
+<p>5. 컨텍스트 : Span을 통해 관련 로그를 자동으로 그룹화</p>
+<p>6. 확장성 : 커스텀 subscriber로 원하는 백엔드에 로그 전송 가능</p>', 0, now(), now()),
+(gen_random_uuid(), 'ch1_1', 'Tracing', 'n2_settings', '기본 설정',
+'<p>1. 기본 설정 : Cargo.toml에서 Dependencies 추가</p>
+<pre><code># 고성능, 비동기 친화적 로깅 및 추적 라이브러리 / 기존 log크레이트와 연동해 호환성 있게 로그를 출력할 수 있다.
+tracing = {version = "0.1", features = ["log"]}
+# tracing의 서브스크라이버 구현체로 로그를 수신하고 출력하는 기능 담당
+tracing-subscriber = {version = "0.3", features = ["registry", "env-filter", "json", "fmt", "time"]}
+# 기존 log 크레이트로부터 tracing으로 로그 연동하는 브릿지 역할 수행
+tracing-log = "0.1"
+# 로그를 Bunyan 스타일의 JSON 포맷으로 출력해주는 크레이트
+tracing-bunyan-formatter = "0.3"
+# Actix-web 프레임워크용 tracing통합 크레이트
+tracing-actix-web = "0.6"</code></pre>', 1, now(), now()),
+(gen_random_uuid(), 'ch1_1', 'Tracing', 'n3_span_event', 'Span과 Event',
+'<p>1. Span : 시간의 기간을 나타내며, 작업의 시작과 끝을 추적한다.</p>
+<p>2. Event : 특정 시점에 발생하는 일회성 로그이다.</p>', 2, now(), now()),
+(gen_random_uuid(), 'ch1_2', 'Tracing', 'n1_basic', 'Subscriber 트레이트',
+'<p>1. Tracing 크레이트에서 트레이스 데이터를 수집하고 처리하는 핵심 트레이트</p>
+<p>2. 트레이스 이벤트와 스팬을 수집하는 역할을 한다.</p>
+<p>3. 애플리케이션 실행 중 발생하는 이벤트 로그(함수 집입/종료, 로그 메시지, 메트릭 수집)를 처리한다.</p>
+<p>4. 트레이스 데이터를 다양한 대상으로 출력하거나 필터링하는 기능을 정의한 추상 인터페이스.</p>', 0, now(), now()),
+(gen_random_uuid(), 'ch1_2', 'Tracing', 'n2_example', '예시',
+'<h4>핵심 코드</h4>
+<pre><code>pub fn get_subscriber&lt;Sink&gt;(
+    env_filter: String,
+    sink: Sink,
+    use_json: bool,
+) -&gt; Box&lt;dyn Subscriber + Send + Sync + ''static&gt;
+    where
+        Sink: for&lt;''a&gt;MakeWriter&lt;''a&gt; + Send + Sync + ''static
+{
+    if use_json {
+        Box::new(init_json_layer(env_filter, sink))
+    }else {
+        Box::new(init_pretty_layer(env_filter, sink))
+    }
+}</code></pre>
+<div class="info-box">
+    <p>조건에 따라 <strong>다른 타입</strong>을 반환하고 싶을 때, <code>impl Trait</code>만으로는 해결할 수 없습니다.</p>
+</div>', 1, now(), now());
@@ -0,0 +1,71 @@
+-- Add migration script here
+-- documents.body는 이제 마크다운 원문이다. (HTML 변환은 서버가 렌더링할 때 한다.)
+COMMENT ON COLUMN documents.body IS 'Markdown source';
+
+UPDATE documents SET updated_at = now(), body =
+'1. 구조화된 로깅과 분산 추적을 위한 강력한 프레임워크이다. 표준 로깅보다 더 풍부한 진단 정보를 제공한다.
+2. 복잡한 비동기 애플리케이션이나 마이크로서비스에서 요청 흐름을 추적하는 데 매우 유용하다. async/await와 완벽하게 통합
+3. 성능 : 비활성화된 로그는 거의 오버헤드가 없다.
+4. 구조화 : JSON,텍스트 등 다양한 형식으로 출력 가능
+5. 컨텍스트 : Span을 통해 관련 로그를 자동으로 그룹화
+6. 확장성 : 커스텀 subscriber로 원하는 백엔드에 로그 전송 가능'
+WHERE chapter = 'ch1_1' AND slug = 'n1_basic';
+
+UPDATE documents SET updated_at = now(), body =
+'## Cargo.toml에 Dependencies 추가
+
+```toml
+# 고성능, 비동기 친화적 로깅 및 추적 라이브러리 / 기존 log크레이트와 연동해 호환성 있게 로그를 출력할 수 있다.
+tracing = {version = "0.1", features = ["log"]}
+# tracing의 서브스크라이버 구현체로 로그를 수신하고 출력하는 기능 담당
+tracing-subscriber = {version = "0.3", features = ["registry", "env-filter", "json", "fmt", "time"]}
+# 기존 log 크레이트로부터 tracing으로 로그 연동하는 브릿지 역할 수행
+tracing-log = "0.1"
+# 로그를 Bunyan 스타일의 JSON 포맷으로 출력해주는 크레이트
+tracing-bunyan-formatter = "0.3"
+# Actix-web 프레임워크용 tracing통합 크레이트
+tracing-actix-web = "0.6"
+```'
+WHERE chapter = 'ch1_1' AND slug = 'n2_settings';
+
+UPDATE documents SET updated_at = now(), body =
+'## Span
+
+시간의 기간을 나타내며, 작업의 시작과 끝을 추적한다.
+
+## Event
+
+특정 시점에 발생하는 일회성 로그이다.'
+WHERE chapter = 'ch1_1' AND slug = 'n3_span_event';
+
+UPDATE documents SET updated_at = now(), body =
+'1. Tracing 크레이트에서 트레이스 데이터를 수집하고 처리하는 핵심 트레이트
+2. 트레이스 이벤트와 스팬을 수집하는 역할을 한다.
+3. 애플리케이션 실행 중 발생하는 이벤트 로그(함수 집입/종료, 로그 메시지, 메트릭 수집)를 처리한다.
+4. 트레이스 데이터를 다양한 대상으로 출력하거나 필터링하는 기능을 정의한 추상 인터페이스.'
+WHERE chapter = 'ch1_2' AND slug = 'n1_basic';
+
+UPDATE documents SET updated_at = now(), body =
+'## 핵심 코드
+
+```rust
+pub fn get_subscriber<Sink>(
+    env_filter: String,
+    sink: Sink,
+    use_json: bool,
+) -> Box<dyn Subscriber + Send + Sync + ''static>
+    where
+        Sink: for<''a> MakeWriter<''a> + Send + Sync + ''static
+{
+    if use_json {
+        Box::new(init_json_layer(env_filter, sink))
+    }else {
+        Box::new(init_pretty_layer(env_filter, sink))
+    }
+}
+```
+
+:::핵심
+조건에 따라 **다른 타입**을 반환하고 싶을 때, `impl Trait`만으로는 해결할 수 없습니다.
+:::'
+WHERE chapter = 'ch1_2' AND slug = 'n2_example';
@@ -0,0 +1,98 @@
+-- Add migration script here
+-- 목차(table_contents.html)에 제목만 있던 항목을 문서로 옮긴다.
+INSERT INTO documents (id, chapter, section, slug, title, body, position, created_at, updated_at) VALUES
+(gen_random_uuid(), 'ch0_1', '설계 패턴', 'n1_separation', '관심사의 분리',
+'## get_subscriber vs init_subscriber
+
+`get_subscriber`는 subscriber를 **만들기만** 하고, `init_subscriber`는 만들어진 subscriber를 전역으로 **등록**한다.
+
+```rust
+let subscriber = get_subscriber("info".into(), std::io::stdout, false);
+init_subscriber(subscriber);
+```
+
+## 분리하는 이유
+
+전역 subscriber는 프로세스에서 한 번만 등록할 수 있다. 생성과 등록을 나누면 출력 대상(sink)이나 형식(JSON, pretty)을 바꿔서 만든 뒤 등록 시점은 호출하는 쪽이 정할 수 있다.
+
+## 테스트 이점
+
+테스트는 `TEST_LOG` 환경 변수에 따라 `std::io::stdout` 또는 `std::io::sink`를 넘겨 subscriber를 만들고, `once_cell::Lazy`로 한 번만 등록한다.
+
+:::핵심
+만드는 함수와 등록하는 함수를 나누면 부작용(전역 등록)이 없는 쪽은 자유롭게 재사용하고 테스트할 수 있다.
+:::', 0, now(), now()),
+(gen_random_uuid(), 'ch0_2', '설계 패턴', 'n1_environment', '환경 변수 패턴',
+'## Environment enum
+
+실행 환경은 문자열 대신 enum으로 다룬다. 설정 파일 이름은 `as_str`로 얻는다.
+
+```rust
+pub enum Environment {
+    Local,
+    Production
+}
+```
+
+## TryFrom 구현
+
+`APP_ENVIRONMENT` 값을 `Environment`로 바꿀 때 지원하지 않는 값이면 오류를 반환한다.
+
+```rust
+impl TryFrom<String> for Environment {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "production" => Ok(Self::Production),
+            other => Err(format!("{} is not a supported environment.", other)),
+        }
+    }
+}
+```', 0, now(), now()),
+(gen_random_uuid(), 'ch1_3', 'Tracing', 'n1_impl_trait', 'impl Trait 패턴',
+'`impl Subscriber`를 반환 타입으로 사용해서 여러 레이어를 조합한 길고 복잡한 실제 타입을 숨긴다.
+
+## Send + Sync
+
+subscriber는 멀티스레드 환경에서 전역으로 사용되므로 반환 타입에 `Send + Sync`를 명시해야 한다. `Send`는 다른 스레드로 이동할 수 있음을, `Sync`는 여러 스레드에서 동시에 접근할 수 있음을 의미한다.
+
+## 불투명 타입
+
+`impl Trait`는 **어떤 구체 타입인지 숨기는 것**이 목적이므로, 컴파일러도 호출하는 쪽에서는 그 타입이 정확히 무엇인지 모른다.
+
+## 구체 타입 vs 불투명 타입
+
+| 특징 | 구체 타입(Concrete Type) | 불투명 타입(Opaque Type) |
+| --- | --- | --- |
+| 컴파일러 인식 | 정확한 타입을 알고 있음 | 트레이트를 구현한 무언가 |
+| 타입 비교 | 정확히 비교 가능 | 비교 불가능 |
+| match arms | 같은 타입이면 OK | 각각 다른 불투명 타입 |
+| 예시 | `Ordering`, `Decimal` | `impl Subscriber` |
+
+## 타입 불일치 문제
+
+`init_json_layer`와 `init_pretty_layer`가 모두 `impl Subscriber + Send + Sync`를 반환해도 서로 다른 불투명 타입이므로 `if`/`match`의 각 갈래에서 그대로 반환할 수 없다.
+
+## Box<dyn Trait>
+
+각 갈래를 `Box::new`로 감싸서 `Box<dyn Subscriber + Send + Sync>`라는 하나의 타입으로 맞춘다.
+
+:::핵심
+조건에 따라 다른 타입을 반환해야 하면 `Box<dyn Trait>`(동적 디스패치)를 사용하거나 함수를 분리한다.
+:::', 0, now(), now()),
+(gen_random_uuid(), 'ch1_4', 'Tracing', 'n1_lifetime', '생명주기와 HRTB',
+'## Higher-Ranked Trait Bounds
+
+`for<''a>`는 "모든 생명주기 `''a`에 대해"라는 의미이다. `Sink: for<''a> MakeWriter<''a>`는 `Sink`가 특정 생명주기 하나가 아니라 가능한 모든 생명주기에 대해 `MakeWriter`를 구현해야 한다는 제약이다.
+
+```rust
+fn init_pretty_layer<Sink>(env_filter: String, sink: Sink) -> impl Subscriber + Send + Sync + ''static
+where
+    Sink: for<''a> MakeWriter<''a> + Send + Sync + ''static
+```
+
+## ''static 생명주기
+
+`''static` 제약은 값이 빌린 참조를 포함하지 않아서 프로그램이 끝날 때까지 살아 있을 수 있다는 뜻이다. 전역 subscriber로 등록하려면 필요하다.', 0, now(), now());
@@ -0,0 +1,9 @@
+-- Add migration script here
+-- 사용자별 문서 읽음 기록 / 목차의 읽음 표시에 사용한다.
+CREATE TABLE reading_progress(
+    email TEXT NOT NULL,
+    document_id uuid NOT NULL REFERENCES documents (id) ON DELETE CASCADE,
+    started_at timestamptz NOT NULL,
+    last_read_at timestamptz NOT NULL,
+    PRIMARY KEY (email, document_id)
+);
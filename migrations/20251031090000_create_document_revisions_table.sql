@@ -0,0 +1,29 @@
+-- Add migration script here
+-- 사용자별 추가 권한 (관리자는 모든 권한을 가진 것으로 본다.) / ex) content:edit
+CREATE TABLE user_permissions(
+    email TEXT NOT NULL REFERENCES users (email) ON DELETE CASCADE,
+    permission TEXT NOT NULL,
+    granted_at timestamptz NOT NULL,
+    PRIMARY KEY (email, permission)
+);
+
+-- 낙관적 잠금 / 저장할 때마다 1씩 늘어나고, 편집을 시작한 시점의 값과 다르면 저장하지 않는다.
+ALTER TABLE documents ADD COLUMN revision INTEGER NOT NULL DEFAULT 1;
+
+-- 문서를 저장할 때마다 한 행씩 쌓인다. (diff, 되돌리기)
+CREATE TABLE document_revisions(
+    id uuid PRIMARY KEY,
+    document_id uuid NOT NULL REFERENCES documents (id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    author TEXT NOT NULL,
+    message TEXT NOT NULL DEFAULT '',
+    created_at timestamptz NOT NULL,
+    UNIQUE (document_id, revision)
+);
+
+-- 기존 문서의 현재 내용을 첫 번째 리비전으로 남긴다.
+INSERT INTO document_revisions (id, document_id, revision, title, body, author, message, created_at)
+SELECT gen_random_uuid(), id, revision, title, body, 'system', '초기 버전', updated_at
+FROM documents;
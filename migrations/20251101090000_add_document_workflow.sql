@@ -0,0 +1,25 @@
+-- Add migration script here
+-- 문서 게시 흐름 : draft -> in_review -> (scheduled ->) published -> archived
+-- 기존 문서와 테이블에 직접 넣은 문서는 게시된 상태로 본다.
+ALTER TABLE documents
+    ADD COLUMN status TEXT NOT NULL DEFAULT 'published'
+        CHECK (status IN ('draft', 'in_review', 'scheduled', 'published', 'archived')),
+    ADD COLUMN publish_at timestamptz,
+    ADD COLUMN published_revision INTEGER DEFAULT 1;
+
+UPDATE documents SET published_revision = revision;
+
+-- 예약 게시 스케줄러가 찾는 문서
+CREATE INDEX documents_scheduled_idx ON documents (publish_at) WHERE status = 'scheduled';
+
+CREATE TABLE document_reviews(
+    id uuid PRIMARY KEY,
+    document_id uuid NOT NULL REFERENCES documents (id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    reviewer TEXT NOT NULL,
+    decision TEXT NOT NULL CHECK (decision IN ('approved', 'changes_requested')),
+    comment TEXT NOT NULL DEFAULT '',
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX document_reviews_document_idx ON document_reviews (document_id, created_at);
@@ -0,0 +1,15 @@
+-- Add migration script here
+-- 문서 검색 색인 / 방문자에게 보이는 게시된 리비전만 들어간다. (src/documents/search.rs에서 갱신)
+-- search_vector : 제목(A), 본문(B)의 단어 / 한글은 두 글자씩 나눈 bigram으로 넣는다.
+CREATE TABLE document_search(
+    document_id uuid PRIMARY KEY REFERENCES documents (id) ON DELETE CASCADE,
+    chapter TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    title TEXT NOT NULL,
+    -- 마크다운 문법을 뺀 본문 (검색 결과 미리보기)
+    body TEXT NOT NULL,
+    search_vector tsvector NOT NULL,
+    indexed_at timestamptz NOT NULL
+);
+
+CREATE INDEX document_search_vector_idx ON document_search USING GIN (search_vector);
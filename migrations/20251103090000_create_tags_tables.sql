@@ -0,0 +1,46 @@
+-- Add migration script here
+-- 문서 태그 / slug는 URL(/tags/{slug})에 쓰는 영문 소문자, name은 화면에 보여주는 이름
+CREATE TABLE tags(
+    slug TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT ''
+);
+
+CREATE TABLE document_tags(
+    document_id uuid NOT NULL REFERENCES documents (id) ON DELETE CASCADE,
+    tag TEXT NOT NULL REFERENCES tags (slug) ON DELETE CASCADE,
+    PRIMARY KEY (document_id, tag)
+);
+
+CREATE INDEX document_tags_tag_idx ON document_tags (tag);
+
+INSERT INTO tags (slug, name, description) VALUES
+('tracing', 'Tracing', 'tracing 크레이트로 로그와 추적 정보 남기기'),
+('subscriber', 'Subscriber', '수집한 이벤트를 처리하고 출력하는 subscriber와 layer'),
+('traits', '트레이트', 'impl Trait, 트레이트 객체, 트레이트 경계'),
+('lifetimes', '생명주기', '생명주기와 HRTB'),
+('design', '설계', '관심사의 분리 등 애플리케이션 구조'),
+('configuration', '설정', '환경 변수와 설정 파일');
+
+INSERT INTO document_tags (document_id, tag)
+SELECT d.id, t.tag
+FROM documents d
+JOIN (VALUES
+    ('ch1_1', 'n1_basic', 'tracing'),
+    ('ch1_1', 'n2_settings', 'tracing'),
+    ('ch1_1', 'n2_settings', 'subscriber'),
+    ('ch1_1', 'n2_settings', 'configuration'),
+    ('ch1_1', 'n3_span_event', 'tracing'),
+    ('ch1_2', 'n1_basic', 'tracing'),
+    ('ch1_2', 'n1_basic', 'subscriber'),
+    ('ch1_2', 'n1_basic', 'traits'),
+    ('ch1_2', 'n2_example', 'tracing'),
+    ('ch1_2', 'n2_example', 'subscriber'),
+    ('ch1_3', 'n1_impl_trait', 'tracing'),
+    ('ch1_3', 'n1_impl_trait', 'traits'),
+    ('ch1_4', 'n1_lifetime', 'traits'),
+    ('ch1_4', 'n1_lifetime', 'lifetimes'),
+    ('ch0_1', 'n1_separation', 'design'),
+    ('ch0_2', 'n1_environment', 'design'),
+    ('ch0_2', 'n1_environment', 'configuration')
+) AS t (chapter, slug, tag) ON d.chapter = t.chapter AND d.slug = t.slug;
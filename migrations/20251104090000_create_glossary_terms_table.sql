@@ -0,0 +1,29 @@
+-- Add migration script here
+-- 용어집 / 문서 본문에서 term이나 aliases가 처음 나오는 곳을 용어 설명 링크로 바꾼다.
+-- definition은 마크다운 (용어집 페이지와 팝업에서 렌더링)
+CREATE TABLE glossary_terms(
+    slug TEXT PRIMARY KEY,
+    term TEXT NOT NULL,
+    aliases TEXT[] NOT NULL DEFAULT '{}',
+    definition TEXT NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+INSERT INTO glossary_terms (slug, term, aliases, definition) VALUES
+('hrtb', 'HRTB', ARRAY['Higher-Ranked Trait Bounds', 'for<''a>'],
+'**Higher-Ranked Trait Bounds** / `for<''a> Fn(&''a str)`처럼 "모든 생명주기 `''a`에 대해" 성립하는 트레이트 경계.
+클로저나 트레이트가 호출할 때마다 다른 생명주기의 참조를 받아야 할 때 사용한다.'),
+('static', '''static', ARRAY[]::TEXT[],
+'프로그램이 끝날 때까지 유효한 생명주기. 참조(`&''static str`)에 붙으면 그 값이 끝까지 살아 있다는 뜻이고,
+트레이트 경계(`T: ''static`)에 붙으면 `T`가 짧은 생명주기의 참조를 품고 있지 않다는 뜻이다.'),
+('box-dyn', 'Box<dyn Trait>', ARRAY['Box<dyn', '트레이트 객체', 'trait object'],
+'**트레이트 객체** / 구체 타입을 지우고 트레이트의 vtable을 통해 메서드를 호출하는 값.
+서로 다른 타입을 한 컬렉션에 담거나 런타임에 구현을 고를 때 사용한다. (동적 디스패치)'),
+('opaque-type', '불투명 타입', ARRAY['Opaque Type', 'impl Trait'],
+'`impl Trait`으로 반환하는 타입. 호출하는 쪽은 구체 타입을 모르고 트레이트로만 사용하지만,
+컴파일러는 구체 타입을 알고 있으므로 정적 디스패치된다. 함수마다 서로 다른 타입으로 취급된다.'),
+('send', 'Send', ARRAY[]::TEXT[],
+'값의 소유권을 다른 스레드로 옮겨도 안전한 타입에 자동으로 구현되는 마커 트레이트. (`Rc`는 `Send`가 아니다.)'),
+('sync', 'Sync', ARRAY[]::TEXT[],
+'여러 스레드에서 `&T`로 동시에 참조해도 안전한 타입에 자동으로 구현되는 마커 트레이트. `&T`가 `Send`이면 `T`는 `Sync`이다.');
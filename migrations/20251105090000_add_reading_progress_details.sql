@@ -0,0 +1,10 @@
+-- Add migration script here
+-- 읽기 진행 상황 / scroll_position은 본문을 읽은 비율(0 ~ 1), seconds_spent는 문서를 보고 있던 시간(초)
+-- completed_at : 본문 끝까지 읽은 시각 (목차의 완료 표시와 챕터 진행률)
+ALTER TABLE reading_progress
+    ADD COLUMN completed_at timestamptz,
+    ADD COLUMN scroll_position REAL NOT NULL DEFAULT 0 CHECK (scroll_position >= 0 AND scroll_position <= 1),
+    ADD COLUMN seconds_spent INTEGER NOT NULL DEFAULT 0 CHECK (seconds_spent >= 0);
+
+-- 홈 화면의 "이어서 읽기" (최근에 읽던 문서)
+CREATE INDEX reading_progress_recent_idx ON reading_progress (email, last_read_at DESC);
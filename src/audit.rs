@@ -0,0 +1,137 @@
+use actix_web::{HttpMessage, HttpRequest};
+use anyhow::Context;
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing_actix_web::RequestId;
+use uuid::Uuid;
+
+//감사 로그에 기록하는 행위 종류 (audit_events.action 컬럼 값)
+#[derive(Debug, Clone, Copy)]
+pub enum AuditAction {
+    LoginSuccess,
+    LoginFailure,
+    Logout,
+    TokenRefresh,
+    TokenRevoked,
+    Registration,
+    RegistrationFailure,
+    ImpersonationStart,
+    ImpersonatedRequest,
+    PasswordChange,
+    ForceLogout,
+    Reauthentication,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::LoginSuccess => "login_success",
+            AuditAction::LoginFailure => "login_failure",
+            AuditAction::Logout => "logout",
+            AuditAction::TokenRefresh => "token_refresh",
+            AuditAction::TokenRevoked => "token_revoked",
+            AuditAction::Registration => "registration",
+            AuditAction::RegistrationFailure => "registration_failure",
+            AuditAction::ImpersonationStart => "impersonation_start",
+            AuditAction::ImpersonatedRequest => "impersonated_request",
+            AuditAction::PasswordChange => "password_change",
+            AuditAction::ForceLogout => "force_logout",
+            AuditAction::Reauthentication => "reauthentication",
+        }
+    }
+}
+
+/*
+audit_events 테이블의 한 행
+    -> 빌더 패턴으로 필요한 값만 채운 뒤 record()로 기록한다.
+    -> record()는 tokio::spawn으로 백그라운드에서 INSERT하므로 인증 핸들러의 응답을 지연시키지 않는다.
+*/
+#[derive(Debug)]
+pub struct AuditEvent {
+    action: AuditAction,
+    actor: Option<String>,
+    target: Option<String>,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    request_id: Option<String>,
+    details: serde_json::Value,
+}
+
+impl AuditEvent {
+    pub fn new(action: AuditAction) -> Self {
+        Self {
+            action,
+            actor: None,
+            target: None,
+            ip: None,
+            user_agent: None,
+            request_id: None,
+            details: serde_json::json!({}),
+        }
+    }
+
+    //요청에서 IP, User-Agent, request id(TracingLogger가 발급)를 채운다.
+    pub fn from_request(action: AuditAction, req: &HttpRequest) -> Self {
+        let mut event = Self::new(action);
+        event.ip = req.peer_addr().map(|addr| addr.ip().to_string());
+        event.user_agent = req
+            .headers()
+            .get(actix_web::http::header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_owned());
+        event.request_id = req.extensions().get::<RequestId>().map(|id| id.to_string());
+        event
+    }
+
+    pub fn actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn details(mut self, details: serde_json::Value) -> Self {
+        self.details = details;
+        self
+    }
+
+    //비동기로 기록한다. 실패해도 요청 처리에는 영향을 주지 않고 에러 로그만 남긴다.
+    pub fn record(self, pool: &PgPool) {
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            if let Err(e) = insert_audit_event(&pool, &self).await {
+                tracing::error!(error.cause_chain = ?e, action = self.action.as_str(), "Failed to record audit event");
+            }
+        });
+    }
+}
+
+#[tracing::instrument(name = "Insert audit event", skip(pool))]
+async fn insert_audit_event(
+    pool: &PgPool,
+    event: &AuditEvent,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO audit_events (id, occurred_at, actor, action, target, ip, user_agent, request_id, details)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+        Uuid::new_v4(),
+        Utc::now(),
+        event.actor,
+        event.action.as_str(),
+        event.target,
+        event.ip,
+        event.user_agent,
+        event.request_id,
+        event.details
+    )
+    .execute(pool)
+    .await
+    .context("Failed to insert audit event")?;
+
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub mod proof_of_work;
+
+pub use proof_of_work::*;
\ No newline at end of file
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+use rand::distributions::{Alphanumeric, DistString};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::auth::TokenStore;
+use crate::configuration::ChallengeSettings;
+
+const CHALLENGE_KEY_PREFIX: &str = "pow_challenge:";
+const LOGIN_FAILURE_KEY_PREFIX: &str = "login_failures:";
+const CHALLENGE_LENGTH: usize = 32;
+
+//챌린지를 어디에 쓰는지 / 로그인용 챌린지로 가입할 수 없도록 발급할 때 함께 저장한다.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChallengePurpose {
+    Register,
+    Login,
+}
+
+impl ChallengePurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChallengePurpose::Register => "register",
+            ChallengePurpose::Login => "login",
+        }
+    }
+}
+
+//GET /api/challenge 응답
+#[derive(Debug, Serialize)]
+pub struct PowChallenge {
+    pub challenge: String,
+    pub difficulty: u32,
+    pub expires_in: u64,
+}
+
+/*
+작업 증명(proof-of-work) 챌린지 (hashcash 방식)
+    -> 발급 : 무작위 challenge를 목적, 난이도와 함께 토큰 저장소에 ttl_seconds 동안 저장한다.
+    -> 검증 : 저장소에서 challenge를 삭제(소비)한 뒤 sha256("{challenge}:{nonce}")의 선행 0 비트 수를 확인한다.
+              삭제에 성공한 요청만 검증하므로 같은 풀이를 다시 보내거나 동시에 보내도 한 번만 통과한다.
+    -> 로그인 실패 횟수도 같은 저장소의 카운터로 센다.
+*/
+#[derive(Debug, Clone)]
+pub struct ProofOfWork {
+    store: Arc<dyn TokenStore>,
+    settings: ChallengeSettings,
+}
+
+impl ProofOfWork {
+    pub fn new(store: Arc<dyn TokenStore>, settings: ChallengeSettings) -> Self {
+        Self { store, settings }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    pub async fn issue(&self, purpose: ChallengePurpose) -> Result<PowChallenge, anyhow::Error> {
+        let challenge = Alphanumeric.sample_string(&mut rand::thread_rng(), CHALLENGE_LENGTH);
+        let value = format!("{}:{}", purpose.as_str(), self.settings.difficulty);
+        self.store
+            .set_ex(&format!("{}{}", CHALLENGE_KEY_PREFIX, challenge), &value, self.settings.ttl_seconds)
+            .await?;
+
+        Ok(PowChallenge {
+            challenge,
+            difficulty: self.settings.difficulty,
+            expires_in: self.settings.ttl_seconds,
+        })
+    }
+
+    //풀이가 맞으면 true / 챌린지가 없거나(만료, 이미 사용) 목적이 다르거나 풀이가 틀리면 false
+    pub async fn verify(
+        &self,
+        purpose: ChallengePurpose,
+        challenge: Option<&str>,
+        nonce: Option<&str>,
+    ) -> Result<bool, anyhow::Error> {
+        let (Some(challenge), Some(nonce)) = (challenge, nonce) else {
+            return Ok(false);
+        };
+        let key = format!("{}{}", CHALLENGE_KEY_PREFIX, challenge);
+        let Some(value) = self.store.get(&key).await? else {
+            return Ok(false);
+        };
+        //틀린 풀이로도 챌린지를 소비해서, 하나의 챌린지로 여러 번 시도할 수 없게 한다.
+        if !self.store.del(&key).await? {
+            return Ok(false);
+        }
+        //발급 당시의 난이도로 검증한다. (설정이 바뀌어도 이미 발급한 챌린지는 유효)
+        let difficulty = match value.split_once(':') {
+            Some((stored_purpose, difficulty)) if stored_purpose == purpose.as_str() => difficulty.parse::<u32>()?,
+            _ => return Ok(false),
+        };
+
+        Ok(solves(challenge, nonce, difficulty))
+    }
+
+    //이 email로 로그인할 때 챌린지를 풀어야 하는지
+    pub async fn login_challenge_required(&self, email: &str) -> Result<bool, anyhow::Error> {
+        if !self.settings.enabled {
+            return Ok(false);
+        }
+        let failures = self.store
+            .get(&login_failure_key(email))
+            .await?
+            .and_then(|count| count.parse::<i64>().ok())
+            .unwrap_or(0);
+
+        Ok(failures >= self.settings.login_failure_threshold)
+    }
+
+    //실패 횟수는 첫 실패부터 failure_window_minutes 동안 센다.
+    pub async fn record_login_failure(&self, email: &str) -> Result<i64, anyhow::Error> {
+        self.store
+            .incr(&login_failure_key(email), self.settings.failure_window_minutes * 60)
+            .await
+    }
+
+    pub async fn reset_login_failures(&self, email: &str) -> Result<(), anyhow::Error> {
+        self.store.del(&login_failure_key(email)).await?;
+        Ok(())
+    }
+}
+
+//email 대소문자만 바꿔서 카운터를 피하지 못하도록 소문자로 센다.
+fn login_failure_key(email: &str) -> String {
+    format!("{}{}", LOGIN_FAILURE_KEY_PREFIX, email.to_lowercase())
+}
+
+//sha256("{challenge}:{nonce}")의 선행 0 비트 수가 difficulty 이상인지 확인한다. (static/js/common/pow.js와 같은 계산)
+pub fn solves(challenge: &str, nonce: &str, difficulty: u32) -> bool {
+    let hash = Sha256::digest(format!("{}:{}", challenge, nonce).as_bytes());
+    leading_zero_bits(&hash) >= difficulty
+}
+
+pub fn leading_zero_bits(bytes: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in bytes {
+        if *byte == 0 {
+            bits += 8;
+            continue;
+        }
+        bits += byte.leading_zeros();
+        break;
+    }
+    bits
+}
@@ -0,0 +1,87 @@
+use actix_session::{config::SessionMiddlewareBuilder, storage::SessionStore};
+use actix_web::{
+    HttpRequest,
+    cookie::{time, Cookie, CookieBuilder},
+};
+use crate::auth::RefreshTokenClaims;
+use crate::configuration::CookieSettings;
+
+pub const ACCESS_TOKEN_COOKIE: &str = "access_token";
+pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+pub const SESSION_COOKIE: &str = "id";
+
+/*
+인증 쿠키 생성기
+    -> access token, refresh token, 세션 ID 쿠키의 속성(Domain, Path, Secure, SameSite, 이름 접두사)을 한 곳에서 정한다.
+    -> 쿠키를 지울 때도 같은 속성으로 만들어야 브라우저가 같은 쿠키로 인식하므로 삭제 쿠키도 여기서 만든다.
+*/
+#[derive(Debug, Clone)]
+pub struct AuthCookies {
+    settings: CookieSettings,
+}
+
+impl AuthCookies {
+    pub fn new(settings: CookieSettings) -> Self {
+        Self { settings }
+    }
+
+    //실제 쿠키 이름 (host_prefix면 __Host- 접두사)
+    pub fn name(&self, name: &str) -> String {
+        match self.settings.host_prefix {
+            true => format!("__Host-{}", name),
+            false => name.to_owned(),
+        }
+    }
+
+    //요청에서 쿠키 값 읽기
+    pub fn read(&self, req: &HttpRequest, name: &str) -> Option<String> {
+        req.cookie(&self.name(name)).map(|c| c.value().to_string())
+    }
+
+    //access token 쿠키 / 만료되면 refresh token으로 다시 발급받으므로 토큰 수명(max_age)만큼만 유지한다.
+    pub fn access_token(&self, token: String, max_age: chrono::Duration) -> Cookie<'static> {
+        self.builder(ACCESS_TOKEN_COOKIE, token)
+            .max_age(time::Duration::seconds(max_age.num_seconds()))
+            .finish()
+    }
+
+    //refresh token 쿠키 / remember me면 토큰 만료 시각까지 유지하고, 아니면 브라우저를 닫으면 사라지는 세션 쿠키로 만든다.
+    pub fn refresh_token(&self, token: String, claims: &RefreshTokenClaims) -> Cookie<'static> {
+        let mut cookie = self.builder(REFRESH_TOKEN_COOKIE, token).finish();
+        if claims.remember {
+            let remaining = claims.exp as i64 - chrono::Utc::now().timestamp();
+            cookie.set_max_age(time::Duration::seconds(remaining.max(0)));
+        }
+        cookie
+    }
+
+    //쿠키 삭제 / 발급할 때와 같은 이름, Domain, Path로 만든다.
+    pub fn removal(&self, name: &str) -> Cookie<'static> {
+        self.builder(name, String::new())
+            .max_age(time::Duration::ZERO)
+            .finish()
+    }
+
+    //세션 ID 쿠키에도 같은 속성을 적용한다.
+    pub fn configure_session<S: SessionStore>(&self, builder: SessionMiddlewareBuilder<S>) -> SessionMiddlewareBuilder<S> {
+        builder
+            .cookie_name(self.name(SESSION_COOKIE))
+            .cookie_secure(self.settings.secure)
+            .cookie_same_site(self.settings.same_site.into())
+            .cookie_path(self.settings.path.clone())
+            .cookie_domain(self.settings.domain.clone())
+            .cookie_http_only(true)
+    }
+
+    fn builder(&self, name: &str, value: String) -> CookieBuilder<'static> {
+        let mut builder = Cookie::build(self.name(name), value)
+            .path(self.settings.path.clone())
+            .http_only(true)
+            .secure(self.settings.secure)
+            .same_site(self.settings.same_site.into());
+        if let Some(domain) = &self.settings.domain {
+            builder = builder.domain(domain.clone());
+        }
+        builder
+    }
+}
@@ -0,0 +1,3 @@
+pub mod cookie_factory;
+
+pub use cookie_factory::*;
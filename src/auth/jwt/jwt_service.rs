@@ -1,7 +1,5 @@
 use actix_web::{
-    HttpRequest, cookie::{
-        time, Cookie
-    },
+    HttpRequest, cookie::Cookie,
 };
 use chrono::{Utc, Duration};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode, errors::ErrorKind};
@@ -9,12 +7,10 @@ use serde::{
     Serialize,
     Deserialize
 };
-use redis::{
-    Client,
-    Commands, 
-    //RedisResult,
-};
+use std::sync::Arc;
 use uuid::Uuid;
+use crate::auth::{AuthCookies, TokenStore, ACCESS_TOKEN_COOKIE, REFRESH_TOKEN_COOKIE};
+use crate::configuration::AuthPolicy;
 use crate::error::{
     JwtError,
 };
@@ -29,6 +25,20 @@ pub struct AccessTokenClaims {
     pub iat: usize,
     //사용자 역할
     pub role: Option<String>,
+    //대리 로그인(impersonation) 시 실제 요청자 정보 (RFC 8693 act 클레임) / email이 sub 역할을 한다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaim>,
+    //JWT ID(폐기 목록 확인용)
+    pub jti: String,
+    //마지막으로 자격 증명(비밀번호 등)을 확인한 시각 / 민감한 작업의 재인증 판단에 사용한다.
+    #[serde(default)]
+    pub auth_time: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorClaim {
+    //대리 로그인을 시작한 관리자 이메일
+    pub sub: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,28 +51,82 @@ pub struct RefreshTokenClaims {
     pub iat: usize,
     //JWT ID(고유 식별자)
     pub jti: String,
+    //로그인 절대 만료 시각 (absolute timeout) / rotate 되어도 바뀌지 않는다.
+    pub session_exp: usize,
+    //"로그인 상태 유지" 선택 여부
+    #[serde(default)]
+    pub remember: bool,
+    //마지막으로 자격 증명을 확인한 시각 / rotate 되어도 바뀌지 않고, 재인증하면 갱신된다.
+    #[serde(default)]
+    pub auth_time: usize,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MagicLinkClaims {
+    //이메일
+    pub email: String,
+    //만료 시간
+    pub exp: usize,
+    //발급 시간
+    pub iat: usize,
+    //JWT ID(1회용 확인용)
+    pub jti: String,
+}
+
+//매직 링크 유효 시간(분)
+pub const MAGIC_LINK_MINUTES: i64 = 15;
+
+//대리 로그인 토큰 유효 시간(분) / 만료되면 관리자 본인의 refresh token으로 되돌아간다.
+pub const IMPERSONATION_MINUTES: i64 = 30;
+
 #[derive(Debug, Clone)]
 pub struct JwtService {
     pub secret: String,
-    pub redis_client: Client,
+    //refresh token, 폐기 목록 등을 저장하는 저장소 (Redis / Postgres / 메모리)
+    pub store: Arc<dyn TokenStore>,
+    //토큰 수명 정책
+    pub policy: AuthPolicy,
+    //access token, refresh token 쿠키 생성기
+    pub cookies: AuthCookies,
 }
 /*
 &self : 서버 실행 시 이미 jwt_secret를 받기 때문에 해당 메서드를 불러올때 직접 넘길 필요 없다.
 */
 impl JwtService {
-    pub fn new(secret: String, redis_client: Client) -> Self {
-        Self{secret, redis_client}
+    pub fn new(secret: String, store: Arc<dyn TokenStore>, policy: AuthPolicy, cookies: AuthCookies) -> Self {
+        Self{secret, store, policy, cookies}
     }
-    //access token 생성 함수
-    pub fn create_access_token(
+    //access token 생성 함수 / deadline : 로그인 절대 만료 시각(refresh token의 session_exp), auth_time : refresh token의 auth_time
+    pub async fn create_access_token(
         &self,
         email: &str,
         role: Option<String>,
+        deadline: usize,
+        auth_time: usize,
+    ) -> Result<String, JwtError> {
+        let expiration = self.policy.access_token_expiry(Utc::now().timestamp(), deadline as i64) as usize;
+
+        let claims = AccessTokenClaims {
+            email: email.to_owned(),
+            exp: expiration,
+            iat: Utc::now().timestamp() as usize,
+            role,
+            act: None,
+            jti: Uuid::new_v4().to_string(),
+            auth_time,
+        };
+        //println!("sucess");
+        self.issue_access_token(&claims).await
+    }
+
+    //대리 로그인용 access token 생성 함수 / refresh token은 발급하지 않으므로 시간이 지나면 자동으로 종료된다.
+    pub async fn create_impersonation_token(
+        &self,
+        email: &str,
+        actor: &str,
     ) -> Result<String, JwtError> {
         let expiration = Utc::now()
-            .checked_add_signed(Duration::minutes(15))
+            .checked_add_signed(Duration::minutes(IMPERSONATION_MINUTES))
             .expect("valid timestamp")
             .timestamp() as usize;
 
@@ -70,34 +134,69 @@ impl JwtService {
             email: email.to_owned(),
             exp: expiration,
             iat: Utc::now().timestamp() as usize,
-            role
+            role: None,
+            act: Some(ActorClaim { sub: actor.to_owned() }),
+            jti: Uuid::new_v4().to_string(),
+            auth_time: Utc::now().timestamp() as usize,
         };
-        //println!("sucess");
+        self.issue_access_token(&claims).await
+    }
+
+    /*
+    access token 서명 + 발급 기록
+        -> 강제 로그아웃 시 사용자의 유효한 access token을 찾을 수 있도록 토큰 저장소에 jti를 남긴다.
+        Key : access_token:{email}:{jti}
+        Value : exp
+        TTL : 토큰 남은 유효시간
+    */
+    async fn issue_access_token(
+        &self,
+        claims: &AccessTokenClaims,
+    ) -> Result<String, JwtError> {
         let token = encode(
-            &Header::default(), 
-            &claims, 
+            &Header::default(),
+            claims,
             &EncodingKey::from_secret(self.secret.as_ref())
         )
         .map_err(|e| JwtError::Other(e.to_string()))?;
 
+        let remaining = claims.exp as i64 - Utc::now().timestamp();
+        let key = format!("access_token:{}:{}", claims.email, claims.jti);
+        self.store.set_ex(&key, &claims.exp.to_string(), remaining.max(1) as u64).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
+
         Ok(token)
     }
 
-    //refresh token 생성 함수
-    pub fn create_refresh_token(
+    //refresh token 생성 함수 (로그인 시점) / 만료 시각은 AuthPolicy의 idle, absolute timeout 중 먼저 오는 것
+    pub async fn create_refresh_token(
         &self,
         email: &str,
-    ) -> Result<String, JwtError> {
+        remember: bool,
+    ) -> Result<(String, RefreshTokenClaims), JwtError> {
+        let login_at = Utc::now().timestamp();
+        let expiration = self.policy.initial_expiry(login_at, remember);
+        let session_exp = self.policy.login_deadline(login_at, remember);
+
+        self.sign_refresh_token(email, expiration as usize, session_exp as usize, remember, login_at as usize).await
+    }
+
+    async fn sign_refresh_token(
+        &self,
+        email: &str,
+        expiration: usize,
+        session_exp: usize,
+        remember: bool,
+        auth_time: usize,
+    ) -> Result<(String, RefreshTokenClaims), JwtError> {
         let jti = Uuid::new_v4().to_string();
-        let expiration = Utc::now()
-            .checked_add_signed(Duration::days(15))
-            .expect("valid timestamp")
-            .timestamp() as usize;
         let claims = RefreshTokenClaims {
             email: email.to_owned(),
             exp: expiration,
             iat: Utc::now().timestamp() as usize,
             jti: jti.clone(),
+            session_exp,
+            remember,
+            auth_time,
         };
         let token = encode(
             &Header::default(),
@@ -106,24 +205,24 @@ impl JwtService {
         )
         .map_err(|e| JwtError::Other(e.to_string()))?;
         /*
-        Redis에 Refresh Token 정보 저장
+        토큰 저장소에 Refresh Token 정보 저장
         Key : refresh_token:{email}:{jti}
         Value : token
-        TTL : 7일
+        TTL : 토큰 만료 시각까지 (JWT exp와 같다)
          */
-        let mut con = self.redis_client.get_connection().map_err(|e| JwtError::RedisError(e.to_string()))?;
         //println!("jti(create) : {}", jti);
-        let redis_key = format!("refresh_token:{}:{}", email, jti);
-        con.set_ex::<_, _, ()>(&redis_key, &token, 7*24*60*60).map_err(|e| JwtError::RedisError(e.to_string()))?;
+        let key = format!("refresh_token:{}:{}", email, jti);
+        let ttl = (expiration as i64 - Utc::now().timestamp()).max(1);
+        self.store.set_ex(&key, &token, ttl as u64).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
         /*
         반환 타입을 명시해야된다. -> Rust2024에서는 ()fallback을 금지한다.
          */
 
-        Ok(token)
+        Ok((token, claims))
     }
 
     //access token 검증 함수
-    pub fn verify_access_token(
+    pub async fn verify_access_token(
         &self,
         token: &str
     ) -> Result<AccessTokenClaims, JwtError> {
@@ -140,12 +239,16 @@ impl JwtService {
             _ => JwtError::Other(e.to_string()),
         })?;
         let claims = token_data.claims;
+        //로그아웃, 비밀번호 변경, 강제 로그아웃으로 폐기된 토큰
+        if self.is_jti_denied(&claims.jti).await? {
+            return Err(JwtError::TokenRevoked)
+        }
 
         Ok(claims)
     }
 
     //refresh token 검증 함수
-    pub fn verify_refresh_token(
+    pub async fn verify_refresh_token(
         &self,
         token: &str
     ) -> Result<RefreshTokenClaims, JwtError> {
@@ -163,11 +266,8 @@ impl JwtService {
         })?;
 
         let claims = token_data.claims;
-        println!("claims.email : {}, claims.jti : {}", claims.email, claims.jti);
-        let mut con = self.redis_client.get_connection().map_err(|e| JwtError::RedisError(e.to_string()))?;
-        let redis_key = format!("refresh_token:{}:{}", claims.email, claims.jti);
-        println!("redis_key : {}", redis_key);
-        let exists: bool = con.exists(&redis_key).map_err(|e| JwtError::RedisError(e.to_string()))?;
+        let key = format!("refresh_token:{}:{}", claims.email, claims.jti);
+        let exists = self.store.exists(&key).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
 
         if !exists {
             return Err(JwtError::TokenRevoked)
@@ -176,13 +276,33 @@ impl JwtService {
         Ok(claims)
     }
 
-    //refresh_token rotate 함수
-    pub fn rotate_refresh_token(
+    /*
+    refresh_token rotate 함수
+        -> sliding renewal이면 idle timeout을 지금부터 다시 세고, 아니면 기존 만료 시각을 그대로 이어받는다.
+        -> 어느 경우든 로그인 절대 만료 시각(session_exp)은 넘지 않는다.
+    */
+    pub async fn rotate_refresh_token(
         &self,
         token: &str,
-    ) -> Result<String, JwtError> {
+    ) -> Result<(String, RefreshTokenClaims), JwtError> {
+        self.rotate(token, None).await
+    }
+
+    //재인증 후 rotate / 로그인 만료 시각은 그대로 두고 auth_time만 지금으로 갱신한다.
+    pub async fn reauthenticate_refresh_token(
+        &self,
+        token: &str,
+    ) -> Result<(String, RefreshTokenClaims), JwtError> {
+        self.rotate(token, Some(Utc::now().timestamp() as usize)).await
+    }
+
+    async fn rotate(
+        &self,
+        token: &str,
+        auth_time: Option<usize>,
+    ) -> Result<(String, RefreshTokenClaims), JwtError> {
         let token_data = decode::<RefreshTokenClaims>(
-            &token,
+            token,
             &DecodingKey::from_secret(self.secret.as_ref()),
             &Validation::default()
         )
@@ -194,21 +314,23 @@ impl JwtService {
             _ => JwtError::Other(e.to_string()),
         })?;
         let claims = token_data.claims;
-        self.remove_refresh_token(&token).map_err(|e| JwtError::Other(e.to_string()))?;
-        
-        let refresh_token = self.create_refresh_token(&claims.email).expect("Fail to create refresh token");
+        let expiration = self.policy
+            .renewed_expiry(Utc::now().timestamp(), claims.exp as i64, claims.session_exp as i64, claims.remember)
+            .ok_or(JwtError::ExpiredToken)?;
+        self.remove_refresh_token(token).await?;
 
-        Ok(refresh_token)
+        let auth_time = auth_time.unwrap_or(claims.auth_time);
+        self.sign_refresh_token(&claims.email, expiration as usize, claims.session_exp, claims.remember, auth_time).await
     }
 
     //refresh token으로 새로운 Access Token 발급
-    pub fn refresh_access_token(
+    pub async fn refresh_access_token(
         &self,
         refresh_token: &str,
         role: Option<String>
     ) -> Result<String, JwtError> {
-        let claims = self.verify_refresh_token(refresh_token)?;
-        self.create_access_token(&claims.email, role).map_err(|e| e.into())
+        let claims = self.verify_refresh_token(refresh_token).await?;
+        self.create_access_token(&claims.email, role, claims.session_exp, claims.auth_time).await
     }
 
     //access token 추출 함수(쿠키용)
@@ -216,7 +338,7 @@ impl JwtService {
         &self,
         req: &HttpRequest
     ) -> Option<String> {
-        req.cookie("access_token").map(|s| s.value().to_string())
+        self.cookies.read(req, ACCESS_TOKEN_COOKIE)
     }
 
     //refresh token 추출 함수
@@ -224,16 +346,16 @@ impl JwtService {
         &self,
         req: &HttpRequest
     ) -> Option<String> {
-        req.cookie("refresh_token").map(|s| s.value().to_string())
+        self.cookies.read(req, REFRESH_TOKEN_COOKIE)
     }
 
-    //refresh token 삭제(Redis) 함수
-    pub fn remove_refresh_token(
+    //refresh token 삭제(토큰 저장소) 함수
+    pub async fn remove_refresh_token(
         &self,
         token: &str,
     ) -> Result<(), JwtError> {
         let token_data = decode::<RefreshTokenClaims>(
-            &token,
+            token,
             &DecodingKey::from_secret(self.secret.as_ref()),
             &Validation::default()
         )
@@ -245,25 +367,167 @@ impl JwtService {
             _ => JwtError::Other(e.to_string()),
         })?;
         let claims = token_data.claims;
-        let mut con = self.redis_client.get_connection().map_err(|e| JwtError::RedisError(e.to_string()))?;
         //println!("jti(remove) : {}", claims.jti);
-        let redis_key = format!("refresh_token:{}:{}", claims.email, claims.jti);
-        //println!("redis key: {}", redis_key);
-        con.del::<_, ()>(&redis_key).map_err(|e| JwtError::RedisError(e.to_string()))?;
+        let key = format!("refresh_token:{}:{}", claims.email, claims.jti);
+        self.store.del(&key).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
 
         Ok(())
     }
 
+    //access token, refresh token 쿠키 발급 / access token 쿠키는 토큰 수명만큼만 유지한다.
+    pub fn token_cookies(
+        &self,
+        access_token: String,
+        refresh_token: String,
+        refresh_claims: &RefreshTokenClaims,
+    ) -> (Cookie<'static>, Cookie<'static>) {
+        (
+            self.cookies.access_token(access_token, self.policy.access_token_ttl()),
+            self.cookies.refresh_token(refresh_token, refresh_claims),
+        )
+    }
+
     //access_token, refresh_token 쿠키삭제
     pub fn remove_token_cookie(
         &self,
         name: &str,
     ) -> Cookie<'static> {
-        Cookie::build(name.to_owned(), "")
-            .path("/")
-            .http_only(true)
-            .max_age(time::Duration::ZERO)
-            .finish()
+        self.cookies.removal(name)
+    }
+
+    /*
+    매직 링크 토큰 생성 함수
+        -> access token과 혼용되지 않도록 secret에서 파생한 별도의 키로 서명한다.
+        -> 토큰 저장소에 jti를 저장해두고, 사용 시 삭제해서 한 번만 쓸 수 있게 한다.
+    */
+    pub async fn create_magic_link_token(
+        &self,
+        email: &str,
+    ) -> Result<String, JwtError> {
+        let jti = Uuid::new_v4().to_string();
+        let expiration = Utc::now()
+            .checked_add_signed(Duration::minutes(MAGIC_LINK_MINUTES))
+            .expect("valid timestamp")
+            .timestamp() as usize;
+        let claims = MagicLinkClaims {
+            email: email.to_owned(),
+            exp: expiration,
+            iat: Utc::now().timestamp() as usize,
+            jti: jti.clone(),
+        };
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.magic_link_secret().as_ref())
+        )
+        .map_err(|e| JwtError::Other(e.to_string()))?;
+
+        let key = format!("magic_link:{}", jti);
+        self.store.set_ex(&key, email, (MAGIC_LINK_MINUTES * 60) as u64).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
+
+        Ok(token)
+    }
+
+    //매직 링크 토큰 검증 + 소비 함수 / DEL 결과가 0이면 이미 사용된 링크이므로 TokenRevoked를 반환한다.
+    pub async fn consume_magic_link_token(
+        &self,
+        token: &str,
+    ) -> Result<MagicLinkClaims, JwtError> {
+        let token_data = decode::<MagicLinkClaims>(
+            token,
+            &DecodingKey::from_secret(self.magic_link_secret().as_ref()),
+            &Validation::default()
+        )
+        .map_err(|e| match *e.kind() {
+            ErrorKind::ExpiredSignature => JwtError::ExpiredToken,
+            ErrorKind::InvalidSignature => JwtError::InvalidSignature,
+            ErrorKind::InvalidIssuer => JwtError::InvalidIssuer,
+            ErrorKind::InvalidToken => JwtError::InvalidToken,
+            _ => JwtError::Other(e.to_string()),
+        })?;
+        let claims = token_data.claims;
+
+        let key = format!("magic_link:{}", claims.jti);
+        let deleted = self.store.del(&key).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
+        if !deleted {
+            return Err(JwtError::TokenRevoked)
+        }
+
+        Ok(claims)
+    }
+
+    /*
+    access token 폐기 목록(denylist)
+        -> access token은 서명만으로 검증되므로 만료 전까지 유효하다. 폐기된 jti를 토큰 저장소에 남겨 verify_access_token에서 거부한다.
+        -> TTL을 토큰의 남은 유효시간으로 두면 만료와 동시에 키도 사라진다.
+        Key : access_denylist:{jti}
+    */
+    pub async fn deny_jti(
+        &self,
+        jti: &str,
+        exp: usize,
+    ) -> Result<(), JwtError> {
+        let remaining = exp as i64 - Utc::now().timestamp();
+        if remaining <= 0 {
+            return Ok(())
+        }
+        self.store.set_ex(&format!("access_denylist:{}", jti), "1", remaining as u64)
+            .await
+            .map_err(|e| JwtError::StoreError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn is_jti_denied(
+        &self,
+        jti: &str,
+    ) -> Result<bool, JwtError> {
+        self.store.exists(&format!("access_denylist:{}", jti)).await.map_err(|e| JwtError::StoreError(e.to_string()))
+    }
+
+    //access token 하나를 폐기한다. (로그아웃)
+    pub async fn revoke_access_token(
+        &self,
+        claims: &AccessTokenClaims,
+    ) -> Result<(), JwtError> {
+        self.deny_jti(&claims.jti, claims.exp).await?;
+        self.store.del(&format!("access_token:{}:{}", claims.email, claims.jti)).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /*
+    사용자의 모든 토큰 폐기 (비밀번호 변경, 관리자 강제 로그아웃)
+        -> 발급 기록(access_token:{email}:*)의 jti를 모두 폐기 목록에 올리고, refresh token은 삭제한다.
+        -> 폐기한 access token 개수를 반환한다.
+    */
+    pub async fn revoke_all_tokens(
+        &self,
+        email: &str,
+    ) -> Result<usize, JwtError> {
+        let access_keys = self.store.keys_with_prefix(&format!("access_token:{}:", email))
+            .await
+            .map_err(|e| JwtError::StoreError(e.to_string()))?;
+        let refresh_keys = self.store.keys_with_prefix(&format!("refresh_token:{}:", email))
+            .await
+            .map_err(|e| JwtError::StoreError(e.to_string()))?;
+
+        for key in &access_keys {
+            let exp = self.store.get(key).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
+            if let (Some(exp), Some(jti)) = (exp.and_then(|exp| exp.parse::<usize>().ok()), key.rsplit(':').next()) {
+                self.deny_jti(jti, exp).await?;
+            }
+            self.store.del(key).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
+        }
+        for key in &refresh_keys {
+            self.store.del(key).await.map_err(|e| JwtError::StoreError(e.to_string()))?;
+        }
+
+        Ok(access_keys.len())
+    }
+
+    fn magic_link_secret(&self) -> String {
+        format!("{}:magic_link", self.secret)
     }
 
     /* 
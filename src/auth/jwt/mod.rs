@@ -1,3 +1,5 @@
 pub mod jwt_service;
+pub mod oauth_token;
 
-pub use jwt_service::*;
\ No newline at end of file
+pub use jwt_service::*;
+pub use oauth_token::*;
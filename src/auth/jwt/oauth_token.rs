@@ -0,0 +1,138 @@
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, errors::ErrorKind, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::auth::JwtService;
+use crate::error::JwtError;
+
+//OAuth2 access token 유효 시간(분) / refresh token 유효 시간(일)
+pub const OAUTH_ACCESS_TOKEN_MINUTES: i64 = 15;
+pub const OAUTH_REFRESH_TOKEN_DAYS: i64 = 30;
+
+//내부 도구에 발급하는 OAuth2 토큰 클레임 / token_use로 access, refresh를 구분한다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthTokenClaims {
+    //사용자 email (client_credentials면 client_id)
+    pub sub: String,
+    pub client_id: String,
+    pub scope: String,
+    //"access" | "refresh"
+    pub token_use: String,
+    pub exp: usize,
+    pub iat: usize,
+    pub jti: String,
+}
+
+/*
+OAuth2 인가 서버용 토큰
+    -> 앱 로그인 토큰(AccessTokenClaims)과 섞이지 않도록 secret에서 파생한 별도 키로 서명한다.
+    -> refresh token은 토큰 저장소에 jti를 저장하고 사용할 때마다 rotate 한다.
+    -> 폐기(RFC 7009)된 access token의 jti는 남은 유효 시간 동안 폐기 목록에 보관한다.
+*/
+impl JwtService {
+    pub async fn create_oauth_token(
+        &self,
+        sub: &str,
+        client_id: &str,
+        scope: &str,
+        token_use: &str,
+    ) -> Result<(String, OAuthTokenClaims), JwtError> {
+        let lifetime = match token_use {
+            "refresh" => Duration::days(OAUTH_REFRESH_TOKEN_DAYS),
+            _ => Duration::minutes(OAUTH_ACCESS_TOKEN_MINUTES),
+        };
+        let claims = OAuthTokenClaims {
+            sub: sub.to_owned(),
+            client_id: client_id.to_owned(),
+            scope: scope.to_owned(),
+            token_use: token_use.to_owned(),
+            exp: (Utc::now() + lifetime).timestamp() as usize,
+            iat: Utc::now().timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
+        };
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.oauth_secret().as_ref())
+        )
+        .map_err(|e| JwtError::Other(e.to_string()))?;
+
+        if token_use == "refresh" {
+            let key = format!("oauth_refresh:{}", claims.jti);
+            self.store.set_ex(&key, &claims.client_id, lifetime.num_seconds() as u64)
+                .await
+                .map_err(|e| JwtError::StoreError(e.to_string()))?;
+        }
+
+        Ok((token, claims))
+    }
+
+    //서명, 만료, 폐기 여부까지 확인한다.
+    pub async fn verify_oauth_token(
+        &self,
+        token: &str,
+    ) -> Result<OAuthTokenClaims, JwtError> {
+        let claims = decode::<OAuthTokenClaims>(
+            token,
+            &DecodingKey::from_secret(self.oauth_secret().as_ref()),
+            &Validation::default()
+        )
+        .map_err(|e| match *e.kind() {
+            ErrorKind::ExpiredSignature => JwtError::ExpiredToken,
+            ErrorKind::InvalidSignature => JwtError::InvalidSignature,
+            ErrorKind::InvalidIssuer => JwtError::InvalidIssuer,
+            ErrorKind::InvalidToken => JwtError::InvalidToken,
+            _ => JwtError::Other(e.to_string()),
+        })?
+        .claims;
+
+        let active = match claims.token_use.as_str() {
+            "refresh" => self.store.exists(&format!("oauth_refresh:{}", claims.jti))
+                .await
+                .map_err(|e| JwtError::StoreError(e.to_string()))?,
+            //access token은 앱 access token과 같은 폐기 목록을 사용한다.
+            _ => !self.is_jti_denied(&claims.jti).await?,
+        };
+        if !active {
+            return Err(JwtError::TokenRevoked)
+        }
+
+        Ok(claims)
+    }
+
+    /*
+    refresh token 1회 사용 처리 (rotate)
+        -> 저장소의 get_del로 확인과 삭제를 한 번에 처리한다. 같은 토큰으로 동시에 요청해도 하나만 성공한다.
+        -> 키가 이미 없으면(사용/폐기됨) false
+    */
+    pub async fn consume_oauth_refresh_token(
+        &self,
+        claims: &OAuthTokenClaims,
+    ) -> Result<bool, JwtError> {
+        let client_id = self.store.get_del(&format!("oauth_refresh:{}", claims.jti))
+            .await
+            .map_err(|e| JwtError::StoreError(e.to_string()))?;
+
+        Ok(client_id.as_deref() == Some(claims.client_id.as_str()))
+    }
+
+    //토큰 폐기 / refresh는 저장소 키 삭제, access는 남은 시간만큼 폐기 목록에 등록
+    pub async fn revoke_oauth_token(
+        &self,
+        claims: &OAuthTokenClaims,
+    ) -> Result<(), JwtError> {
+        if claims.token_use == "refresh" {
+            self.store.del(&format!("oauth_refresh:{}", claims.jti))
+                .await
+                .map_err(|e| JwtError::StoreError(e.to_string()))?;
+        } else {
+            self.deny_jti(&claims.jti, claims.exp).await?;
+        }
+
+        Ok(())
+    }
+
+    fn oauth_secret(&self) -> String {
+        format!("{}:oauth", self.secret)
+    }
+}
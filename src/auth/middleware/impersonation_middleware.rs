@@ -0,0 +1,49 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    web,
+    FromRequest,
+};
+use actix_web_lab::middleware::Next;
+use sqlx::PgPool;
+use crate::audit::{AuditAction, AuditEvent};
+use crate::auth::{AccessTokenClaims, JwtService, TypedSession};
+
+/*
+대리 로그인(impersonation) 중인 요청은 모두 감사 로그(audit_events)에 남긴다.
+DB 기록은 AuditEvent::record에서 백그라운드로 처리되므로 요청 처리 시간에 영향을 주지 않는다.
+*/
+pub async fn impersonation_audit(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    if let Some((subject, actor)) = impersonation_of(&req).await {
+        if let Some(pool) = req.app_data::<web::Data<PgPool>>() {
+            AuditEvent::from_request(AuditAction::ImpersonatedRequest, req.request())
+                .actor(actor)
+                .target(subject)
+                .details(serde_json::json!({
+                    "method": req.method().as_str(),
+                    "path": req.path(),
+                }))
+                .record(pool);
+        }
+    }
+
+    next.call(req).await
+}
+
+//(대리 대상 email, 관리자 email)을 반환한다.
+async fn impersonation_of(req: &ServiceRequest) -> Option<(String, String)> {
+    //1. JWT 모드 : access token의 act 클레임 확인
+    if let Some(jwt_service) = req.app_data::<web::Data<JwtService>>() {
+        if let Some(token) = jwt_service.extract_access_token(req.request()) {
+            if let Ok(AccessTokenClaims { email, act: Some(actor), .. }) = jwt_service.verify_access_token(&token).await {
+                return Some((email, actor.sub));
+            }
+        }
+    }
+    //2. 세션 모드 : 세션에 저장된 대리 대상 확인
+    let session = TypedSession::extract(req.request()).await.ok()?;
+    session.get_impersonation().ok().flatten()
+}
@@ -19,10 +19,10 @@ pub async fn jwt_auth_middleware(
     //1. HttpRequest 추출
     let http_req = req.request();
     //2. 토큰 추출
-    let token = jwt_service.extract_access_token(&http_req).ok_or_else(|| ErrorUnauthorized("Missing or invalid Authoriztion header"))?;
+    let token = jwt_service.extract_access_token(http_req).ok_or_else(|| ErrorUnauthorized("Missing or invalid Authoriztion header"))?;
 
     //3. 토큰 검증
-    let claims = jwt_service.verify_access_token(&token)
+    let claims = jwt_service.verify_access_token(&token).await
         .map_err(|e| ErrorUnauthorized(e.to_string()))?;
 
     //4. 검증된 Claims를 request extensions에 저장
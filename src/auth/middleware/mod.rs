@@ -1,5 +1,7 @@
+pub mod impersonation_middleware;
 pub mod jwt_middleware;
 pub mod session_middleware;
 
+pub use impersonation_middleware::*;
 pub use jwt_middleware::*;
-pub use session_middleware::*;
\ No newline at end of file
+pub use session_middleware::*;
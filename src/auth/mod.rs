@@ -1,7 +1,19 @@
+pub mod challenge;
+pub mod cookie;
 pub mod jwt;
 pub mod middleware;
+pub mod oidc;
+pub mod passkey;
+pub mod reauth;
 pub mod session;
+pub mod store;
 
+pub use challenge::*;
+pub use cookie::*;
 pub use jwt::*;
 pub use middleware::*;
-pub use session::*;
\ No newline at end of file
+pub use oidc::*;
+pub use passkey::*;
+pub use reauth::*;
+pub use session::*;
+pub use store::*;
\ No newline at end of file
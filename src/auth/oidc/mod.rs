@@ -0,0 +1,3 @@
+pub mod oidc_client;
+
+pub use oidc_client::*;
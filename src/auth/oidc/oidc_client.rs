@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use anyhow::{anyhow, Context};
+use jsonwebtoken::{decode, decode_header, jwk::{Jwk, JwkSet}, Algorithm, DecodingKey, Validation};
+use rand::RngCore;
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use crate::configuration::{OidcProviderSettings, OidcSettings};
+
+//토큰 엔드포인트 응답 (필요한 값만)
+#[derive(Debug, Deserialize)]
+pub struct OidcTokenResponse {
+    pub id_token: String,
+    pub access_token: Option<String>,
+}
+
+//ID 토큰 클레임 / iss, aud, exp는 Validation에서 검증한다.
+#[derive(Debug, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub nonce: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+    pub name: Option<String>,
+}
+
+//ID 토큰 서명에 허용하는 알고리즘 / 토큰 헤더의 alg를 그대로 믿지 않는다. (HS256 등 대칭키 알고리즘 혼동 공격 방지)
+const ALLOWED_ID_TOKEN_ALGORITHMS: [Algorithm; 2] = [Algorithm::RS256, Algorithm::ES256];
+//JWKS 캐시 유지 시간 / 지나거나 모르는 kid가 오면(키 교체) 다시 가져온다.
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+struct CachedJwks {
+    jwks: JwkSet,
+    fetched_at: Instant,
+}
+
+/*
+Authorization Code + PKCE 방식의 OIDC 클라이언트
+    -> 공급자별 엔드포인트는 모두 configuration에서 읽는다.
+    -> state, nonce, code_verifier는 호출하는 쪽(핸들러)이 Redis 세션에 보관한다.
+*/
+#[derive(Clone)]
+pub struct OidcClient {
+    http_client: reqwest::Client,
+    providers: HashMap<String, OidcProviderSettings>,
+    //jwks_uri별 공개키 캐시 / 로그인마다 공급자에 JWKS를 요청하지 않는다.
+    jwks_cache: Arc<RwLock<HashMap<String, CachedJwks>>>,
+}
+
+impl OidcClient {
+    pub fn new(settings: &OidcSettings) -> Self {
+        let providers = settings.providers.iter()
+            .map(|p| (p.name.clone(), p.clone()))
+            .collect();
+        let http_client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .expect("Failed to build OIDC http client");
+
+        Self { http_client, providers, jwks_cache: Arc::new(RwLock::new(HashMap::new())) }
+    }
+
+    pub fn provider(&self, name: &str) -> Option<&OidcProviderSettings> {
+        self.providers.get(name)
+    }
+
+    //로그인 화면의 소셜 로그인 버튼용 (name, display_name) 목록
+    pub fn provider_links(&self) -> Vec<(String, String)> {
+        let mut links: Vec<_> = self.providers.values()
+            .map(|p| (p.name.clone(), p.display_name.clone()))
+            .collect();
+        links.sort();
+        links
+    }
+
+    //공급자의 authorization endpoint로 보낼 URL 생성
+    pub fn authorization_url(
+        &self,
+        provider: &OidcProviderSettings,
+        redirect_uri: &str,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String, anyhow::Error> {
+        let scope = provider.scopes.join(" ");
+        let url = reqwest::Url::parse_with_params(
+            &provider.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", provider.client_id.as_str()),
+                ("redirect_uri", redirect_uri),
+                ("scope", scope.as_str()),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .context("Invalid authorization endpoint")?;
+
+        Ok(url.to_string())
+    }
+
+    //authorization code를 토큰으로 교환
+    #[tracing::instrument(name = "Exchange OIDC authorization code", skip(self, provider, code, code_verifier), fields(provider = %provider.name))]
+    pub async fn exchange_code(
+        &self,
+        provider: &OidcProviderSettings,
+        code: &str,
+        redirect_uri: &str,
+        code_verifier: &str,
+    ) -> Result<OidcTokenResponse, anyhow::Error> {
+        let response = self.http_client
+            .post(&provider.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+                ("client_id", provider.client_id.as_str()),
+                ("client_secret", provider.client_secret.expose_secret().as_str()),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .context("Failed to call token endpoint")?
+            .error_for_status()
+            .context("Token endpoint returned an error")?;
+
+        response.json::<OidcTokenResponse>().await.context("Invalid token response")
+    }
+
+    /*
+    ID 토큰 검증
+        -> 헤더의 alg가 허용 목록(RS256, ES256)에 없으면 바로 거부하고, 검증도 그 알고리즘 하나로만 한다.
+        -> 공급자의 JWKS(캐시)에서 kid에 맞는 키를 찾아 서명을 검증한다. (알고리즘 계열이 키와 다르면 jsonwebtoken이 거부한다.)
+        -> iss, aud(client_id), exp 검증 후 세션에 저장해둔 nonce와 비교한다.
+    */
+    #[tracing::instrument(name = "Validate OIDC id token", skip(self, provider, id_token, expected_nonce), fields(provider = %provider.name))]
+    pub async fn validate_id_token(
+        &self,
+        provider: &OidcProviderSettings,
+        id_token: &str,
+        expected_nonce: &str,
+    ) -> Result<IdTokenClaims, anyhow::Error> {
+        let header = decode_header(id_token).context("Invalid id token header")?;
+        if !ALLOWED_ID_TOKEN_ALGORITHMS.contains(&header.alg) {
+            return Err(anyhow!("Id token algorithm {:?} is not allowed", header.alg));
+        }
+
+        let jwk = match self.cached_jwk(&provider.jwks_uri, header.kid.as_deref()) {
+            Some(jwk) => jwk,
+            None => {
+                self.fetch_jwks(&provider.jwks_uri).await?;
+                self.cached_jwk(&provider.jwks_uri, header.kid.as_deref())
+                    .ok_or_else(|| anyhow!("No matching key in JWKS"))?
+            }
+        };
+        let key = DecodingKey::from_jwk(&jwk).context("Unsupported JWK")?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&provider.issuer]);
+        validation.set_audience(&[&provider.client_id]);
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .context("Invalid id token")?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(expected_nonce) {
+            return Err(anyhow!("Nonce mismatch"));
+        }
+
+        Ok(claims)
+    }
+
+    //캐시가 유효하면 kid에 맞는 키를 돌려준다. (kid가 없으면 키가 하나일 때만)
+    fn cached_jwk(&self, jwks_uri: &str, kid: Option<&str>) -> Option<Jwk> {
+        let cache = self.jwks_cache.read().ok()?;
+        let cached = cache.get(jwks_uri).filter(|c| c.fetched_at.elapsed() < JWKS_CACHE_TTL)?;
+        match kid {
+            Some(kid) => cached.jwks.find(kid),
+            None if cached.jwks.keys.len() == 1 => cached.jwks.keys.first(),
+            None => None,
+        }
+        .cloned()
+    }
+
+    #[tracing::instrument(name = "Fetch OIDC JWKS", skip(self))]
+    async fn fetch_jwks(&self, jwks_uri: &str) -> Result<(), anyhow::Error> {
+        let jwks = self.http_client
+            .get(jwks_uri)
+            .send()
+            .await
+            .context("Failed to fetch JWKS")?
+            .error_for_status()
+            .context("JWKS endpoint returned an error")?
+            .json::<JwkSet>()
+            .await
+            .context("Invalid JWKS")?;
+
+        self.jwks_cache
+            .write()
+            .map_err(|_| anyhow!("JWKS cache lock poisoned"))?
+            .insert(jwks_uri.to_owned(), CachedJwks { jwks, fetched_at: Instant::now() });
+
+        Ok(())
+    }
+}
+
+//URL에 그대로 쓸 수 있는 무작위 문자열 (state, nonce, code_verifier 용)
+pub fn random_url_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+//PKCE S256 : BASE64URL(SHA256(code_verifier))
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    let digest = Sha256::digest(code_verifier.as_bytes());
+    base64::encode_config(digest, base64::URL_SAFE_NO_PAD)
+}
@@ -0,0 +1,3 @@
+pub mod passkey_service;
+
+pub use passkey_service::*;
@@ -0,0 +1,19 @@
+use anyhow::Context;
+use webauthn_rs::{prelude::Url, Webauthn, WebauthnBuilder};
+use crate::configuration::WebauthnSettings;
+
+/*
+WebAuthn Relying Party 생성
+    -> rp_id는 도메인(ex. localhost), rp_origin은 scheme + host + port까지 포함한 origin이다.
+    -> Webauthn 자체는 상태가 없으므로 web::Data로 공유하고, 진행 중인 ceremony 상태는 세션(Redis)에 저장한다.
+*/
+pub fn build_webauthn(settings: &WebauthnSettings) -> Result<Webauthn, anyhow::Error> {
+    let rp_origin = Url::parse(&settings.rp_origin).context("Invalid webauthn rp_origin")?;
+    let webauthn = WebauthnBuilder::new(&settings.rp_id, &rp_origin)
+        .context("Invalid webauthn configuration")?
+        .rp_name(&settings.rp_name)
+        .build()
+        .context("Failed to build webauthn")?;
+
+    Ok(webauthn)
+}
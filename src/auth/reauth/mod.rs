@@ -0,0 +1,3 @@
+pub mod recent_auth;
+
+pub use recent_auth::*;
@@ -0,0 +1,48 @@
+use actix_web::HttpRequest;
+use chrono::{Duration, Utc};
+use crate::auth::{JwtService, TypedSession};
+use crate::error::{e401, e500, ApiError, ReauthRequired};
+
+/*
+최근 인증 확인(step-up)
+    -> 세션 모드는 세션의 auth_time, JWT 모드는 access token의 auth_time 클레임을 본다.
+    -> 로그인하지 않았으면 401 Unauthorized, 인증한 지 Duration보다 오래됐으면 401 reauth_required를 반환한다.
+    -> 대리 로그인 중에는 관리자 본인이 아니므로 사용할 수 없다.
+    ex) RequireRecentAuth(jwt_service.policy.reauth_window()).check(&req, &session, &jwt_service).await?
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct RequireRecentAuth(pub Duration);
+
+impl RequireRecentAuth {
+    //확인에 성공하면 로그인한 사용자 email을 반환한다.
+    pub async fn check(
+        &self,
+        req: &HttpRequest,
+        session: &TypedSession,
+        jwt_service: &JwtService,
+    ) -> Result<String, actix_web::Error> {
+        let (email, auth_time) = match session.get_email().map_err(e500)? {
+            Some(email) => {
+                if session.get_impersonation().map_err(e500)?.is_some() {
+                    return Err(e401(ApiError::Unauthorized("Impersonation session cannot re-authenticate".into())));
+                }
+                (email, session.get_auth_time().map_err(e500)?.unwrap_or(0))
+            }
+            None => {
+                let token = jwt_service.extract_access_token(req)
+                    .ok_or_else(|| e401(ApiError::Unauthorized("Login required".into())))?;
+                let claims = jwt_service.verify_access_token(&token).await
+                    .map_err(|e| e401(ApiError::Unauthorized(e.to_string())))?;
+                if claims.act.is_some() {
+                    return Err(e401(ApiError::Unauthorized("Impersonation token cannot re-authenticate".into())));
+                }
+                (claims.email, claims.auth_time as i64)
+            }
+        };
+
+        if Utc::now().timestamp() - auth_time > self.0.num_seconds() {
+            return Err(ReauthRequired { max_age_seconds: self.0.num_seconds() }.into());
+        }
+        Ok(email)
+    }
+}
@@ -1,38 +1,172 @@
 use actix_session::{Session, SessionExt, SessionGetError, SessionInsertError};
 use actix_web::dev::Payload;
-use actix_web::{FromRequest, HttpRequest};
+use actix_web::{web, FromRequest, HttpRequest};
+use serde::{de::DeserializeOwned, Serialize};
 use std::future::{Ready, ready};
+use crate::configuration::AuthPolicy;
 
-pub struct TypedSession(Session);
+//AuthPolicy가 app_data로 등록되어 있으면 get_email에서 idle / absolute timeout을 확인한다.
+pub struct TypedSession(Session, Option<AuthPolicy>);
 
 impl TypedSession{
     const EMAIL_KEY: &'static str = "email";
+    const LOGIN_EXPIRY_KEY: &'static str = "login_expiry";
+    const AUTH_TIME_KEY: &'static str = "auth_time";
+    const IMPERSONATED_EMAIL_KEY: &'static str = "impersonated_email";
+    const IMPERSONATION_EXPIRES_KEY: &'static str = "impersonation_expires_at";
+    const OIDC_FLOW_KEY: &'static str = "oidc_flow";
+    const OAUTH_CONSENT_KEY: &'static str = "oauth_consent";
+    const WEBAUTHN_STATE_KEY: &'static str = "webauthn_state";
 
     //----------------------------------session 정보 저장 시 필요한 메서드들------------------------------------
     pub fn renew(&self) {
         self.0.renew()
     }
 
-    pub fn insert_email(&self, email: String) -> Result<(), SessionInsertError> {
-        self.0.insert(Self::EMAIL_KEY, email)
+    //로그인 처리 / remember : "로그인 상태 유지" 선택 여부
+    pub fn insert_email(&self, email: String, remember: bool) -> Result<(), SessionInsertError> {
+        self.0.insert(Self::EMAIL_KEY, email)?;
+        self.mark_authenticated()?;
+        match self.1 {
+            Some(policy) => {
+                let login_at = chrono::Utc::now().timestamp();
+                self.0.insert(Self::LOGIN_EXPIRY_KEY, LoginExpiry {
+                    expires_at: policy.initial_expiry(login_at, remember),
+                    deadline: policy.login_deadline(login_at, remember),
+                    remember,
+                })
+            }
+            None => Ok(()),
+        }
     }
 
+    /*
+    로그인한 email 조회
+        -> idle / absolute timeout이 지났으면 세션을 비우고 None을 반환한다.
+        -> sliding renewal이면 조회할 때마다 만료 시각을 연장한다. (세션 상태가 바뀌므로 Redis TTL도 같이 연장된다.)
+    */
     pub fn get_email(&self) -> Result<Option<String>, SessionGetError> {
-        self.0.get(Self::EMAIL_KEY)
+        let Some(email) = self.0.get::<String>(Self::EMAIL_KEY)? else {
+            return Ok(None);
+        };
+        let Some(policy) = self.1 else {
+            return Ok(Some(email));
+        };
+        let Some(expiry) = self.0.get::<LoginExpiry>(Self::LOGIN_EXPIRY_KEY)? else {
+            return Ok(Some(email));
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        match policy.renewed_expiry(now, expiry.expires_at, expiry.deadline, expiry.remember) {
+            Some(expires_at) => {
+                if expires_at != expiry.expires_at {
+                    //갱신 실패는 다음 요청에서 다시 시도하면 되므로 무시한다.
+                    let _ = self.0.insert(Self::LOGIN_EXPIRY_KEY, LoginExpiry { expires_at, ..expiry });
+                }
+                Ok(Some(email))
+            }
+            None => {
+                self.0.purge();
+                Ok(None)
+            }
+        }
     }
 
     pub fn delete_email(self) {
         self.0.purge()
     }
+
+    //마지막으로 자격 증명을 확인한 시각(unix timestamp)을 지금으로 기록한다. (로그인, 재인증)
+    pub fn mark_authenticated(&self) -> Result<(), SessionInsertError> {
+        self.0.insert(Self::AUTH_TIME_KEY, chrono::Utc::now().timestamp())
+    }
+
+    pub fn get_auth_time(&self) -> Result<Option<i64>, SessionGetError> {
+        self.0.get(Self::AUTH_TIME_KEY)
+    }
+    //----------------------------------대리 로그인(impersonation) 관련 메서드들------------------------------------
+    //관리자 본인의 email은 그대로 두고, 대리 대상 email과 만료 시각(unix timestamp)만 추가로 저장한다.
+    pub fn start_impersonation(&self, email: String, expires_at: i64) -> Result<(), SessionInsertError> {
+        self.0.insert(Self::IMPERSONATED_EMAIL_KEY, email)?;
+        self.0.insert(Self::IMPERSONATION_EXPIRES_KEY, expires_at)
+    }
+
+    //(대리 대상 email, 관리자 email)을 반환한다. 만료된 경우 세션에서 정리하고 None을 반환한다.
+    pub fn get_impersonation(&self) -> Result<Option<(String, String)>, SessionGetError> {
+        let subject: Option<String> = self.0.get(Self::IMPERSONATED_EMAIL_KEY)?;
+        let expires_at: Option<i64> = self.0.get(Self::IMPERSONATION_EXPIRES_KEY)?;
+        let actor = self.get_email()?;
+
+        match (subject, expires_at, actor) {
+            (Some(subject), Some(expires_at), Some(actor)) if expires_at > chrono::Utc::now().timestamp() => {
+                Ok(Some((subject, actor)))
+            }
+            (Some(_), _, _) => {
+                self.stop_impersonation();
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn stop_impersonation(&self) {
+        self.0.remove(Self::IMPERSONATED_EMAIL_KEY);
+        self.0.remove(Self::IMPERSONATION_EXPIRES_KEY);
+    }
+    //----------------------------------소셜 로그인(OIDC) 관련 메서드들------------------------------------
+    //state, nonce, code_verifier 등 콜백에서 검증할 값을 보관한다.
+    pub fn insert_oidc_flow<T: Serialize>(&self, flow: &T) -> Result<(), SessionInsertError> {
+        self.0.insert(Self::OIDC_FLOW_KEY, flow)
+    }
+
+    //콜백에서 한 번만 꺼내 쓸 수 있도록 조회와 동시에 삭제한다.
+    pub fn take_oidc_flow<T: DeserializeOwned>(&self) -> Result<Option<T>, SessionGetError> {
+        let flow = self.0.get(Self::OIDC_FLOW_KEY)?;
+        self.0.remove(Self::OIDC_FLOW_KEY);
+        Ok(flow)
+    }
+    //----------------------------------OAuth2 동의 화면 관련 메서드들------------------------------------
+    //동의 화면을 그릴 때 발급한 1회용 값 / POST에서 비교해서 다른 사이트가 동의를 위조하지 못하게 한다.
+    pub fn insert_consent_token(&self, token: String) -> Result<(), SessionInsertError> {
+        self.0.insert(Self::OAUTH_CONSENT_KEY, token)
+    }
+
+    pub fn take_consent_token(&self) -> Result<Option<String>, SessionGetError> {
+        let token = self.0.get(Self::OAUTH_CONSENT_KEY)?;
+        self.0.remove(Self::OAUTH_CONSENT_KEY);
+        Ok(token)
+    }
+    //----------------------------------패스키(WebAuthn) 관련 메서드들------------------------------------
+    //등록/인증 ceremony의 challenge 상태 / finish에서 한 번만 꺼내 쓴다.
+    pub fn insert_webauthn_state<T: Serialize>(&self, state: &T) -> Result<(), SessionInsertError> {
+        self.0.insert(Self::WEBAUTHN_STATE_KEY, state)
+    }
+
+    pub fn take_webauthn_state<T: DeserializeOwned>(&self) -> Result<Option<T>, SessionGetError> {
+        let state = self.0.get(Self::WEBAUTHN_STATE_KEY)?;
+        self.0.remove(Self::WEBAUTHN_STATE_KEY);
+        Ok(state)
+    }
     //----------------------------------refresh token 관련 메서드들------------------------------------
 
 }
 
+//세션 로그인의 만료 정보 (unix timestamp)
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+struct LoginExpiry {
+    //idle timeout 기준 만료 시각
+    expires_at: i64,
+    //absolute timeout 기준 만료 시각
+    deadline: i64,
+    remember: bool,
+}
+
 impl FromRequest for TypedSession {
     type Error = <Session as FromRequest>::Error;
     type Future = Ready<Result<TypedSession, Self::Error>>;
 
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
-        ready(Ok(TypedSession(req.get_session())))
+        let policy = req.app_data::<web::Data<AuthPolicy>>().map(|policy| *policy.get_ref());
+        ready(Ok(TypedSession(req.get_session(), policy)))
     }
 }
\ No newline at end of file
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::auth::TokenStore;
+
+//key -> (값, 만료 시각)
+type TokenEntries = HashMap<String, (String, Instant)>;
+
+/*
+메모리 토큰 저장소
+    -> 프로세스 안에만 저장하므로 서버를 재시작하면 사라지고, 여러 인스턴스가 공유할 수 없다.
+    -> Redis 없이 로컬 실행하거나 테스트할 때 사용한다.
+*/
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    entries: Mutex<TokenEntries>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, TokenEntries>, anyhow::Error> {
+        let mut entries = self.entries.lock().map_err(|_| anyhow::anyhow!("Token store lock poisoned"))?;
+        //만료된 key 정리
+        let now = Instant::now();
+        entries.retain(|_, (_, expires_at)| *expires_at > now);
+        Ok(entries)
+    }
+}
+
+#[async_trait]
+impl TokenStore for MemoryTokenStore {
+    async fn set_ex(&self, key: &str, value: &str, ttl: u64) -> Result<(), anyhow::Error> {
+        let expires_at = Instant::now() + Duration::from_secs(ttl);
+        self.lock()?.insert(key.to_owned(), (value.to_owned(), expires_at));
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+        Ok(self.lock()?.get(key).map(|(value, _)| value.clone()))
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, anyhow::Error> {
+        Ok(self.lock()?.contains_key(key))
+    }
+
+    async fn del(&self, key: &str) -> Result<bool, anyhow::Error> {
+        Ok(self.lock()?.remove(key).is_some())
+    }
+
+    async fn get_del(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+        Ok(self.lock()?.remove(key).map(|(value, _)| value))
+    }
+
+    async fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>, anyhow::Error> {
+        Ok(self.lock()?.keys().filter(|key| key.starts_with(prefix)).cloned().collect())
+    }
+
+    async fn incr(&self, key: &str, ttl: u64) -> Result<i64, anyhow::Error> {
+        let mut entries = self.lock()?;
+        let expires_at = Instant::now() + Duration::from_secs(ttl);
+        let entry = entries.entry(key.to_owned()).or_insert_with(|| ("0".to_string(), expires_at));
+        let count = entry.0.parse::<i64>()? + 1;
+        entry.0 = count.to_string();
+        Ok(count)
+    }
+}
@@ -0,0 +1,11 @@
+pub mod memory_store;
+pub mod postgres_store;
+pub mod redis_store;
+pub mod session_store;
+pub mod token_store;
+
+pub use memory_store::*;
+pub use postgres_store::*;
+pub use redis_store::*;
+pub use session_store::*;
+pub use token_store::*;
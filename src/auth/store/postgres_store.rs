@@ -0,0 +1,129 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use crate::auth::TokenStore;
+
+/*
+Postgres 토큰 저장소
+    -> token_store 테이블에 expires_at과 함께 저장하고, 조회할 때 만료된 행은 없는 것으로 취급한다.
+    -> 만료된 행은 purge_expired로 주기적으로 지운다. (startup에서 백그라운드 작업으로 실행)
+*/
+#[derive(Debug, Clone)]
+pub struct PostgresTokenStore {
+    pool: PgPool,
+}
+
+impl PostgresTokenStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    //만료된 토큰과 세션을 지운다.
+    pub async fn purge_expired(&self) -> Result<(), anyhow::Error> {
+        sqlx::query!("DELETE FROM token_store WHERE expires_at <= now()")
+            .execute(&self.pool)
+            .await
+            .context("Failed to purge expired tokens")?;
+        sqlx::query!("DELETE FROM sessions WHERE expires_at <= now()")
+            .execute(&self.pool)
+            .await
+            .context("Failed to purge expired sessions")?;
+        Ok(())
+    }
+}
+
+fn expires_at(ttl: u64) -> chrono::DateTime<Utc> {
+    Utc::now() + Duration::seconds(ttl as i64)
+}
+
+#[async_trait]
+impl TokenStore for PostgresTokenStore {
+    async fn set_ex(&self, key: &str, value: &str, ttl: u64) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO token_store (key, value, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at
+            "#,
+            key,
+            value,
+            expires_at(ttl)
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to store token")?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+        let value = sqlx::query_scalar!(
+            "SELECT value FROM token_store WHERE key = $1 AND expires_at > now()",
+            key
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to perform a query")?;
+        Ok(value)
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, anyhow::Error> {
+        Ok(self.get(key).await?.is_some())
+    }
+
+    async fn del(&self, key: &str) -> Result<bool, anyhow::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM token_store WHERE key = $1 AND expires_at > now()",
+            key
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to delete token")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_del(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+        let value = sqlx::query_scalar!(
+            "DELETE FROM token_store WHERE key = $1 AND expires_at > now() RETURNING value",
+            key
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to delete token")?;
+        Ok(value)
+    }
+
+    async fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>, anyhow::Error> {
+        //LIKE는 email의 '_'를 와일드카드로 해석하므로 starts_with를 사용한다.
+        let keys = sqlx::query_scalar!(
+            "SELECT key FROM token_store WHERE starts_with(key, $1) AND expires_at > now()",
+            prefix
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to perform a query")?;
+        Ok(keys)
+    }
+
+    async fn incr(&self, key: &str, ttl: u64) -> Result<i64, anyhow::Error> {
+        //만료된 행이 남아있으면 새로 만든 것처럼 1부터 다시 센다.
+        let count = sqlx::query_scalar!(
+            r#"
+            INSERT INTO token_store (key, value, expires_at)
+            VALUES ($1, '1', $2)
+            ON CONFLICT (key) DO UPDATE SET
+                value = CASE WHEN token_store.expires_at <= now() THEN '1'
+                             ELSE (token_store.value::bigint + 1)::text END,
+                expires_at = CASE WHEN token_store.expires_at <= now() THEN EXCLUDED.expires_at
+                                  ELSE token_store.expires_at END
+            RETURNING value
+            "#,
+            key,
+            expires_at(ttl)
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to increment counter")?;
+        Ok(count.parse()?)
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, AsyncCommands, Client};
+use crate::auth::TokenStore;
+
+/*
+Redis 토큰 저장소 / key의 TTL은 Redis의 만료 기능을 그대로 사용한다.
+    -> ConnectionManager는 하나의 멀티플렉스 연결을 공유하고 끊어지면 다시 연결한다. (요청마다 연결을 만들지 않는다)
+    -> 비동기 명령만 사용하므로 actix 워커 스레드를 막지 않는다.
+*/
+#[derive(Clone)]
+pub struct RedisTokenStore {
+    manager: ConnectionManager,
+}
+
+impl std::fmt::Debug for RedisTokenStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisTokenStore").finish_non_exhaustive()
+    }
+}
+
+impl RedisTokenStore {
+    pub async fn new(redis_uri: &str) -> Result<Self, anyhow::Error> {
+        let client = Client::open(redis_uri).context("Failed to create Redis client")?;
+        let manager = ConnectionManager::new(client).await.context("Failed to connect to Redis")?;
+        Ok(Self { manager })
+    }
+
+    //ConnectionManager는 복제해도 같은 연결을 공유한다.
+    fn connection(&self) -> ConnectionManager {
+        self.manager.clone()
+    }
+}
+
+//SCAN MATCH 패턴에서 특수 문자로 쓰이는 문자를 이스케이프한다.
+fn escape_pattern(value: &str) -> String {
+    value.chars().fold(String::with_capacity(value.len()), |mut escaped, c| {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+#[async_trait]
+impl TokenStore for RedisTokenStore {
+    async fn set_ex(&self, key: &str, value: &str, ttl: u64) -> Result<(), anyhow::Error> {
+        self.connection().set_ex::<_, _, ()>(key, value, ttl.max(1) as usize).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+        Ok(self.connection().get(key).await?)
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, anyhow::Error> {
+        Ok(self.connection().exists(key).await?)
+    }
+
+    async fn del(&self, key: &str) -> Result<bool, anyhow::Error> {
+        let deleted: usize = self.connection().del(key).await?;
+        Ok(deleted > 0)
+    }
+
+    async fn get_del(&self, key: &str) -> Result<Option<String>, anyhow::Error> {
+        Ok(self.connection().get_del(key).await?)
+    }
+
+    async fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>, anyhow::Error> {
+        let mut con = self.connection();
+        let mut iter = con.scan_match::<_, String>(format!("{}*", escape_pattern(prefix))).await?;
+        let mut keys = Vec::new();
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        Ok(keys)
+    }
+
+    async fn incr(&self, key: &str, ttl: u64) -> Result<i64, anyhow::Error> {
+        let mut con = self.connection();
+        let count: i64 = con.incr(key, 1).await?;
+        if count == 1 {
+            con.expire::<_, ()>(key, ttl.max(1) as usize).await?;
+        }
+        Ok(count)
+    }
+}
@@ -0,0 +1,188 @@
+use actix_session::storage::{LoadError, RedisSessionStore, SaveError, SessionKey, SessionStore, UpdateError};
+use actix_web::cookie::time::Duration;
+use anyhow::{anyhow, Context};
+use chrono::Utc;
+use rand::distributions::{Alphanumeric, DistString};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+type SessionState = HashMap<String, String>;
+//세션 key -> (세션 상태, 만료 시각)
+type SessionEntries = HashMap<String, (SessionState, Instant)>;
+type MemorySessions = Arc<Mutex<SessionEntries>>;
+
+/*
+세션 저장소
+    -> SessionMiddleware는 저장소 타입을 제네릭으로 받으므로, 설정(store.backend)에 따라 고른 구현을 enum 하나로 감싼다.
+    -> Redis는 actix-session의 RedisSessionStore를 그대로 사용하고, Postgres는 sessions 테이블, 메모리는 프로세스 안의 HashMap에 저장한다.
+*/
+#[derive(Clone)]
+pub enum SessionBackend {
+    Redis(RedisSessionStore),
+    Postgres(PgPool),
+    Memory(MemorySessions),
+}
+
+impl SessionBackend {
+    pub fn memory() -> Self {
+        Self::Memory(Arc::new(Mutex::new(HashMap::new())))
+    }
+}
+
+//OWASP 권장 길이의 무작위 세션 key (actix-session과 같은 방식)
+fn generate_session_key() -> Result<SessionKey, anyhow::Error> {
+    Alphanumeric
+        .sample_string(&mut rand::thread_rng(), 64)
+        .try_into()
+        .map_err(|e| anyhow!("{}", e))
+}
+
+fn expires_at(ttl: &Duration) -> chrono::DateTime<Utc> {
+    Utc::now() + chrono::Duration::seconds(ttl.whole_seconds())
+}
+
+fn instant_after(ttl: &Duration) -> Instant {
+    Instant::now() + std::time::Duration::from_secs(ttl.whole_seconds().max(0) as u64)
+}
+
+fn lock(sessions: &MemorySessions) -> Result<std::sync::MutexGuard<'_, SessionEntries>, anyhow::Error> {
+    let mut sessions = sessions.lock().map_err(|_| anyhow!("Session store lock poisoned"))?;
+    let now = Instant::now();
+    sessions.retain(|_, (_, expires_at)| *expires_at > now);
+    Ok(sessions)
+}
+
+impl SessionStore for SessionBackend {
+    async fn load(&self, session_key: &SessionKey) -> Result<Option<SessionState>, LoadError> {
+        match self {
+            Self::Redis(store) => store.load(session_key).await,
+            Self::Postgres(pool) => {
+                let state = sqlx::query_scalar!(
+                    "SELECT state FROM sessions WHERE session_key = $1 AND expires_at > now()",
+                    session_key.as_ref()
+                )
+                .fetch_optional(pool)
+                .await
+                .context("Failed to load session")
+                .map_err(LoadError::Other)?;
+                state
+                    .map(serde_json::from_value)
+                    .transpose()
+                    .map_err(|e| LoadError::Deserialization(e.into()))
+            }
+            Self::Memory(sessions) => {
+                let sessions = lock(sessions).map_err(LoadError::Other)?;
+                Ok(sessions.get(session_key.as_ref()).map(|(state, _)| state.clone()))
+            }
+        }
+    }
+
+    async fn save(&self, session_state: SessionState, ttl: &Duration) -> Result<SessionKey, SaveError> {
+        match self {
+            Self::Redis(store) => store.save(session_state, ttl).await,
+            Self::Postgres(pool) => {
+                let session_key = generate_session_key().map_err(SaveError::Other)?;
+                let state = serde_json::to_value(&session_state).map_err(|e| SaveError::Serialization(e.into()))?;
+                sqlx::query!(
+                    "INSERT INTO sessions (session_key, state, expires_at) VALUES ($1, $2, $3)",
+                    session_key.as_ref(),
+                    state,
+                    expires_at(ttl)
+                )
+                .execute(pool)
+                .await
+                .context("Failed to save session")
+                .map_err(SaveError::Other)?;
+                Ok(session_key)
+            }
+            Self::Memory(sessions) => {
+                let session_key = generate_session_key().map_err(SaveError::Other)?;
+                lock(sessions)
+                    .map_err(SaveError::Other)?
+                    .insert(session_key.as_ref().to_owned(), (session_state, instant_after(ttl)));
+                Ok(session_key)
+            }
+        }
+    }
+
+    //기존 세션이 그 사이에 만료되었으면 RedisSessionStore와 같이 새 key로 저장한다.
+    async fn update(&self, session_key: SessionKey, session_state: SessionState, ttl: &Duration) -> Result<SessionKey, UpdateError> {
+        let updated = match self {
+            Self::Redis(store) => return store.update(session_key, session_state, ttl).await,
+            Self::Postgres(pool) => {
+                let state = serde_json::to_value(&session_state).map_err(|e| UpdateError::Serialization(e.into()))?;
+                sqlx::query!(
+                    "UPDATE sessions SET state = $2, expires_at = $3 WHERE session_key = $1 AND expires_at > now()",
+                    session_key.as_ref(),
+                    state,
+                    expires_at(ttl)
+                )
+                .execute(pool)
+                .await
+                .context("Failed to update session")
+                .map_err(UpdateError::Other)?
+                .rows_affected() > 0
+            }
+            Self::Memory(sessions) => {
+                let mut sessions = lock(sessions).map_err(UpdateError::Other)?;
+                match sessions.get_mut(session_key.as_ref()) {
+                    Some(entry) => {
+                        *entry = (session_state.clone(), instant_after(ttl));
+                        true
+                    }
+                    None => false,
+                }
+            }
+        };
+
+        if updated {
+            return Ok(session_key);
+        }
+        self.save(session_state, ttl).await.map_err(|e| match e {
+            SaveError::Serialization(e) => UpdateError::Serialization(e),
+            SaveError::Other(e) => UpdateError::Other(e),
+        })
+    }
+
+    async fn update_ttl(&self, session_key: &SessionKey, ttl: &Duration) -> Result<(), anyhow::Error> {
+        match self {
+            Self::Redis(store) => store.update_ttl(session_key, ttl).await,
+            Self::Postgres(pool) => {
+                sqlx::query!(
+                    "UPDATE sessions SET expires_at = $2 WHERE session_key = $1",
+                    session_key.as_ref(),
+                    expires_at(ttl)
+                )
+                .execute(pool)
+                .await
+                .context("Failed to update session ttl")?;
+                Ok(())
+            }
+            Self::Memory(sessions) => {
+                if let Some(entry) = lock(sessions)?.get_mut(session_key.as_ref()) {
+                    entry.1 = instant_after(ttl);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn delete(&self, session_key: &SessionKey) -> Result<(), anyhow::Error> {
+        match self {
+            Self::Redis(store) => store.delete(session_key).await,
+            Self::Postgres(pool) => {
+                sqlx::query!("DELETE FROM sessions WHERE session_key = $1", session_key.as_ref())
+                    .execute(pool)
+                    .await
+                    .context("Failed to delete session")?;
+                Ok(())
+            }
+            Self::Memory(sessions) => {
+                lock(sessions)?.remove(session_key.as_ref());
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+
+/*
+토큰 저장소
+    -> refresh token key, access token 폐기 목록, 1회용 토큰(매직 링크 등), 카운터를 저장하는 TTL 기반 key-value 저장소
+    -> 설정(store.backend)에 따라 Redis, Postgres, 메모리 구현 중 하나를 사용한다.
+    -> ttl은 초 단위이며, 만료된 key는 없는 것으로 취급한다.
+*/
+#[async_trait]
+pub trait TokenStore: Send + Sync + std::fmt::Debug {
+    async fn set_ex(&self, key: &str, value: &str, ttl: u64) -> Result<(), anyhow::Error>;
+
+    async fn get(&self, key: &str) -> Result<Option<String>, anyhow::Error>;
+
+    async fn exists(&self, key: &str) -> Result<bool, anyhow::Error>;
+
+    //삭제했으면 true / 1회용 토큰 소비 확인에 사용한다.
+    async fn del(&self, key: &str) -> Result<bool, anyhow::Error>;
+
+    //값을 읽으면서 삭제한다. 동시에 호출해도 값은 한 곳에만 반환된다. (refresh token rotate)
+    async fn get_del(&self, key: &str) -> Result<Option<String>, anyhow::Error>;
+
+    //prefix로 시작하는 key 목록 (ex. 사용자의 모든 refresh token)
+    async fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>, anyhow::Error>;
+
+    //카운터를 1 증가시키고 증가된 값을 반환한다. 새로 만들어지는 경우에만 ttl을 설정한다.
+    async fn incr(&self, key: &str, ttl: u64) -> Result<i64, anyhow::Error>;
+}
+
+//저장소 종류 (configuration의 store.backend)
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreBackend {
+    #[default]
+    Redis,
+    Postgres,
+    Memory,
+}
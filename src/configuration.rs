@@ -1,4 +1,7 @@
+use actix_web::cookie::SameSite;
+use chrono::Duration;
 use secrecy::{ExposeSecret, Secret};
+use crate::auth::StoreBackend;
 //serde와 함께 사용하는 '헬퍼 함수'로 JSON등에서 숫자 타입 필드를 문자열로 역직렬화(deserialize)할 때 사용된다.
 use serde_aux::field_attributes::deserialize_number_from_string;
 use sqlx::{
@@ -14,6 +17,20 @@ pub struct Settings {
     pub application: ApplicationSettings,
     pub redis_uri: Secret<String>,
     pub jwt: JwtSettings,
+    pub email: EmailSettings,
+    //소셜 로그인 공급자가 없으면 생략 가능
+    #[serde(default)]
+    pub oidc: OidcSettings,
+    pub webauthn: WebauthnSettings,
+    pub auth_policy: AuthPolicy,
+    //생략하면 Redis를 사용한다.
+    #[serde(default)]
+    pub store: StoreSettings,
+    pub cookie: CookieSettings,
+    //생략하면 누구나 가입할 수 있다.
+    #[serde(default)]
+    pub registration: RegistrationSettings,
+    pub challenge: ChallengeSettings,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -42,6 +59,247 @@ pub struct JwtSettings {
     pub jwt_secret: Secret<String>,
 }
 
+#[derive(serde::Deserialize, Clone)]
+pub struct EmailSettings {
+    //로컬 outbox 메일러가 메일을 저장할 디렉토리
+    pub outbox_dir: String,
+    pub sender: String,
+}
+
+#[derive(serde::Deserialize, Clone, Default)]
+pub struct OidcSettings {
+    #[serde(default)]
+    pub providers: Vec<OidcProviderSettings>,
+}
+
+//OIDC 공급자 설정 / 엔드포인트를 모두 설정으로 받기 때문에 테스트에서는 로컬 mock IdP를 가리킬 수 있다.
+#[derive(serde::Deserialize, Clone)]
+pub struct OidcProviderSettings {
+    //URL 경로에 쓰이는 식별자 (ex. google -> /auth/oidc/google/start)
+    pub name: String,
+    pub display_name: String,
+    pub client_id: String,
+    pub client_secret: Secret<String>,
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec!["openid".to_string(), "email".to_string(), "profile".to_string()]
+}
+
+//WebAuthn Relying Party 설정 / rp_origin은 브라우저 주소창의 origin과 정확히 같아야 한다.
+#[derive(serde::Deserialize, Clone)]
+pub struct WebauthnSettings {
+    pub rp_id: String,
+    pub rp_origin: String,
+    pub rp_name: String,
+}
+
+//토큰 / 세션 저장소 설정 / redis, postgres, memory 중 하나 (memory는 테스트, 로컬 개발용)
+#[derive(serde::Deserialize, Clone, Default)]
+pub struct StoreSettings {
+    #[serde(default)]
+    pub backend: StoreBackend,
+}
+
+/*
+회원 가입 방식
+    -> open : 누구나 가입 / invite : 관리자가 발급한 초대 코드가 있어야 가입 / closed : 가입 불가
+    -> open이 아니면 소셜 로그인(OIDC)으로도 새 계정을 만들지 않고 기존 계정 연결만 허용한다.
+*/
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default)]
+pub struct RegistrationSettings {
+    #[serde(default)]
+    pub mode: RegistrationMode,
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistrationMode {
+    #[default]
+    Open,
+    Invite,
+    Closed,
+}
+
+impl RegistrationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegistrationMode::Open => "open",
+            RegistrationMode::Invite => "invite",
+            RegistrationMode::Closed => "closed",
+        }
+    }
+}
+
+/*
+작업 증명(proof-of-work) 챌린지 / 봇의 대량 가입, 비밀번호 대입을 늦춘다.
+    -> 서버가 발급한 challenge에 대해 sha256("{challenge}:{nonce}")의 앞 difficulty 비트가 0인 nonce를 브라우저가 찾아야 한다.
+    -> 회원 가입은 항상, 로그인은 같은 email로 failure_window_minutes 안에 login_failure_threshold번 실패한 뒤부터 요구한다.
+*/
+#[derive(serde::Deserialize, Clone, Copy, Debug)]
+pub struct ChallengeSettings {
+    pub enabled: bool,
+    //요구하는 선행 0 비트 수 / 1 늘릴 때마다 평균 계산량이 2배가 된다.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub difficulty: u32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub ttl_seconds: u64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub login_failure_threshold: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub failure_window_minutes: u64,
+}
+
+/*
+인증 쿠키(access token, refresh token, 세션 ID) 속성 / 환경(local, production)별 yaml에서 지정한다.
+    -> host_prefix : 쿠키 이름에 __Host- 접두사를 붙인다. 브라우저가 Secure, Path=/, Domain 없음을 강제하므로
+                     하위 도메인에서 쿠키를 덮어쓸 수 없다.
+    -> same_site가 none이면 브라우저가 Secure를 요구한다.
+*/
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct CookieSettings {
+    #[serde(default)]
+    pub domain: Option<String>,
+    pub secure: bool,
+    pub same_site: CookieSameSite,
+    #[serde(default)]
+    pub host_prefix: bool,
+    #[serde(default = "default_cookie_path")]
+    pub path: String,
+}
+
+fn default_cookie_path() -> String {
+    "/".to_string()
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CookieSameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl From<CookieSameSite> for SameSite {
+    fn from(value: CookieSameSite) -> Self {
+        match value {
+            CookieSameSite::Strict => SameSite::Strict,
+            CookieSameSite::Lax => SameSite::Lax,
+            CookieSameSite::None => SameSite::None,
+        }
+    }
+}
+
+impl CookieSettings {
+    //브라우저가 거부하는 조합은 서버 시작 시점에 막는다.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.host_prefix {
+            if !self.secure {
+                return Err("cookie.host_prefix requires cookie.secure".to_string())
+            }
+            if self.domain.is_some() || self.path != "/" {
+                return Err("cookie.host_prefix requires no cookie.domain and cookie.path \"/\"".to_string())
+            }
+        }
+        if self.same_site == CookieSameSite::None && !self.secure {
+            return Err("cookie.same_site \"none\" requires cookie.secure".to_string())
+        }
+        Ok(())
+    }
+}
+
+/*
+인증 수명 정책
+    -> access token, refresh token(JWT 모드의 로그인 유지), Redis 세션, 쿠키의 수명이 모두 여기서 결정된다.
+    -> idle timeout : 마지막 활동 이후 이 시간이 지나면 다시 로그인해야 한다.
+    -> absolute timeout : 활동과 상관없이 로그인 시점부터 이 시간이 지나면 다시 로그인해야 한다.
+    -> sliding renewal : 활동(토큰 갱신, 세션 사용)할 때마다 idle timeout을 다시 센다. 꺼져 있으면 로그인 시점 기준으로 한 번만 센다.
+    -> remember me : "로그인 상태 유지"를 선택하면 idle / absolute timeout 모두 remember_me_days를 사용한다.
+    -> reauth : 로그인 상태와 별개로, 민감한 작업은 최근에 자격 증명을 확인한 경우에만 허용한다.
+*/
+#[derive(serde::Deserialize, Clone, Copy, Debug)]
+pub struct AuthPolicy {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub access_token_minutes: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub idle_timeout_minutes: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub absolute_timeout_hours: i64,
+    pub sliding_renewal: bool,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub remember_me_days: i64,
+    //민감한 작업(비밀번호 변경, 관리자 기능 등)은 마지막 인증 후 이 시간 안에만 허용한다.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub reauth_minutes: i64,
+}
+
+//시각은 모두 unix timestamp(초)로 다룬다.
+impl AuthPolicy {
+    pub fn access_token_ttl(&self) -> Duration {
+        Duration::minutes(self.access_token_minutes)
+    }
+
+    pub fn reauth_window(&self) -> Duration {
+        Duration::minutes(self.reauth_minutes)
+    }
+
+    pub fn idle_timeout(&self, remember: bool) -> Duration {
+        match remember {
+            true => Duration::days(self.remember_me_days),
+            false => Duration::minutes(self.idle_timeout_minutes),
+        }
+    }
+
+    pub fn absolute_timeout(&self, remember: bool) -> Duration {
+        match remember {
+            true => Duration::days(self.remember_me_days),
+            false => Duration::hours(self.absolute_timeout_hours),
+        }
+    }
+
+    //세션 저장소 TTL / 세션마다 remember 여부가 다르므로 가장 긴 값을 쓰고, 실제 만료는 login_expiry로 판단한다.
+    pub fn session_store_ttl(&self) -> Duration {
+        self.idle_timeout(true).max(self.idle_timeout(false))
+    }
+
+    //로그인 시점 기준 절대 만료 시각
+    pub fn login_deadline(&self, login_at: i64, remember: bool) -> i64 {
+        login_at + self.absolute_timeout(remember).num_seconds()
+    }
+
+    //로그인 직후의 만료 시각 (idle, absolute 중 먼저 오는 것)
+    pub fn initial_expiry(&self, login_at: i64, remember: bool) -> i64 {
+        (login_at + self.idle_timeout(remember).num_seconds()).min(self.login_deadline(login_at, remember))
+    }
+
+    /*
+    now 시점에 활동이 있었을 때의 새 만료 시각 / 이미 만료되었으면 None
+        -> current_expiry : 직전까지의 만료 시각, deadline : login_deadline
+    */
+    pub fn renewed_expiry(&self, now: i64, current_expiry: i64, deadline: i64, remember: bool) -> Option<i64> {
+        if now >= current_expiry || now >= deadline {
+            return None
+        }
+        let expiry = match self.sliding_renewal {
+            true => now + self.idle_timeout(remember).num_seconds(),
+            false => current_expiry,
+        };
+
+        Some(expiry.min(deadline))
+    }
+
+    //access token 만료 시각 / 로그인 절대 만료 시각을 넘지 않는다.
+    pub fn access_token_expiry(&self, now: i64, deadline: i64) -> i64 {
+        (now + self.access_token_ttl().num_seconds()).min(deadline)
+    }
+}
+
 //PgConnections는 DB연결 시 주로 사용된다. without_db는 DB선택 없이 서버 연결 설정만 하고, with_db는 해당 DB까지 지정해주는 기능
 impl DatabaseSettings {
     //PgConnectOpions는 PostgreSQL 연결 설정을 표한하는 타입
@@ -57,7 +315,7 @@ impl DatabaseSettings {
         PgConnectOptions::new()
             .host(&self.host)
             .username(&self.username)
-            .password(&self.password.expose_secret())
+            .password(self.password.expose_secret())
             .port(self.port)
             .ssl_mode(ssl_mode)
     }
@@ -0,0 +1,83 @@
+use serde::Serialize;
+use similar::{DiffOp, TextDiff};
+
+//나란히 보기(side-by-side) diff의 한 줄 / 한쪽에만 있는 줄이면 반대쪽은 None
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DiffRow {
+    pub kind: DiffKind,
+    pub left_number: Option<usize>,
+    pub left: Option<String>,
+    pub right_number: Option<usize>,
+    pub right: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Equal,
+    Delete,
+    Insert,
+    Replace,
+}
+
+impl DiffKind {
+    //템플릿의 CSS class
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffKind::Equal => "equal",
+            DiffKind::Delete => "delete",
+            DiffKind::Insert => "insert",
+            DiffKind::Replace => "replace",
+        }
+    }
+}
+
+/*
+줄 단위 diff -> 나란히 보기
+    -> 바뀐 구간(replace)은 왼쪽(이전)과 오른쪽(이후)의 줄을 순서대로 짝짓고, 남는 줄은 반대쪽을 비워 둔다.
+    -> 줄 번호는 1부터 센다.
+*/
+pub fn side_by_side(old: &str, new: &str) -> Vec<DiffRow> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let diff = TextDiff::from_slices(&old_lines, &new_lines);
+
+    let mut rows = Vec::new();
+    for op in diff.ops() {
+        match *op {
+            DiffOp::Equal { old_index, new_index, len } => {
+                for i in 0..len {
+                    rows.push(row(DiffKind::Equal, Some((old_index + i, old_lines[old_index + i])), Some((new_index + i, new_lines[new_index + i]))));
+                }
+            }
+            DiffOp::Delete { old_index, old_len, .. } => {
+                for (i, line) in old_lines[old_index..old_index + old_len].iter().enumerate() {
+                    rows.push(row(DiffKind::Delete, Some((old_index + i, line)), None));
+                }
+            }
+            DiffOp::Insert { new_index, new_len, .. } => {
+                for (i, line) in new_lines[new_index..new_index + new_len].iter().enumerate() {
+                    rows.push(row(DiffKind::Insert, None, Some((new_index + i, line))));
+                }
+            }
+            DiffOp::Replace { old_index, old_len, new_index, new_len } => {
+                for i in 0..old_len.max(new_len) {
+                    let left = (i < old_len).then(|| (old_index + i, old_lines[old_index + i]));
+                    let right = (i < new_len).then(|| (new_index + i, new_lines[new_index + i]));
+                    rows.push(row(DiffKind::Replace, left, right));
+                }
+            }
+        }
+    }
+    rows
+}
+
+fn row(kind: DiffKind, left: Option<(usize, &str)>, right: Option<(usize, &str)>) -> DiffRow {
+    DiffRow {
+        kind,
+        left_number: left.map(|(index, _)| index + 1),
+        left: left.map(|(_, line)| line.to_string()),
+        right_number: right.map(|(index, _)| index + 1),
+        right: right.map(|(_, line)| line.to_string()),
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/*
+학습 문서
+    -> chapter(ex. ch1_1) 안에 여러 문서가 position 순서로 들어 있고, chapter + slug로 찾는다.
+    -> 문서를 추가할 때는 documents 테이블에 행을 넣기만 하면 된다. (템플릿 파일 추가, 재컴파일 불필요)
+    -> 방문자에게는 게시된 리비전(published_revision)만 보여준다. (find_published_document, list_published_documents)
+       documents의 title, body는 편집 중인 최신 리비전이다.
+*/
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub id: Uuid,
+    pub chapter: String,
+    pub section: String,
+    pub slug: String,
+    pub title: String,
+    pub body: String,
+    pub position: i32,
+    //낙관적 잠금용 리비전 번호 (저장할 때마다 1 증가)
+    pub revision: i32,
+    //draft, in_review, scheduled, published, archived (documents::workflow)
+    pub status: String,
+    //예약 게시 시각 / 승인 후 이 시각이 지나면 스케줄러가 게시한다.
+    pub publish_at: Option<DateTime<Utc>>,
+    //방문자에게 보여주는 리비전 / 게시된 적 없거나 보관(archived)되면 None
+    pub published_revision: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Document {
+    //GET /docs/{chapter}/{slug}
+    pub fn path(&self) -> String {
+        document_path(&self.chapter, &self.slug)
+    }
+}
+
+pub fn document_path(chapter: &str, slug: &str) -> String {
+    format!("/docs/{}/{}", chapter, slug)
+}
+
+#[tracing::instrument(name = "Find document", skip(pool))]
+pub async fn find_document(
+    chapter: &str,
+    slug: &str,
+    pool: &PgPool,
+) -> Result<Option<Document>, anyhow::Error> {
+    sqlx::query_as!(
+        Document,
+        r#"
+        SELECT id, chapter, section, slug, title, body, position, revision, status, publish_at, published_revision, created_at, updated_at
+        FROM documents
+        WHERE chapter = $1 AND slug = $2
+        "#,
+        chapter,
+        slug
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to query document")
+}
+
+#[tracing::instrument(name = "Find document by id", skip(pool))]
+pub async fn find_document_by_id(
+    id: Uuid,
+    pool: &PgPool,
+) -> Result<Option<Document>, anyhow::Error> {
+    sqlx::query_as!(
+        Document,
+        r#"
+        SELECT id, chapter, section, slug, title, body, position, revision, status, publish_at, published_revision, created_at, updated_at
+        FROM documents
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to query document")
+}
+
+//목차 순서(chapter, position)로 모든 문서를 가져온다. (편집기용, 게시 여부와 무관)
+#[tracing::instrument(name = "List documents", skip(pool))]
+pub async fn list_documents(pool: &PgPool) -> Result<Vec<Document>, anyhow::Error> {
+    sqlx::query_as!(
+        Document,
+        r#"
+        SELECT id, chapter, section, slug, title, body, position, revision, status, publish_at, published_revision, created_at, updated_at
+        FROM documents
+        ORDER BY chapter, position, slug
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list documents")
+}
+
+/*
+게시된 문서 (방문자용)
+    -> 최신 리비전이 게시된 리비전이면 documents의 내용을, 게시 후 다시 편집 중이면 document_revisions의 게시된 리비전 내용을 사용한다.
+    -> revision은 게시된 리비전 번호가 된다.
+*/
+#[tracing::instrument(name = "Find published document", skip(pool))]
+pub async fn find_published_document(
+    chapter: &str,
+    slug: &str,
+    pool: &PgPool,
+) -> Result<Option<Document>, anyhow::Error> {
+    sqlx::query_as!(
+        Document,
+        r#"
+        SELECT d.id, d.chapter, d.section, d.slug,
+            COALESCE(r.title, d.title) AS "title!", COALESCE(r.body, d.body) AS "body!",
+            d.position, d.published_revision AS "revision!", d.status, d.publish_at, d.published_revision,
+            d.created_at, d.updated_at
+        FROM documents d
+        LEFT JOIN document_revisions r
+            ON r.document_id = d.id AND r.revision = d.published_revision AND d.published_revision <> d.revision
+        WHERE d.chapter = $1 AND d.slug = $2 AND d.published_revision IS NOT NULL
+        "#,
+        chapter,
+        slug
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to query published document")
+}
+
+#[tracing::instrument(name = "Find published document by id", skip(pool))]
+pub async fn find_published_document_by_id(
+    id: Uuid,
+    pool: &PgPool,
+) -> Result<Option<Document>, anyhow::Error> {
+    sqlx::query_as!(
+        Document,
+        r#"
+        SELECT d.id, d.chapter, d.section, d.slug,
+            COALESCE(r.title, d.title) AS "title!", COALESCE(r.body, d.body) AS "body!",
+            d.position, d.published_revision AS "revision!", d.status, d.publish_at, d.published_revision,
+            d.created_at, d.updated_at
+        FROM documents d
+        LEFT JOIN document_revisions r
+            ON r.document_id = d.id AND r.revision = d.published_revision AND d.published_revision <> d.revision
+        WHERE d.id = $1 AND d.published_revision IS NOT NULL
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to query published document")
+}
+
+//목차 순서로 게시된 문서만 가져온다. (목차, 이전 / 다음 링크)
+#[tracing::instrument(name = "List published documents", skip(pool))]
+pub async fn list_published_documents(pool: &PgPool) -> Result<Vec<Document>, anyhow::Error> {
+    sqlx::query_as!(
+        Document,
+        r#"
+        SELECT d.id, d.chapter, d.section, d.slug,
+            COALESCE(r.title, d.title) AS "title!", COALESCE(r.body, d.body) AS "body!",
+            d.position, d.published_revision AS "revision!", d.status, d.publish_at, d.published_revision,
+            d.created_at, d.updated_at
+        FROM documents d
+        LEFT JOIN document_revisions r
+            ON r.document_id = d.id AND r.revision = d.published_revision AND d.published_revision <> d.revision
+        WHERE d.published_revision IS NOT NULL
+        ORDER BY d.chapter, d.position, d.slug
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list published documents")
+}
+
+//목차 순서에서 이전 / 다음 문서 (문서 하단 이동 링크)
+pub fn neighbours<'a>(documents: &'a [Document], current: &Document) -> (Option<&'a Document>, Option<&'a Document>) {
+    let index = documents.iter().position(|d| d.id == current.id);
+    match index {
+        Some(index) => (
+            index.checked_sub(1).and_then(|i| documents.get(i)),
+            documents.get(index + 1),
+        ),
+        None => (None, None),
+    }
+}
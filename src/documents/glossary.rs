@@ -0,0 +1,171 @@
+use anyhow::Context;
+use serde::Serialize;
+use sqlx::PgPool;
+
+//이 태그 안의 글자는 용어 링크로 바꾸지 않는다. (이미 링크, 코드 블록, 제목)
+const SKIPPED_TAGS: [&str; 8] = ["a", "pre", "h1", "h2", "h3", "h4", "h5", "h6"];
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GlossaryTerm {
+    pub slug: String,
+    pub term: String,
+    //본문에서 같은 용어로 보는 다른 표기 ex) HRTB -> Higher-Ranked Trait Bounds
+    pub aliases: Vec<String>,
+    //마크다운
+    pub definition: String,
+}
+
+impl GlossaryTerm {
+    //GET /glossary#{slug}
+    pub fn path(&self) -> String {
+        format!("/glossary#{}", self.slug)
+    }
+
+    //GET /fragments/glossary-term?term={slug} (문서의 용어 링크에 마우스를 올리면 showPopup으로 불러온다.)
+    pub fn popup_path(&self) -> String {
+        format!("/fragments/glossary-term?term={}", self.slug)
+    }
+}
+
+#[tracing::instrument(name = "List glossary terms", skip(pool))]
+pub async fn list_glossary_terms(pool: &PgPool) -> Result<Vec<GlossaryTerm>, anyhow::Error> {
+    sqlx::query_as!(
+        GlossaryTerm,
+        "SELECT slug, term, aliases, definition FROM glossary_terms ORDER BY lower(term)"
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list glossary terms")
+}
+
+#[tracing::instrument(name = "Find glossary term", skip(pool))]
+pub async fn find_glossary_term(slug: &str, pool: &PgPool) -> Result<Option<GlossaryTerm>, anyhow::Error> {
+    sqlx::query_as!(
+        GlossaryTerm,
+        "SELECT slug, term, aliases, definition FROM glossary_terms WHERE slug = $1",
+        slug
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to query glossary term")
+}
+
+/*
+용어 링크 / sanitize된 문서 HTML에서 용어가 처음 나오는 곳을 용어 설명 링크로 바꾼다.
+    -> 렌더링 캐시에는 링크 전 HTML을 두고 요청마다 적용한다. (용어집을 바꿔도 문서 캐시를 비울 필요가 없다.)
+    -> 글자(text)만 바꾸고 태그와 속성은 그대로 둔다. 링크, 코드 블록, 제목 안은 건너뛴다. (인라인 코드는 바꾼다.)
+    -> 한 용어는 문서에서 한 번만 링크한다. 같은 위치에서 여러 표기가 맞으면 긴 표기를 고른다.
+    -> 영문 표기는 단어 중간에서 일치하지 않게 한다. ex) Send는 Sender에 링크하지 않는다.
+       한글은 조사가 붙으므로 뒤쪽 경계를 보지 않는다. ex) "불투명 타입이므로"
+*/
+pub fn link_glossary_terms(html: &str, terms: &[GlossaryTerm]) -> String {
+    //sanitize된 HTML의 글자는 &, <, > 만 escape되어 있다.
+    let mut forms: Vec<(String, usize)> = terms
+        .iter()
+        .enumerate()
+        .flat_map(|(index, term)| {
+            std::iter::once(&term.term)
+                .chain(term.aliases.iter())
+                .filter(|form| !form.is_empty())
+                .map(move |form| (escape_text(form), index))
+        })
+        .collect();
+    forms.sort_by_key(|(form, _)| std::cmp::Reverse(form.len()));
+
+    let mut linked = vec![false; terms.len()];
+    let mut output = String::with_capacity(html.len());
+    let mut skip_depth = 0usize;
+    let mut rest = html;
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let end = tag_end(rest);
+            let (name, closing) = tag_name(&rest[..end]);
+            if SKIPPED_TAGS.contains(&name.as_str()) {
+                skip_depth = match closing {
+                    true => skip_depth.saturating_sub(1),
+                    false => skip_depth + 1,
+                };
+            }
+            output.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = rest.find('<').unwrap_or(rest.len());
+        match skip_depth {
+            0 => link_text(&rest[..end], &forms, terms, &mut linked, &mut output),
+            _ => output.push_str(&rest[..end]),
+        }
+        rest = &rest[end..];
+    }
+    output
+}
+
+fn link_text(text: &str, forms: &[(String, usize)], terms: &[GlossaryTerm], linked: &mut [bool], output: &mut String) {
+    let mut copied = 0;
+    let mut position = 0;
+    while position < text.len() {
+        let found = forms.iter().find(|(form, index)| {
+            !linked[*index] && text[position..].starts_with(form.as_str()) && is_word_boundary(text, position, position + form.len())
+        });
+        match found {
+            Some((form, index)) => {
+                let term = &terms[*index];
+                output.push_str(&text[copied..position]);
+                output.push_str(&format!(
+                    r#"<a class="glossary-term" href="{}" data-popup="{}">{}</a>"#,
+                    term.path(),
+                    term.popup_path(),
+                    &text[position..position + form.len()]
+                ));
+                linked[*index] = true;
+                position += form.len();
+                copied = position;
+            }
+            None => position += text[position..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    output.push_str(&text[copied..]);
+}
+
+//영문/숫자로 시작(끝)하는 표기는 앞(뒤) 글자가 영문/숫자가 아니어야 한다.
+fn is_word_boundary(text: &str, start: usize, end: usize) -> bool {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let matched = &text[start..end];
+    let starts_word = matched.chars().next().is_some_and(is_word);
+    let ends_word = matched.chars().next_back().is_some_and(is_word);
+
+    (!starts_word || !text[..start].chars().next_back().is_some_and(is_word))
+        && (!ends_word || !text[end..].chars().next().is_some_and(is_word))
+}
+
+//'<'부터 '>'까지 (따옴표 안의 '>'는 속성 값이다.)
+fn tag_end(html: &str) -> usize {
+    let mut quote: Option<char> = None;
+    for (index, c) in html.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return index + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+//<pre class="..."> -> ("pre", false) / </pre> -> ("pre", true)
+fn tag_name(tag: &str) -> (String, bool) {
+    let inner = tag.trim_start_matches('<');
+    let closing = inner.starts_with('/');
+    let name = inner
+        .trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    (name, closing)
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use pulldown_cmark::{html, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
+use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+//코드 블록 자리 표시자 / sanitize가 끝난 뒤 구문 강조된 HTML로 바꾼다. (사용자 입력과 겹치지 않도록 사용자 정의 영역 문자 사용)
+const CODE_BLOCK_MARK: char = '\u{E000}';
+
+//문서 안의 제목 / 제목 링크(#anchor)와 목차 생성에 사용한다.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    pub anchor: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderedMarkdown {
+    pub html: String,
+    pub headings: Vec<Heading>,
+}
+
+/*
+마크다운 -> HTML
+    1. 확장 문법(callout)을 HTML 블록으로 바꾼다.
+    2. pulldown-cmark로 변환하면서 제목에 anchor를 붙이고, 코드 블록은 자리 표시자로 빼 둔다.
+    3. ammonia로 sanitize한다. (문서 본문에 들어간 script, on* 속성 등 제거)
+    4. 자리 표시자를 syntect로 구문 강조한 코드로 바꾼다. (강조 결과는 코드 내용을 escape한 span만 만든다.)
+*/
+pub fn render_markdown(source: &str) -> RenderedMarkdown {
+    let source = expand_callouts(source);
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+
+    let mut events: Vec<Event> = Vec::new();
+    let mut headings = Vec::new();
+    let mut code_blocks = Vec::new();
+    let mut anchors = HashMap::new();
+    let mut heading: Option<(HeadingLevel, Vec<Event>)> = None;
+    let mut code: Option<(String, String)> = None;
+
+    for event in Parser::new_ext(&source, options) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => heading = Some((level, Vec::new())),
+            Event::End(TagEnd::Heading(_)) => {
+                let Some((level, inner)) = heading.take() else { continue };
+                let text = plain_text(&inner);
+                let anchor = unique_anchor(&text, &mut anchors);
+                let mut inner_html = String::new();
+                html::push_html(&mut inner_html, inner.into_iter());
+                let level = heading_level(level);
+                events.push(Event::Html(format!(
+                    r##"<h{level} id="{anchor}"><a class="heading-anchor" href="#{anchor}">#</a> {inner_html}</h{level}>"##
+                ).into()));
+                headings.push(Heading { level, text, anchor });
+            }
+            event if heading.is_some() => {
+                if let Some((_, inner)) = heading.as_mut() {
+                    inner.push(event);
+                }
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info.split_whitespace().next().unwrap_or_default().to_lowercase(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code = Some((language, String::new()));
+            }
+            Event::Text(text) if code.is_some() => {
+                if let Some((_, buffer)) = code.as_mut() {
+                    buffer.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                let Some((language, buffer)) = code.take() else { continue };
+                events.push(Event::Html(format!(
+                    "<div class=\"code-block\">{mark}{index}{mark}</div>\n",
+                    mark = CODE_BLOCK_MARK,
+                    index = code_blocks.len()
+                ).into()));
+                code_blocks.push(highlight_code(&language, &buffer));
+            }
+            event => events.push(event),
+        }
+    }
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, events.into_iter());
+    let mut html = sanitizer().clean(&unsafe_html).to_string();
+    for (index, block) in code_blocks.iter().enumerate() {
+        html = html.replace(&format!("{mark}{index}{mark}", mark = CODE_BLOCK_MARK), block);
+    }
+
+    RenderedMarkdown { html, headings }
+}
+
+/*
+callout 확장 문법 / 학습 노트의 "핵심" 상자
+    :::핵심 [제목]
+    내용(마크다운)
+    :::
+    -> 종류 : 핵심(key), 주의(warning), 참고(note) / 그 외 단어는 참고 상자에 그 단어를 제목으로 사용한다.
+    -> 여는 div 다음과 닫는 div 앞에 빈 줄을 넣어서, 안쪽 내용이 HTML이 아닌 마크다운으로 해석되게 한다.
+    -> 코드 블록(```) 안의 ::: 는 건드리지 않는다.
+*/
+fn expand_callouts(source: &str) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut in_code = false;
+    let mut depth = 0usize;
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code && trimmed.starts_with(":::") {
+            let rest = trimmed.trim_start_matches(':').trim();
+            if rest.is_empty() {
+                if depth > 0 {
+                    depth -= 1;
+                    output.push_str("\n</div>\n\n");
+                    continue;
+                }
+            } else {
+                let (kind, title) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let class = match kind {
+                    "핵심" => "key",
+                    "주의" => "warning",
+                    _ => "note",
+                };
+                let title = match title.trim() {
+                    "" => kind.to_string(),
+                    title => format!("{} · {}", kind, title),
+                };
+                depth += 1;
+                output.push_str(&format!(
+                    "<div class=\"callout callout-{}\">\n<p class=\"callout-title\">{}</p>\n\n",
+                    class,
+                    htmlescape::encode_minimal(&title)
+                ));
+                continue;
+            }
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    //닫히지 않은 callout
+    for _ in 0..depth {
+        output.push_str("\n</div>\n");
+    }
+    output
+}
+
+//검색 색인용 본문 텍스트 (마크다운 문법과 HTML을 뺀 글자, 코드 블록 포함)
+pub fn markdown_text(source: &str) -> String {
+    let source = expand_callouts(source);
+    let mut text = String::with_capacity(source.len());
+    for event in Parser::new_ext(&source, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH) {
+        match event {
+            Event::Text(t) | Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak | Event::End(_) if !text.ends_with(char::is_whitespace) => text.push(' '),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+fn heading_level(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+fn plain_text(events: &[Event]) -> String {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Text(text) | Event::Code(text) => Some(text.as_ref()),
+            _ => None,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/*
+제목 -> anchor
+    -> 영문은 소문자로, 한글 등 문자와 숫자는 그대로 두고, 공백과 - 는 - 로 바꾸고 나머지 기호는 버린다.
+    ex) "Send + Sync" -> "send-sync" / "'static 생명주기" -> "static-생명주기"
+    -> 같은 anchor가 다시 나오면 -1, -2를 붙인다.
+*/
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() || c == '_' {
+            slug.push(c);
+        } else if (c.is_whitespace() || c == '-') && !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn unique_anchor(text: &str, anchors: &mut HashMap<String, usize>) -> String {
+    let base = match slugify(text) {
+        slug if slug.is_empty() => "section".to_string(),
+        slug => slug,
+    };
+    let count = anchors.entry(base.clone()).or_insert(0);
+    let anchor = match *count {
+        0 => base,
+        n => format!("{}-{}", base, n),
+    };
+    *count += 1;
+    anchor
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(two_face::syntax::extra_newlines)
+}
+
+//hl- 접두사 class로 구문 강조한다. 색상은 static/css/highlight.css
+fn highlight_code(language: &str, code: &str) -> String {
+    let language: String = language.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+    let syntaxes = syntax_set();
+    let highlighted = syntaxes.find_syntax_by_token(&language).and_then(|syntax| {
+        let mut generator = ClassedHTMLGenerator::new_with_class_style(
+            syntax,
+            syntaxes,
+            ClassStyle::SpacedPrefixed { prefix: "hl-" },
+        );
+        for line in LinesWithEndings::from(code) {
+            generator.parse_html_for_line_which_includes_newline(line).ok()?;
+        }
+        Some(generator.finalize())
+    });
+    let body = highlighted.unwrap_or_else(|| htmlescape::encode_minimal(code));
+
+    match language.as_str() {
+        "" => format!("<pre class=\"highlight\"><code>{}</code></pre>", body),
+        language => format!("<pre class=\"highlight\"><code class=\"language-{}\">{}</code></pre>", language, body),
+    }
+}
+
+//허용 태그는 ammonia 기본값을 따르고, 제목 anchor와 callout / 코드 블록 class만 추가로 허용한다.
+fn sanitizer() -> &'static ammonia::Builder<'static> {
+    static SANITIZER: OnceLock<ammonia::Builder<'static>> = OnceLock::new();
+    SANITIZER.get_or_init(|| {
+        let mut builder = ammonia::Builder::default();
+        for tag in ["h1", "h2", "h3", "h4", "h5", "h6"] {
+            builder.add_tag_attributes(tag, &["id"]);
+        }
+        builder
+            .add_tag_attributes("div", &["class"])
+            .add_tag_attributes("p", &["class"])
+            .add_tag_attributes("a", &["class"])
+            .add_tag_attributes("input", &["type", "checked", "disabled"])
+            .add_tags(&["input"]);
+        builder
+    })
+}
@@ -0,0 +1,23 @@
+pub mod diff;
+pub mod document;
+pub mod glossary;
+pub mod markdown;
+pub mod progress;
+pub mod render_cache;
+pub mod revision;
+pub mod search;
+pub mod tags;
+pub mod toc;
+pub mod workflow;
+
+pub use diff::*;
+pub use document::*;
+pub use glossary::*;
+pub use markdown::*;
+pub use progress::*;
+pub use render_cache::*;
+pub use revision::*;
+pub use search::*;
+pub use tags::*;
+pub use toc::*;
+pub use workflow::*;
\ No newline at end of file
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::documents::list_published_documents;
+
+//본문을 이 비율 이상 읽으면 완료로 기록한다.
+pub const COMPLETED_SCROLL: f32 = 0.9;
+//한 번의 보고로 더할 수 있는 최대 시간(초) / 브라우저는 30초마다 보고하므로 이보다 길면 탭을 열어 둔 채 자리를 비운 것이다.
+pub const MAX_REPORTED_SECONDS: i32 = 300;
+
+//사용자 / 문서별 읽기 진행 상황
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadingProgress {
+    pub document_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub last_read_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    //마지막으로 보고 있던 위치 (본문을 읽은 비율 0 ~ 1)
+    pub scroll_position: f32,
+    pub seconds_spent: i32,
+}
+
+impl ReadingProgress {
+    pub fn is_completed(&self) -> bool {
+        self.completed_at.is_some()
+    }
+
+    //완료한 문서는 다시 위에서부터 읽고 있어도 100
+    pub fn percent(&self) -> u8 {
+        match self.is_completed() {
+            true => 100,
+            false => (self.scroll_position.clamp(0.0, 1.0) * 100.0).round() as u8,
+        }
+    }
+}
+
+//홈 화면의 "이어서 읽기" 카드
+#[derive(Debug, Serialize)]
+pub struct ContinueReading {
+    pub title: String,
+    pub path: String,
+    pub chapter: String,
+    pub percent: u8,
+    pub seconds_spent: i32,
+    //읽던 문서가 없어서 마지막으로 끝낸 문서의 다음 문서를 권하는 경우
+    pub next: bool,
+}
+
+impl ContinueReading {
+    //화면에 보여줄 읽은 시간 (분, 1분 미만은 1분)
+    pub fn minutes_spent(&self) -> i32 {
+        (self.seconds_spent + 59) / 60
+    }
+}
+
+//로그인한 사용자가 문서를 열면 읽기 시작으로 기록한다. (처음 연 시각, 읽은 위치와 시간은 유지)
+#[tracing::instrument(name = "Mark document read", skip(pool))]
+pub async fn mark_read(
+    email: &str,
+    document_id: Uuid,
+    pool: &PgPool,
+) -> Result<ReadingProgress, anyhow::Error> {
+    let now = Utc::now();
+    sqlx::query_as!(
+        ReadingProgress,
+        r#"
+        INSERT INTO reading_progress (email, document_id, started_at, last_read_at)
+        VALUES ($1, $2, $3, $3)
+        ON CONFLICT (email, document_id) DO UPDATE SET last_read_at = EXCLUDED.last_read_at
+        RETURNING document_id, started_at, last_read_at, completed_at, scroll_position, seconds_spent
+        "#,
+        email,
+        document_id,
+        now
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to record reading progress")
+}
+
+/*
+문서를 읽는 동안 브라우저가 보내는 진행 상황을 기록한다.
+    -> scroll은 지금 보고 있는 위치로 바꾸고, seconds는 지난 보고 이후 문서를 보고 있던 시간이라 더한다.
+    -> completed를 보내거나 scroll이 COMPLETED_SCROLL 이상이면 완료로 기록한다. (한 번 완료한 문서는 완료로 남는다.)
+*/
+#[tracing::instrument(name = "Record reading progress", skip(pool))]
+pub async fn record_progress(
+    pool: &PgPool,
+    email: &str,
+    document_id: Uuid,
+    scroll: f32,
+    seconds: i32,
+    completed: bool,
+) -> Result<ReadingProgress, anyhow::Error> {
+    let now = Utc::now();
+    let scroll = scroll.clamp(0.0, 1.0);
+    let completed_at = (completed || scroll >= COMPLETED_SCROLL).then_some(now);
+    sqlx::query_as!(
+        ReadingProgress,
+        r#"
+        INSERT INTO reading_progress (email, document_id, started_at, last_read_at, completed_at, scroll_position, seconds_spent)
+        VALUES ($1, $2, $3, $3, $4, $5, $6)
+        ON CONFLICT (email, document_id) DO UPDATE
+        SET last_read_at = EXCLUDED.last_read_at,
+            completed_at = COALESCE(reading_progress.completed_at, EXCLUDED.completed_at),
+            scroll_position = EXCLUDED.scroll_position,
+            seconds_spent = reading_progress.seconds_spent + EXCLUDED.seconds_spent
+        RETURNING document_id, started_at, last_read_at, completed_at, scroll_position, seconds_spent
+        "#,
+        email,
+        document_id,
+        now,
+        completed_at,
+        scroll,
+        seconds.clamp(0, MAX_REPORTED_SECONDS)
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to record reading progress")
+}
+
+//사용자의 문서별 진행 상황 (목차의 완료 표시와 챕터 진행률)
+#[tracing::instrument(name = "Reading progress", skip(pool))]
+pub async fn reading_progress(
+    email: &str,
+    pool: &PgPool,
+) -> Result<HashMap<Uuid, ReadingProgress>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        ReadingProgress,
+        r#"
+        SELECT document_id, started_at, last_read_at, completed_at, scroll_position, seconds_spent
+        FROM reading_progress
+        WHERE email = $1
+        "#,
+        email
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to query reading progress")?;
+
+    Ok(rows.into_iter().map(|row| (row.document_id, row)).collect())
+}
+
+/*
+이어서 읽기
+    -> 끝내지 않은 문서 중 가장 최근에 읽은 문서
+    -> 읽던 문서가 없으면 가장 최근에 끝낸 문서 다음(목차 순서)의 끝내지 않은 문서 / 아직 아무것도 읽지 않았으면 None
+    -> 게시된 문서만 대상으로 한다.
+*/
+#[tracing::instrument(name = "Continue reading", skip(pool))]
+pub async fn continue_reading(email: &str, pool: &PgPool) -> Result<Option<ContinueReading>, anyhow::Error> {
+    let progress = reading_progress(email, pool).await?;
+    if progress.is_empty() {
+        return Ok(None);
+    }
+    let documents = list_published_documents(pool).await?;
+
+    let in_progress = documents
+        .iter()
+        .filter_map(|document| progress.get(&document.id).map(|p| (document, p)))
+        .filter(|(_, p)| !p.is_completed())
+        .max_by_key(|(_, p)| p.last_read_at);
+    if let Some((document, p)) = in_progress {
+        return Ok(Some(ContinueReading {
+            title: document.title.clone(),
+            path: document.path(),
+            chapter: document.chapter.clone(),
+            percent: p.percent(),
+            seconds_spent: p.seconds_spent,
+            next: false,
+        }));
+    }
+
+    let last = documents
+        .iter()
+        .enumerate()
+        .filter_map(|(index, document)| progress.get(&document.id).map(|p| (index, p)))
+        .max_by_key(|(_, p)| p.last_read_at);
+    let next = last.and_then(|(index, _)| {
+        //읽던 문서가 없으므로 읽은 기록이 없는 문서가 끝내지 않은 문서다.
+        documents[index + 1..].iter().find(|document| !progress.contains_key(&document.id))
+    });
+
+    Ok(next.map(|document| ContinueReading {
+        title: document.title.clone(),
+        path: document.path(),
+        chapter: document.chapter.clone(),
+        percent: 0,
+        seconds_spent: 0,
+        next: true,
+    }))
+}
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::documents::{render_markdown, Document, RenderedMarkdown};
+
+//문서 id -> (렌더링할 때의 updated_at, 렌더링 결과)
+type CacheEntries = HashMap<Uuid, (DateTime<Utc>, Arc<RenderedMarkdown>)>;
+
+/*
+렌더링 결과 캐시
+    -> 마크다운 변환 + 구문 강조는 요청마다 하기에는 비싸므로 문서 id별로 결과를 보관한다.
+    -> 문서의 updated_at이 바뀌면(수정) 다시 렌더링한다. 다른 서버 인스턴스에서 수정해도 DB의 updated_at으로 알 수 있다.
+    -> 편집 화면처럼 같은 시각에 여러 번 저장될 수 있는 곳은 invalidate로 직접 비운다.
+*/
+#[derive(Debug, Default)]
+pub struct RenderCache {
+    entries: Mutex<CacheEntries>,
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn render(&self, document: &Document) -> Arc<RenderedMarkdown> {
+        if let Some((updated_at, rendered)) = self.lock().get(&document.id) {
+            if *updated_at == document.updated_at {
+                return rendered.clone();
+            }
+        }
+        let rendered = Arc::new(render_markdown(&document.body));
+        self.lock().insert(document.id, (document.updated_at, rendered.clone()));
+        rendered
+    }
+
+    pub fn invalidate(&self, id: Uuid) {
+        self.lock().remove(&id);
+    }
+
+    //다른 스레드가 lock을 잡은 채 panic해도 캐시는 다시 렌더링하면 되므로 그대로 사용한다.
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheEntries> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use crate::documents::index_document;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentRevision {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub revision: i32,
+    pub title: String,
+    pub body: String,
+    pub author: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+//새 문서 (편집기에서 추가) / draft로 시작하므로 검토, 승인을 거쳐야 방문자에게 보인다.
+#[derive(Debug, Deserialize)]
+pub struct NewDocument {
+    pub chapter: String,
+    pub section: String,
+    pub slug: String,
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub position: i32,
+}
+
+//저장 결과 / Conflict면 다른 편집자가 먼저 저장한 것이므로 최신 내용을 다시 불러와야 한다.
+#[derive(Debug, PartialEq)]
+pub enum SaveOutcome {
+    Saved { revision: i32 },
+    Conflict { current_revision: i32 },
+    NotFound,
+}
+
+/*
+문서 저장 (낙관적 잠금)
+    -> 편집을 시작할 때 받은 revision(expected_revision)이 지금 DB의 revision과 같을 때만 저장한다.
+    -> 조건 확인과 revision 증가를 UPDATE 한 문장으로 처리하고, 같은 트랜잭션에서 document_revisions에 새 리비전을 남긴다.
+    -> 저장한 내용은 다시 draft가 된다. 게시된 문서라면 검토를 거쳐 승인될 때까지 이전에 게시된 리비전이 보인다.
+    -> 저장할 때마다 검색 색인도 다시 만든다. (색인은 게시된 리비전 기준)
+*/
+#[tracing::instrument(name = "Save document", skip(pool, title, body))]
+pub async fn save_document(
+    pool: &PgPool,
+    id: Uuid,
+    expected_revision: i32,
+    title: &str,
+    body: &str,
+    author: &str,
+    message: &str,
+) -> Result<SaveOutcome, anyhow::Error> {
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+    let saved = sqlx::query_scalar!(
+        r#"
+        UPDATE documents
+        SET title = $3, body = $4, revision = revision + 1, status = 'draft', updated_at = $5
+        WHERE id = $1 AND revision = $2
+        RETURNING revision
+        "#,
+        id,
+        expected_revision,
+        title,
+        body,
+        Utc::now()
+    )
+    .fetch_optional(&mut transaction)
+    .await
+    .context("Failed to update document")?;
+
+    let Some(revision) = saved else {
+        let current = sqlx::query_scalar!("SELECT revision FROM documents WHERE id = $1", id)
+            .fetch_optional(&mut transaction)
+            .await
+            .context("Failed to query document revision")?;
+        return Ok(match current {
+            Some(current_revision) => SaveOutcome::Conflict { current_revision },
+            None => SaveOutcome::NotFound,
+        });
+    };
+    insert_revision(&mut transaction, id, revision, title, body, author, message).await?;
+    transaction.commit().await.context("Failed to commit document")?;
+    index_document(pool, id).await?;
+
+    Ok(SaveOutcome::Saved { revision })
+}
+
+#[tracing::instrument(name = "Create document", skip(pool, document))]
+pub async fn create_document(
+    pool: &PgPool,
+    document: &NewDocument,
+    author: &str,
+) -> Result<Uuid, anyhow::Error> {
+    let id = Uuid::new_v4();
+    let now = Utc::now();
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+    sqlx::query!(
+        r#"
+        INSERT INTO documents (id, chapter, section, slug, title, body, position, revision, status, published_revision, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, 1, 'draft', NULL, $8, $8)
+        "#,
+        id,
+        document.chapter,
+        document.section,
+        document.slug,
+        document.title,
+        document.body,
+        document.position,
+        now
+    )
+    .execute(&mut transaction)
+    .await
+    .context("Failed to insert document")?;
+    insert_revision(&mut transaction, id, 1, &document.title, &document.body, author, "새 문서").await?;
+    transaction.commit().await.context("Failed to commit document")?;
+
+    Ok(id)
+}
+
+async fn insert_revision(
+    transaction: &mut Transaction<'_, Postgres>,
+    document_id: Uuid,
+    revision: i32,
+    title: &str,
+    body: &str,
+    author: &str,
+    message: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO document_revisions (id, document_id, revision, title, body, author, message, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        Uuid::new_v4(),
+        document_id,
+        revision,
+        title,
+        body,
+        author,
+        message,
+        Utc::now()
+    )
+    .execute(transaction)
+    .await
+    .context("Failed to insert document revision")?;
+
+    Ok(())
+}
+
+//최신 리비전부터
+#[tracing::instrument(name = "List document revisions", skip(pool))]
+pub async fn list_revisions(
+    document_id: Uuid,
+    pool: &PgPool,
+) -> Result<Vec<DocumentRevision>, anyhow::Error> {
+    sqlx::query_as!(
+        DocumentRevision,
+        r#"
+        SELECT id, document_id, revision, title, body, author, message, created_at
+        FROM document_revisions
+        WHERE document_id = $1
+        ORDER BY revision DESC
+        "#,
+        document_id
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list document revisions")
+}
+
+#[tracing::instrument(name = "Find document revision", skip(pool))]
+pub async fn find_revision(
+    document_id: Uuid,
+    revision: i32,
+    pool: &PgPool,
+) -> Result<Option<DocumentRevision>, anyhow::Error> {
+    sqlx::query_as!(
+        DocumentRevision,
+        r#"
+        SELECT id, document_id, revision, title, body, author, message, created_at
+        FROM document_revisions
+        WHERE document_id = $1 AND revision = $2
+        "#,
+        document_id,
+        revision
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to query document revision")
+}
@@ -0,0 +1,269 @@
+use anyhow::Context;
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::documents::{chapter_number, document_path, find_published_document_by_id, list_published_documents, markdown_text};
+
+//검색 결과 미리보기 길이(글자 수)와 일치 위치 앞에 보여줄 글자 수
+const SNIPPET_LENGTH: usize = 160;
+const SNIPPET_LEADING: usize = 40;
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub document_id: Uuid,
+    pub chapter: String,
+    pub title: String,
+    pub path: String,
+    //<mark>로 일치한 부분을 감싼 HTML (나머지는 escape)
+    pub snippet: String,
+    pub rank: f32,
+}
+
+//챕터별 결과 수 (검색 결과를 챕터로 좁히는 링크)
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ChapterFacet {
+    pub chapter: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResults {
+    pub query: String,
+    pub chapter: Option<String>,
+    //챕터로 좁히기 전의 전체 결과 수
+    pub total: usize,
+    pub facets: Vec<ChapterFacet>,
+    pub hits: Vec<SearchHit>,
+}
+
+/*
+검색어 / 색인 단어(lexeme) 만들기
+    -> 글자와 숫자가 아닌 문자로 나누고, 영문은 소문자로 바꾼다. ex) "Send + Sync" -> send, sync
+    -> 한글은 띄어쓰기 단위로 찾을 수 없으므로(조사, 합성어) 두 글자씩 겹쳐 나눈다(bigram).
+       ex) "생명주기는" -> 생명, 명주, 주기, 기는 / 검색어 "생명주기" -> 생명, 명주, 주기 (모두 포함하면 일치)
+    -> Postgres의 텍스트 검색 파서는 DB locale에 따라 한글을 단어로 보지 않을 수 있어서, 나누기는 여기서 하고
+       DB에는 array_to_tsvector로 그대로 넣는다.
+*/
+pub fn search_terms(text: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    let mut push = |term: String| {
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    };
+
+    let mut run = String::new();
+    let mut hangul_run = false;
+    for c in text.chars().chain(std::iter::once(' ')) {
+        let hangul = is_hangul(c);
+        if !c.is_alphanumeric() || (!run.is_empty() && hangul != hangul_run) {
+            for term in run_terms(&run, hangul_run) {
+                push(term);
+            }
+            run.clear();
+        }
+        if c.is_alphanumeric() {
+            run.extend(c.to_lowercase());
+            hangul_run = hangul;
+        }
+    }
+    terms
+}
+
+fn run_terms(run: &str, hangul: bool) -> Vec<String> {
+    let chars: Vec<char> = run.chars().collect();
+    if !hangul || chars.len() < 2 {
+        return vec![run.to_string()];
+    }
+    chars.windows(2).map(|pair| pair.iter().collect()).collect()
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7A3}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}')
+}
+
+//검색어 -> tsquery 문자열 / 모든 단어를 포함해야 한다. 한 글자 한글은 그 글자로 시작하는 bigram과 일치시킨다.
+fn to_tsquery(terms: &[String]) -> String {
+    terms
+        .iter()
+        .map(|term| {
+            let lexeme = format!("'{}'", term.replace('\'', "''"));
+            match term.chars().count() == 1 && term.chars().all(is_hangul) {
+                true => format!("{}:*", lexeme),
+                false => lexeme,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" & ")
+}
+
+/*
+문서 하나의 검색 색인을 다시 만든다.
+    -> 방문자에게 보이는 게시된 리비전을 색인한다. 게시되지 않았거나 보관된 문서는 색인에서 뺀다.
+    -> 제목은 가중치 A, 본문은 B
+*/
+#[tracing::instrument(name = "Index document", skip(pool))]
+pub async fn index_document(pool: &PgPool, id: Uuid) -> Result<(), anyhow::Error> {
+    let Some(document) = find_published_document_by_id(id, pool).await? else {
+        sqlx::query!("DELETE FROM document_search WHERE document_id = $1", id)
+            .execute(pool)
+            .await
+            .context("Failed to remove document from search index")?;
+        return Ok(());
+    };
+
+    let body = markdown_text(&document.body);
+    sqlx::query!(
+        r#"
+        INSERT INTO document_search (document_id, chapter, slug, title, body, search_vector, indexed_at)
+        VALUES ($1, $2, $3, $4, $5,
+            setweight(array_to_tsvector($6::text[]), 'A') || setweight(array_to_tsvector($7::text[]), 'B'), $8)
+        ON CONFLICT (document_id) DO UPDATE
+        SET chapter = EXCLUDED.chapter, slug = EXCLUDED.slug, title = EXCLUDED.title, body = EXCLUDED.body,
+            search_vector = EXCLUDED.search_vector, indexed_at = EXCLUDED.indexed_at
+        "#,
+        document.id,
+        document.chapter,
+        document.slug,
+        document.title,
+        body,
+        &search_terms(&document.title)[..],
+        &search_terms(&body)[..],
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .context("Failed to update search index")?;
+
+    Ok(())
+}
+
+//게시된 문서 전체를 다시 색인한다. (서버 시작 시 / 테이블에 직접 넣은 문서 포함)
+#[tracing::instrument(name = "Reindex documents", skip(pool))]
+pub async fn reindex_documents(pool: &PgPool) -> Result<usize, anyhow::Error> {
+    let documents = list_published_documents(pool).await?;
+    sqlx::query!(
+        "DELETE FROM document_search WHERE document_id <> ALL($1)",
+        &documents.iter().map(|document| document.id).collect::<Vec<_>>()[..]
+    )
+    .execute(pool)
+    .await
+    .context("Failed to clean search index")?;
+    for document in &documents {
+        index_document(pool, document.id).await?;
+    }
+    Ok(documents.len())
+}
+
+/*
+검색
+    -> 모든 검색어를 포함하는 문서를 ts_rank(제목 가중치 우선) 순서로 돌려준다.
+    -> facets는 chapter로 좁히기 전의 전체 결과로 센다.
+*/
+#[tracing::instrument(name = "Search documents", skip(pool))]
+pub async fn search_documents(
+    pool: &PgPool,
+    query: &str,
+    chapter: Option<&str>,
+    limit: usize,
+) -> Result<SearchResults, anyhow::Error> {
+    let query = query.trim();
+    let terms = search_terms(query);
+    let mut results = SearchResults {
+        query: query.to_string(),
+        chapter: chapter.map(str::to_string),
+        total: 0,
+        facets: Vec::new(),
+        hits: Vec::new(),
+    };
+    if terms.is_empty() {
+        return Ok(results);
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT document_id, chapter, slug, title, body,
+            ts_rank(search_vector, $1::text::tsquery) AS "rank!"
+        FROM document_search
+        WHERE search_vector @@ $1::text::tsquery
+        ORDER BY ts_rank(search_vector, $1::text::tsquery) DESC, chapter, title
+        "#,
+        to_tsquery(&terms)
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to search documents")?;
+
+    results.total = rows.len();
+    for row in &rows {
+        match results.facets.iter_mut().find(|facet| facet.chapter == row.chapter) {
+            Some(facet) => facet.count += 1,
+            None => results.facets.push(ChapterFacet { chapter: row.chapter.clone(), count: 1 }),
+        }
+    }
+    results.facets.sort_by_key(|facet| (chapter_number(&facet.chapter).is_none(), chapter_number(&facet.chapter), facet.chapter.clone()));
+
+    results.hits = rows
+        .into_iter()
+        .filter(|row| match chapter {
+            Some(chapter) => row.chapter == chapter,
+            None => true,
+        })
+        .take(limit)
+        .map(|row| SearchHit {
+            document_id: row.document_id,
+            path: document_path(&row.chapter, &row.slug),
+            snippet: snippet(&row.body, &terms),
+            chapter: row.chapter,
+            title: row.title,
+            rank: row.rank,
+        })
+        .collect();
+
+    Ok(results)
+}
+
+/*
+검색 결과 미리보기
+    -> 처음 일치한 위치 조금 앞부터 SNIPPET_LENGTH 글자를 자르고, 검색어와 일치하는 부분을 <mark>로 감싼다.
+    -> 한글 bigram은 겹쳐서 일치하므로 글자 단위로 표시한 뒤 이어진 글자를 하나의 <mark>로 묶는다.
+*/
+pub fn snippet(text: &str, terms: &[String]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let lowered: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let terms: Vec<Vec<char>> = terms.iter().map(|term| term.chars().collect()).collect();
+
+    let mut marked = vec![false; chars.len()];
+    for start in 0..lowered.len() {
+        for term in &terms {
+            if lowered[start..].starts_with(term) {
+                marked[start..start + term.len()].iter_mut().for_each(|mark| *mark = true);
+            }
+        }
+    }
+
+    let first = marked.iter().position(|mark| *mark).unwrap_or(0);
+    let start = first.saturating_sub(SNIPPET_LEADING);
+    let end = (start + SNIPPET_LENGTH).min(chars.len());
+
+    let mut html = String::new();
+    if start > 0 {
+        html.push('…');
+    }
+    let mut in_mark = false;
+    for index in start..end {
+        if marked[index] != in_mark {
+            html.push_str(if marked[index] { "<mark>" } else { "</mark>" });
+            in_mark = marked[index];
+        }
+        html.push_str(&htmlescape::encode_minimal(&chars[index].to_string()));
+    }
+    if in_mark {
+        html.push_str("</mark>");
+    }
+    if end < chars.len() {
+        html.push('…');
+    }
+    html
+}
@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+use anyhow::Context;
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::documents::{document_path, Document};
+
+//관련 문서 상자에 보여줄 문서 수
+const RELATED_LIMIT: usize = 3;
+//관련도 = 공유 태그 수 + 검색어 유사도 * SIMILARITY_WEIGHT (+ 같은 챕터면 SAME_CHAPTER_BONUS)
+const SIMILARITY_WEIGHT: f32 = 2.0;
+const SAME_CHAPTER_BONUS: f32 = 0.5;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Tag {
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+}
+
+impl Tag {
+    //GET /tags/{slug}
+    pub fn path(&self) -> String {
+        format!("/tags/{}", self.slug)
+    }
+}
+
+//태그 목록 / count는 게시된 문서 수
+#[derive(Debug, Serialize)]
+pub struct TagCount {
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelatedDocument {
+    pub title: String,
+    pub path: String,
+    pub chapter: String,
+    //겹치는 태그 이름
+    pub shared_tags: Vec<String>,
+    pub score: f32,
+}
+
+//태그 slug는 URL에 들어가므로 영문 소문자, 숫자, - 만 허용한다.
+pub fn is_valid_tag(slug: &str) -> bool {
+    !slug.is_empty() && slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+#[tracing::instrument(name = "List tags", skip(pool))]
+pub async fn list_tags(pool: &PgPool) -> Result<Vec<TagCount>, anyhow::Error> {
+    sqlx::query_as!(
+        TagCount,
+        r#"
+        SELECT t.slug, t.name, t.description, COUNT(d.id) AS "count!"
+        FROM tags t
+        LEFT JOIN document_tags dt ON dt.tag = t.slug
+        LEFT JOIN documents d ON d.id = dt.document_id AND d.published_revision IS NOT NULL
+        GROUP BY t.slug, t.name, t.description
+        ORDER BY t.name
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list tags")
+}
+
+#[tracing::instrument(name = "Find tag", skip(pool))]
+pub async fn find_tag(slug: &str, pool: &PgPool) -> Result<Option<Tag>, anyhow::Error> {
+    sqlx::query_as!(Tag, "SELECT slug, name, description FROM tags WHERE slug = $1", slug)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to query tag")
+}
+
+#[tracing::instrument(name = "Document tags", skip(pool))]
+pub async fn document_tags(document_id: Uuid, pool: &PgPool) -> Result<Vec<Tag>, anyhow::Error> {
+    sqlx::query_as!(
+        Tag,
+        r#"
+        SELECT t.slug, t.name, t.description
+        FROM document_tags dt
+        JOIN tags t ON t.slug = dt.tag
+        WHERE dt.document_id = $1
+        ORDER BY t.name
+        "#,
+        document_id
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to query document tags")
+}
+
+//이 태그가 붙은 문서 id (목차를 태그로 거를 때)
+#[tracing::instrument(name = "Tagged document ids", skip(pool))]
+pub async fn tagged_document_ids(tag: &str, pool: &PgPool) -> Result<HashSet<Uuid>, anyhow::Error> {
+    let ids = sqlx::query_scalar!("SELECT document_id FROM document_tags WHERE tag = $1", tag)
+        .fetch_all(pool)
+        .await
+        .context("Failed to query tagged documents")?;
+
+    Ok(ids.into_iter().collect())
+}
+
+/*
+문서의 태그를 바꾼다. (편집기)
+    -> 태그는 리비전에 포함하지 않는 분류 정보라서 검토 없이 바로 반영된다.
+    -> 없는 태그는 slug를 이름으로 새로 만든다.
+*/
+#[tracing::instrument(name = "Set document tags", skip(pool))]
+pub async fn set_document_tags(pool: &PgPool, document_id: Uuid, tags: &[String]) -> Result<(), anyhow::Error> {
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+    sqlx::query!("DELETE FROM document_tags WHERE document_id = $1", document_id)
+        .execute(&mut transaction)
+        .await
+        .context("Failed to clear document tags")?;
+    for tag in tags {
+        sqlx::query!("INSERT INTO tags (slug, name) VALUES ($1, $1) ON CONFLICT (slug) DO NOTHING", tag)
+            .execute(&mut transaction)
+            .await
+            .context("Failed to insert tag")?;
+        sqlx::query!(
+            "INSERT INTO document_tags (document_id, tag) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            document_id,
+            tag
+        )
+        .execute(&mut transaction)
+        .await
+        .context("Failed to tag document")?;
+    }
+    transaction.commit().await.context("Failed to commit document tags")?;
+
+    Ok(())
+}
+
+/*
+관련 문서 (문서 하단 "함께 보면 좋은 문서")
+    -> 공유 태그 수와 검색 색인 단어의 유사도(Jaccard)로 점수를 매긴다. 같은 챕터의 문서는 조금 더 앞에 둔다.
+    -> 게시된(검색 색인에 있는) 문서만 후보가 된다.
+*/
+#[tracing::instrument(name = "Related documents", skip(pool, document))]
+pub async fn related_documents(pool: &PgPool, document: &Document) -> Result<Vec<RelatedDocument>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT s.document_id, s.chapter, s.slug, s.title,
+            tsvector_to_array(s.search_vector) AS "terms!",
+            ARRAY(
+                SELECT t.name FROM document_tags mine
+                JOIN document_tags theirs ON theirs.tag = mine.tag
+                JOIN tags t ON t.slug = mine.tag
+                WHERE mine.document_id = $1 AND theirs.document_id = s.document_id
+                ORDER BY t.name
+            ) AS "shared_tags!"
+        FROM document_search s
+        "#,
+        document.id
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to query related documents")?;
+
+    let Some(current) = rows.iter().find(|row| row.document_id == document.id) else {
+        return Ok(Vec::new());
+    };
+    let current_terms: HashSet<&str> = current.terms.iter().map(String::as_str).collect();
+
+    let mut related: Vec<RelatedDocument> = rows
+        .iter()
+        .filter(|row| row.document_id != document.id)
+        .map(|row| {
+            let terms: HashSet<&str> = row.terms.iter().map(String::as_str).collect();
+            let mut score = row.shared_tags.len() as f32 + jaccard(&current_terms, &terms) * SIMILARITY_WEIGHT;
+            if row.chapter == document.chapter {
+                score += SAME_CHAPTER_BONUS;
+            }
+            RelatedDocument {
+                title: row.title.clone(),
+                path: document_path(&row.chapter, &row.slug),
+                chapter: row.chapter.clone(),
+                shared_tags: row.shared_tags.clone(),
+                score,
+            }
+        })
+        .filter(|related| !related.shared_tags.is_empty() || related.score > SAME_CHAPTER_BONUS)
+        .collect();
+    related.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    related.truncate(RELATED_LIMIT);
+
+    Ok(related)
+}
+
+//두 단어 집합의 겹치는 정도 (0 ~ 1)
+pub fn jaccard(a: &HashSet<&str>, b: &HashSet<&str>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
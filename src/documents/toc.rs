@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use serde::Serialize;
+use uuid::Uuid;
+use crate::documents::{Document, Heading, ReadingProgress, RenderCache};
+
+/*
+목차
+    구역(section)      ex) 1. Tracing
+    └ 챕터(chapter)    ex) 1.1 Tracing 기초 (챕터의 첫 번째 문서)
+      └ 항목(entry)    -> 첫 번째 문서의 ## 제목들, 챕터의 나머지 문서들
+        └ 링크(link)   -> 항목 아래의 ### 제목 / 나머지 문서의 ## 제목
+    -> 번호는 chapter 식별자(ch{구역}_{챕터})에서 만든다. (ch1_2 -> 구역 1, 챕터 1.2)
+*/
+#[derive(Debug, Serialize)]
+pub struct TableOfContents {
+    pub sections: Vec<TocSection>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TocSection {
+    pub number: String,
+    pub title: String,
+    pub chapters: Vec<TocChapter>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TocChapter {
+    pub number: String,
+    pub chapter: String,
+    pub title: String,
+    pub path: String,
+    //챕터 첫 문서를 읽기 시작했는지 / 끝까지 읽었는지
+    pub read: bool,
+    pub completed: bool,
+    //챕터의 문서 중 끝까지 읽은 문서 비율(%)
+    pub progress: u8,
+    pub entries: Vec<TocEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TocEntry {
+    pub title: String,
+    pub path: String,
+    //문서 항목이면 읽기 시작 / 완료 여부, 제목 항목이면 None
+    pub read: Option<bool>,
+    pub completed: Option<bool>,
+    pub children: Vec<TocLink>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TocLink {
+    pub title: String,
+    pub path: String,
+}
+
+//ch1_2 -> (1, 2) / 형식이 다르면 None (목차 맨 뒤에 번호 없이 표시)
+pub fn chapter_number(chapter: &str) -> Option<(u32, u32)> {
+    let (part, number) = chapter.strip_prefix("ch")?.split_once('_')?;
+    Some((part.parse().ok()?, number.parse().ok()?))
+}
+
+//progress : 로그인한 사용자의 문서별 진행 상황 (로그인하지 않았으면 비어 있다.)
+pub fn build_toc(documents: &[Document], cache: &RenderCache, progress: &HashMap<Uuid, ReadingProgress>) -> TableOfContents {
+    let completed = |document: &Document| progress.get(&document.id).is_some_and(ReadingProgress::is_completed);
+
+    //챕터별로 묶는다. (documents는 chapter, position 순서)
+    let mut chapters: Vec<Vec<&Document>> = Vec::new();
+    for document in documents {
+        match chapters.last_mut() {
+            Some(chapter) if chapter[0].chapter == document.chapter => chapter.push(document),
+            _ => chapters.push(vec![document]),
+        }
+    }
+    chapters.sort_by(|a, b| {
+        let key = |c: &Vec<&Document>| (chapter_number(&c[0].chapter).is_none(), chapter_number(&c[0].chapter), c[0].chapter.clone());
+        key(a).cmp(&key(b))
+    });
+
+    let mut sections: Vec<TocSection> = Vec::new();
+    for chapter in chapters {
+        let main = chapter[0];
+        let (section_number, number) = match chapter_number(&main.chapter) {
+            Some((part, number)) => (part.to_string(), format!("{}.{}", part, number)),
+            None => (String::new(), String::new()),
+        };
+
+        let rendered = cache.render(main);
+        let mut entries = heading_entries(&main.path(), &rendered.headings);
+        for document in &chapter[1..] {
+            let rendered = cache.render(document);
+            entries.push(TocEntry {
+                title: document.title.clone(),
+                path: document.path(),
+                read: Some(progress.contains_key(&document.id)),
+                completed: Some(completed(document)),
+                children: rendered.headings.iter()
+                    .filter(|heading| heading.level == 2)
+                    .map(|heading| TocLink { title: heading.text.clone(), path: format!("{}#{}", document.path(), heading.anchor) })
+                    .collect(),
+            });
+        }
+        let toc_chapter = TocChapter {
+            number,
+            chapter: main.chapter.clone(),
+            title: main.title.clone(),
+            path: main.path(),
+            read: progress.contains_key(&main.id),
+            completed: completed(main),
+            progress: (chapter.iter().filter(|&&document| completed(document)).count() * 100 / chapter.len()) as u8,
+            entries,
+        };
+
+        match sections.last_mut() {
+            Some(section) if section.number == section_number && section.title == main.section => section.chapters.push(toc_chapter),
+            _ => sections.push(TocSection {
+                number: section_number,
+                title: main.section.clone(),
+                chapters: vec![toc_chapter],
+            }),
+        }
+    }
+
+    TableOfContents { sections }
+}
+
+//챕터 첫 문서의 ## 제목은 항목으로, 그 아래 ### 제목은 하위 링크로
+fn heading_entries(path: &str, headings: &[Heading]) -> Vec<TocEntry> {
+    let mut entries: Vec<TocEntry> = Vec::new();
+    for heading in headings {
+        let link = format!("{}#{}", path, heading.anchor);
+        match heading.level {
+            2 => entries.push(TocEntry { title: heading.text.clone(), path: link, read: None, completed: None, children: Vec::new() }),
+            3 => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.children.push(TocLink { title: heading.text.clone(), path: link });
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
@@ -0,0 +1,255 @@
+use std::time::Duration;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::documents::index_document;
+
+//예약 게시 확인 주기
+const PUBLISH_SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewDecision {
+    Approve,
+    RequestChanges,
+}
+
+impl ReviewDecision {
+    //document_reviews.decision
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewDecision::Approve => "approved",
+            ReviewDecision::RequestChanges => "changes_requested",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentReview {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub revision: i32,
+    pub reviewer: String,
+    pub decision: String,
+    pub comment: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/*
+문서 게시 흐름
+    draft ──제출──> in_review ──승인──> published
+      ^                │  └──승인(publish_at이 미래)──> scheduled ──스케줄러──> published
+      └──수정 요청─────┘
+    -> 어느 상태에서든 저장하면 draft가 되고, 보관(archived)하면 방문자에게 보이지 않는다.
+    -> 상태 변경 결과 / InvalidState면 지금 상태에서는 할 수 없는 변경이다.
+*/
+#[derive(Debug, PartialEq)]
+pub enum TransitionOutcome {
+    Changed { status: String },
+    InvalidState { status: String },
+    //검토한 리비전 이후에 다시 저장되었다.
+    Conflict { current_revision: i32 },
+    NotFound,
+}
+
+//draft(또는 archived) -> in_review / publish_at을 지정하면 승인 후 그 시각에 게시된다.
+#[tracing::instrument(name = "Submit document for review", skip(pool))]
+pub async fn submit_for_review(
+    pool: &PgPool,
+    id: Uuid,
+    publish_at: Option<DateTime<Utc>>,
+) -> Result<TransitionOutcome, anyhow::Error> {
+    let changed = sqlx::query_scalar!(
+        r#"
+        UPDATE documents
+        SET status = 'in_review', publish_at = $2
+        WHERE id = $1 AND status IN ('draft', 'archived')
+        RETURNING status
+        "#,
+        id,
+        publish_at
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to submit document")?;
+
+    match changed {
+        Some(status) => Ok(TransitionOutcome::Changed { status }),
+        None => current_state(pool, id, None).await,
+    }
+}
+
+/*
+검토 (승인 / 수정 요청)
+    -> 검토자가 본 리비전(revision)이 지금 리비전과 같고 in_review일 때만 처리한다.
+    -> 승인 : publish_at이 없거나 지났으면 바로 게시, 아니면 scheduled
+    -> 수정 요청 : draft로 돌려보낸다.
+*/
+#[tracing::instrument(name = "Review document", skip(pool, comment))]
+pub async fn review_document(
+    pool: &PgPool,
+    id: Uuid,
+    revision: i32,
+    decision: ReviewDecision,
+    comment: &str,
+    reviewer: &str,
+) -> Result<TransitionOutcome, anyhow::Error> {
+    let now = Utc::now();
+    let mut transaction = pool.begin().await.context("Failed to begin transaction")?;
+    let changed = match decision {
+        ReviewDecision::Approve => sqlx::query_scalar!(
+            r#"
+            UPDATE documents
+            SET status = CASE WHEN publish_at IS NULL OR publish_at <= $3 THEN 'published' ELSE 'scheduled' END,
+                published_revision = CASE WHEN publish_at IS NULL OR publish_at <= $3 THEN revision ELSE published_revision END,
+                updated_at = $3
+            WHERE id = $1 AND revision = $2 AND status = 'in_review'
+            RETURNING status
+            "#,
+            id,
+            revision,
+            now
+        )
+        .fetch_optional(&mut transaction)
+        .await,
+        ReviewDecision::RequestChanges => sqlx::query_scalar!(
+            r#"
+            UPDATE documents
+            SET status = 'draft'
+            WHERE id = $1 AND revision = $2 AND status = 'in_review'
+            RETURNING status
+            "#,
+            id,
+            revision
+        )
+        .fetch_optional(&mut transaction)
+        .await,
+    }
+    .context("Failed to review document")?;
+
+    let Some(status) = changed else {
+        return current_state(pool, id, Some(revision)).await;
+    };
+    sqlx::query!(
+        r#"
+        INSERT INTO document_reviews (id, document_id, revision, reviewer, decision, comment, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+        Uuid::new_v4(),
+        id,
+        revision,
+        reviewer,
+        decision.as_str(),
+        comment,
+        now
+    )
+    .execute(&mut transaction)
+    .await
+    .context("Failed to insert document review")?;
+    transaction.commit().await.context("Failed to commit document review")?;
+    index_document(pool, id).await?;
+
+    Ok(TransitionOutcome::Changed { status })
+}
+
+//보관 / 게시된 리비전을 내려서 방문자에게 보이지 않게 한다. (리비전 기록은 남는다.)
+#[tracing::instrument(name = "Archive document", skip(pool))]
+pub async fn archive_document(pool: &PgPool, id: Uuid) -> Result<TransitionOutcome, anyhow::Error> {
+    let changed = sqlx::query_scalar!(
+        r#"
+        UPDATE documents
+        SET status = 'archived', published_revision = NULL, publish_at = NULL, updated_at = $2
+        WHERE id = $1 AND status <> 'archived'
+        RETURNING status
+        "#,
+        id,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to archive document")?;
+
+    match changed {
+        Some(status) => {
+            index_document(pool, id).await?;
+            Ok(TransitionOutcome::Changed { status })
+        }
+        None => current_state(pool, id, None).await,
+    }
+}
+
+//상태를 바꾸지 못했을 때 이유를 찾는다.
+async fn current_state(pool: &PgPool, id: Uuid, revision: Option<i32>) -> Result<TransitionOutcome, anyhow::Error> {
+    let row = sqlx::query!("SELECT status, revision FROM documents WHERE id = $1", id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to query document status")?;
+
+    Ok(match row {
+        None => TransitionOutcome::NotFound,
+        Some(row) if revision.is_some_and(|revision| revision != row.revision) => {
+            TransitionOutcome::Conflict { current_revision: row.revision }
+        }
+        Some(row) => TransitionOutcome::InvalidState { status: row.status },
+    })
+}
+
+//승인된 예약 문서 중 publish_at이 지난 문서를 게시한다. / 게시한 문서 id
+#[tracing::instrument(name = "Publish due documents", skip(pool))]
+pub async fn publish_due_documents(pool: &PgPool) -> Result<Vec<Uuid>, anyhow::Error> {
+    let now = Utc::now();
+    let published = sqlx::query_scalar!(
+        r#"
+        UPDATE documents
+        SET status = 'published', published_revision = revision, updated_at = $1
+        WHERE status = 'scheduled' AND publish_at <= $1
+        RETURNING id
+        "#,
+        now
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to publish scheduled documents")?;
+    for id in &published {
+        index_document(pool, *id).await?;
+    }
+
+    Ok(published)
+}
+
+//예약 게시 스케줄러 / 서버가 여러 대여도 UPDATE 한 문장으로 처리하므로 같은 문서를 두 번 게시하지 않는다.
+pub fn spawn_publish_scheduler(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PUBLISH_SCHEDULER_INTERVAL);
+        loop {
+            interval.tick().await;
+            match publish_due_documents(&pool).await {
+                Ok(published) if !published.is_empty() => {
+                    tracing::info!(count = published.len(), "Published scheduled documents");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!(error.cause_chain = ?e, "Failed to publish scheduled documents"),
+            }
+        }
+    });
+}
+
+//최근 검토부터
+#[tracing::instrument(name = "List document reviews", skip(pool))]
+pub async fn list_reviews(document_id: Uuid, pool: &PgPool) -> Result<Vec<DocumentReview>, anyhow::Error> {
+    sqlx::query_as!(
+        DocumentReview,
+        r#"
+        SELECT id, document_id, revision, reviewer, decision, comment, created_at
+        FROM document_reviews
+        WHERE document_id = $1
+        ORDER BY created_at DESC
+        "#,
+        document_id
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list document reviews")
+}
@@ -1,5 +1,5 @@
-use actix_web::HttpResponse;
-use actix_web::http::header::LOCATION;
+use actix_web::{HttpResponse, ResponseError};
+use actix_web::http::{header::LOCATION, StatusCode};
 
 //#[derive(thiserror::Error)] : rust 표준 라이브러리의 std::error::Error트레이트 구현을 자동화한다.
 #[derive(thiserror::Error)]
@@ -16,6 +16,8 @@ pub enum ApiError {
     JoinError(#[from] tokio::task::JoinError),
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
     #[error("InternalServerError: {0}")]
     InternalServerError(String)
 }
@@ -40,14 +42,141 @@ pub enum JwtError {
     //missing refresh token
     #[error("Refresh token missing in cookie")]
     MissingRefreshToken,
-    //redis 통신 오류 (서버 문제)
-    #[error("Redis error : {0}")]
-    RedisError(String),
+    //토큰 저장소(Redis / Postgres) 통신 오류 (서버 문제)
+    #[error("Token store error : {0}")]
+    StoreError(String),
     //기타 jwt 관련된 에러
     #[error("Other jwt error : {0}")]
     Other(String),
 }
 
+//OAuth2 엔드포인트 오류 / RFC 6749 5.2 형식({"error", "error_description"})으로 응답한다.
+#[derive(thiserror::Error)]
+#[error("{error}: {description}")]
+pub struct OAuthError {
+    pub error: &'static str,
+    pub description: String,
+    pub status: StatusCode,
+}
+
+impl OAuthError {
+    pub fn invalid_request(description: impl Into<String>) -> Self {
+        Self { error: "invalid_request", description: description.into(), status: StatusCode::BAD_REQUEST }
+    }
+
+    pub fn invalid_client(description: impl Into<String>) -> Self {
+        Self { error: "invalid_client", description: description.into(), status: StatusCode::UNAUTHORIZED }
+    }
+
+    pub fn invalid_grant(description: impl Into<String>) -> Self {
+        Self { error: "invalid_grant", description: description.into(), status: StatusCode::BAD_REQUEST }
+    }
+
+    pub fn invalid_scope(description: impl Into<String>) -> Self {
+        Self { error: "invalid_scope", description: description.into(), status: StatusCode::BAD_REQUEST }
+    }
+
+    pub fn unauthorized_client(description: impl Into<String>) -> Self {
+        Self { error: "unauthorized_client", description: description.into(), status: StatusCode::BAD_REQUEST }
+    }
+
+    pub fn unsupported_grant_type(description: impl Into<String>) -> Self {
+        Self { error: "unsupported_grant_type", description: description.into(), status: StatusCode::BAD_REQUEST }
+    }
+
+    pub fn server_error(e: impl std::fmt::Display) -> Self {
+        Self { error: "server_error", description: e.to_string(), status: StatusCode::INTERNAL_SERVER_ERROR }
+    }
+}
+
+impl ResponseError for OAuthError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        //서버 내부 오류의 상세 내용은 클라이언트에 노출하지 않는다.
+        let description = if self.status.is_server_error() {
+            "Internal server error".to_string()
+        } else {
+            self.description.clone()
+        };
+        HttpResponse::build(self.status)
+            .insert_header(("Cache-Control", "no-store"))
+            .json(serde_json::json!({
+                "error": self.error,
+                "error_description": description,
+            }))
+    }
+}
+
+/*
+재인증 필요 / 로그인은 되어 있지만 마지막 인증 시각이 오래된 경우
+    -> 클라이언트는 reauth 경로로 비밀번호를 다시 보낸 뒤 원래 요청을 재시도한다.
+*/
+#[derive(thiserror::Error)]
+#[error("Re-authentication required within {max_age_seconds} seconds")]
+pub struct ReauthRequired {
+    pub max_age_seconds: i64,
+}
+
+impl ResponseError for ReauthRequired {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNAUTHORIZED
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "reauth_required",
+            "max_age": self.max_age_seconds,
+            "reauth": "/api/reauth",
+        }))
+    }
+}
+
+/*
+작업 증명 챌린지 필요 / 챌린지 풀이가 없거나 틀린 경우
+    -> 클라이언트는 challenge 경로에서 새 챌린지를 받아 풀고 pow_challenge, pow_nonce를 함께 다시 보낸다.
+*/
+#[derive(thiserror::Error)]
+#[error("Proof-of-work challenge required for {purpose}")]
+pub struct ChallengeRequired {
+    pub purpose: &'static str,
+}
+
+impl ResponseError for ChallengeRequired {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::PRECONDITION_REQUIRED
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(serde_json::json!({
+            "success": false,
+            "error": "challenge_required",
+            "message": "자동 입력 방지 확인이 필요합니다.",
+            "challenge": format!("/api/challenge?purpose={}", self.purpose),
+        }))
+    }
+}
+
+impl std::fmt::Debug for ChallengeRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+impl std::fmt::Debug for ReauthRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+impl std::fmt::Debug for OAuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
 impl std::fmt::Debug for ApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         error_chain_fmt(self, f)
@@ -92,6 +221,20 @@ where
         actix_web::error::ErrorUnauthorized(e)
     }
 
+//403 Forbidden을 반환(권한 부족 등)
+pub fn e403<T>(e: T) -> actix_web::Error
+where 
+    T: std::fmt::Debug + std::fmt::Display + 'static {
+        actix_web::error::ErrorForbidden(e)
+    }
+
+//404 Not Found를 반환(설정에 없는 공급자 등)
+pub fn e404<T>(e: T) -> actix_web::Error
+where 
+    T: std::fmt::Debug + std::fmt::Display + 'static {
+        actix_web::error::ErrorNotFound(e)
+    }
+
 //로깅을 위해 오류의 근본 원인은 유지한면서 불투명한 500을 반환한다.
 pub fn e500<T>(e: T) -> actix_web::Error
 where
@@ -3,4 +3,8 @@ pub mod configuration;
 pub mod startup;
 pub mod telemetry;
 pub mod error;
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod audit;
+pub mod mailer;
+pub mod login_history;
+pub mod documents;
\ No newline at end of file
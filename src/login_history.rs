@@ -0,0 +1,192 @@
+use actix_web::{web, HttpRequest};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::mailer::{EmailMessage, Mailer};
+
+//계정 화면에 보여주는 최근 로그인 기록 개수
+pub const LOGIN_HISTORY_LIMIT: i64 = 10;
+
+/*
+로그인 기록
+    -> 로그인에 성공할 때마다 IP, User-Agent, 대략적인 기기 식별값을 login_history에 남긴다.
+    -> 처음 보는 기기에서 로그인하면 메일로 알려서 계정 탈취를 빨리 알아챌 수 있게 한다. (첫 로그인은 제외)
+    -> 기기 식별값은 User-Agent의 브라우저 / OS / 기기 종류만 사용한다. 버전이나 IP가 바뀌어도 같은 기기로 본다.
+*/
+#[derive(Debug)]
+pub struct LoginRecord {
+    email: String,
+    method: String,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    device: String,
+    fingerprint: String,
+}
+
+impl LoginRecord {
+    //method : 로그인 수단 (password, magic_link, oidc, passkey)
+    pub fn from_request(req: &HttpRequest, email: impl Into<String>, method: impl Into<String>) -> Self {
+        let user_agent = req
+            .headers()
+            .get(actix_web::http::header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_owned());
+        let device = device_label(user_agent.as_deref().unwrap_or_default());
+        let fingerprint = hex::encode(Sha256::digest(device.as_bytes()))[..16].to_string();
+
+        Self {
+            email: email.into(),
+            method: method.into(),
+            ip: req.peer_addr().map(|addr| addr.ip().to_string()),
+            user_agent,
+            device,
+            fingerprint,
+        }
+    }
+
+    /*
+    기록 후 새 기기면 알림 메일을 보낸다.
+        -> 기록은 계정 화면에 바로 보여야 하므로 기다리고, 메일은 백그라운드로 보낸다.
+        -> 어느 쪽이 실패해도 로그인은 막지 않고 에러 로그만 남긴다.
+    */
+    pub async fn record(self, pool: &PgPool, mailer: &web::Data<dyn Mailer>) {
+        let new_device = match insert_login_record(pool, &self).await {
+            Ok(new_device) => new_device,
+            Err(e) => {
+                tracing::error!(error.cause_chain = ?e, "Failed to record login history");
+                return;
+            }
+        };
+        if !new_device {
+            return;
+        }
+
+        let mailer = mailer.clone();
+        tokio::spawn(async move {
+            let message = EmailMessage {
+                to: self.email.clone(),
+                subject: "새 기기에서 로그인했습니다".to_string(),
+                body: format!(
+                    "처음 보는 기기에서 계정에 로그인했습니다.\n\n기기 : {}\nIP : {}\n로그인 수단 : {}\n시각 : {}\n\n본인이 아니라면 바로 비밀번호를 변경하세요.",
+                    self.device,
+                    self.ip.as_deref().unwrap_or("알 수 없음"),
+                    self.method,
+                    Utc::now().to_rfc2822(),
+                ),
+            };
+            if let Err(e) = mailer.send(&message).await {
+                tracing::error!(error.cause_chain = ?e, "Failed to send new device notification");
+            }
+        });
+    }
+}
+
+//"Chrome on Windows (desktop)" 형태의 기기 이름 / 버전은 버린다.
+pub fn device_label(user_agent: &str) -> String {
+    //Edge, Opera는 Chrome, Safari를 함께 표기하므로 먼저 확인한다.
+    let browser = [
+        ("Edg/", "Edge"), ("OPR/", "Opera"), ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"), ("Safari/", "Safari"), ("curl/", "curl"),
+    ]
+    .iter()
+    .find(|(token, _)| user_agent.contains(token))
+    .map(|(_, name)| *name)
+    .unwrap_or("Unknown browser");
+    let os = [
+        ("Windows", "Windows"), ("Android", "Android"), ("iPhone", "iOS"), ("iPad", "iOS"),
+        ("Mac OS X", "macOS"), ("CrOS", "ChromeOS"), ("Linux", "Linux"),
+    ]
+    .iter()
+    .find(|(token, _)| user_agent.contains(token))
+    .map(|(_, name)| *name)
+    .unwrap_or("Unknown OS");
+    let class = if user_agent.contains("Mobi") || user_agent.contains("iPhone") { "mobile" } else { "desktop" };
+
+    format!("{} on {} ({})", browser, os, class)
+}
+
+//새 기기 여부를 반환한다. (이전 기록이 있는데 같은 기기 식별값이 없으면 새 기기)
+#[tracing::instrument(name = "Insert login history", skip(pool, record), fields(email = %record.email))]
+async fn insert_login_record(
+    pool: &PgPool,
+    record: &LoginRecord,
+) -> Result<bool, anyhow::Error> {
+    let seen = sqlx::query!(
+        r#"
+        SELECT
+            EXISTS(SELECT 1 FROM login_history WHERE email = $1) AS "has_history!",
+            EXISTS(SELECT 1 FROM login_history WHERE email = $1 AND device_fingerprint = $2) AS "known_device!"
+        "#,
+        record.email,
+        record.fingerprint
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to query login history")?;
+    let new_device = seen.has_history && !seen.known_device;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO login_history (id, email, method, ip, user_agent, device_fingerprint, device, new_device, logged_in_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+        Uuid::new_v4(),
+        record.email,
+        record.method,
+        record.ip,
+        record.user_agent,
+        record.fingerprint,
+        record.device,
+        new_device,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .context("Failed to insert login history")?;
+
+    Ok(new_device)
+}
+
+//계정 화면에 보여주는 로그인 기록 한 줄
+#[derive(Debug)]
+pub struct LoginHistoryEntry {
+    pub logged_in_at: DateTime<Utc>,
+    pub method: String,
+    pub ip: String,
+    pub device: String,
+    pub new_device: bool,
+}
+
+#[tracing::instrument(name = "Login history query", skip(pool))]
+pub async fn recent_logins(
+    email: &str,
+    pool: &PgPool,
+) -> Result<Vec<LoginHistoryEntry>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT logged_in_at, method, ip, device, new_device
+        FROM login_history
+        WHERE email = $1
+        ORDER BY logged_in_at DESC
+        LIMIT $2
+        "#,
+        email,
+        LOGIN_HISTORY_LIMIT
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to query login history")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| LoginHistoryEntry {
+            logged_in_at: row.logged_in_at,
+            method: row.method,
+            ip: row.ip.unwrap_or_default(),
+            device: row.device,
+            new_device: row.new_device,
+        })
+        .collect())
+}
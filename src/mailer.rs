@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+use crate::configuration::EmailSettings;
+
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/*
+메일 발송 추상화
+    -> 핸들러는 web::Data<dyn Mailer>만 알고, 실제 구현(로컬 outbox, SMTP 등)은 startup에서 선택한다.
+*/
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, message: &EmailMessage) -> Result<(), anyhow::Error>;
+}
+
+//로컬 개발/테스트용 구현체 / 실제로 발송하지 않고 outbox 디렉토리에 .eml 파일로 저장한다.
+#[derive(Debug, Clone)]
+pub struct LocalOutboxMailer {
+    pub dir: PathBuf,
+    pub sender: String,
+}
+
+impl LocalOutboxMailer {
+    pub fn new(settings: &EmailSettings) -> Self {
+        Self {
+            dir: PathBuf::from(&settings.outbox_dir),
+            sender: settings.sender.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for LocalOutboxMailer {
+    #[tracing::instrument(name = "Write email to local outbox", skip(self, message), fields(to = %message.to))]
+    async fn send(&self, message: &EmailMessage) -> Result<(), anyhow::Error> {
+        std::fs::create_dir_all(&self.dir).context("Failed to create outbox directory")?;
+        //파일명 : {발송시각}_{uuid}.eml -> 정렬하면 발송 순서가 된다.
+        let filename = format!("{}_{}.eml", Utc::now().format("%Y%m%d%H%M%S%.f"), Uuid::new_v4());
+        let content = format!(
+            "From: {}\nTo: {}\nSubject: {}\nDate: {}\n\n{}\n",
+            self.sender,
+            message.to,
+            message.subject,
+            Utc::now().to_rfc2822(),
+            message.body
+        );
+        std::fs::write(self.dir.join(filename), content).context("Failed to write email to outbox")?;
+
+        Ok(())
+    }
+}
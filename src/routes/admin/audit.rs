@@ -0,0 +1,146 @@
+use actix_web::{
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web, HttpRequest, HttpResponse, Result,
+};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+use crate::auth::{JwtService, TypedSession};
+use crate::error::e500;
+use crate::routes::admin::guard::require_admin;
+
+//한 번에 조회 가능한 최대 건수 (JSON / CSV)
+const MAX_PAGE_SIZE: i64 = 500;
+const MAX_EXPORT_SIZE: i64 = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    //RFC 3339 형식 (ex. 2025-10-21T00:00:00Z)
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct AuditEventRow {
+    pub id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub actor: Option<String>,
+    pub action: String,
+    pub target: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    pub details: serde_json::Value,
+}
+
+//GET /admin/audit_events : 필터 조건으로 감사 로그를 JSON으로 조회한다.
+pub async fn list_audit_events(
+    req: HttpRequest,
+    query: web::Query<AuditQuery>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_admin(&req, &session, &jwt_service, &pool).await?;
+    let limit = query.limit.unwrap_or(100).clamp(1, MAX_PAGE_SIZE);
+    let events = query_audit_events(&pool, &query, limit).await.map_err(e500)?;
+
+    Ok(HttpResponse::Ok().json(events))
+}
+
+//GET /admin/audit_events/export : 같은 필터 조건으로 CSV 파일을 내려준다.
+pub async fn export_audit_events(
+    req: HttpRequest,
+    query: web::Query<AuditQuery>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_admin(&req, &session, &jwt_service, &pool).await?;
+    let limit = query.limit.unwrap_or(MAX_EXPORT_SIZE).clamp(1, MAX_EXPORT_SIZE);
+    let events = query_audit_events(&pool, &query, limit).await.map_err(e500)?;
+
+    let mut csv = String::from("id,occurred_at,actor,action,target,ip,user_agent,request_id,details\n");
+    for e in &events {
+        let fields = [
+            e.id.to_string(),
+            e.occurred_at.to_rfc3339(),
+            e.actor.clone().unwrap_or_default(),
+            e.action.clone(),
+            e.target.clone().unwrap_or_default(),
+            e.ip.clone().unwrap_or_default(),
+            e.user_agent.clone().unwrap_or_default(),
+            e.request_id.clone().unwrap_or_default(),
+            e.details.to_string(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename("audit_events.csv".to_string())],
+        })
+        .body(csv))
+}
+
+#[tracing::instrument(name = "Audit Events Query", skip(pool))]
+async fn query_audit_events(
+    pool: &PgPool,
+    query: &AuditQuery,
+    limit: i64,
+) -> Result<Vec<AuditEventRow>, anyhow::Error> {
+    //필터가 선택적이므로 QueryBuilder로 WHERE 절을 조립한다. 값은 모두 push_bind로 바인딩된다.
+    let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
+        "SELECT id, occurred_at, actor, action, target, ip, user_agent, request_id, details FROM audit_events WHERE 1 = 1"
+    );
+    if let Some(actor) = &query.actor {
+        builder.push(" AND actor = ").push_bind(actor.clone());
+    }
+    if let Some(action) = &query.action {
+        builder.push(" AND action = ").push_bind(action.clone());
+    }
+    if let Some(target) = &query.target {
+        builder.push(" AND target = ").push_bind(target.clone());
+    }
+    if let Some(from) = query.from {
+        builder.push(" AND occurred_at >= ").push_bind(from);
+    }
+    if let Some(to) = query.to {
+        builder.push(" AND occurred_at < ").push_bind(to);
+    }
+    builder.push(" ORDER BY occurred_at DESC LIMIT ").push_bind(limit);
+    builder.push(" OFFSET ").push_bind(query.offset.unwrap_or(0).max(0));
+
+    let events = builder
+        .build_query_as::<AuditEventRow>()
+        .fetch_all(pool)
+        .await
+        .context("Failed to perform a query")?;
+
+    Ok(events)
+}
+
+/*
+CSV 필드 인코딩
+    -> 항상 큰따옴표로 감싸고 내부 큰따옴표는 두 번 쓴다.
+    -> 스프레드시트에서 수식으로 해석될 수 있는 값(=, +, -, @ 로 시작)은 앞에 '를 붙인다. (CSV injection 방지)
+*/
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_owned()
+    };
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
@@ -0,0 +1,215 @@
+use actix_web::{http::header::ContentType, web, HttpRequest, HttpResponse, Result};
+use askama::Template;
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::auth::{JwtService, TypedSession};
+use crate::documents::{
+    create_document, document_path, document_tags, find_document, find_document_by_id, is_valid_tag, list_documents,
+    list_reviews, list_revisions, render_markdown, save_document, set_document_tags, Document, DocumentReview,
+    DocumentRevision, NewDocument, RenderCache, SaveOutcome, Tag,
+};
+use crate::error::{e400, e404, e500};
+use crate::routes::admin::guard::{is_granted, require_permission, Permission};
+
+//GET /admin/docs
+#[derive(Template)]
+#[template(path = "admin/documents.html")]
+pub struct DocumentListTemplate {
+    pub documents: Vec<Document>,
+}
+
+//GET /admin/docs/{id}/edit
+#[derive(Template)]
+#[template(path = "admin/document_editor.html")]
+pub struct DocumentEditorTemplate {
+    pub document: Document,
+    pub revisions: Vec<DocumentRevision>,
+    pub reviews: Vec<DocumentReview>,
+    pub tags: Vec<Tag>,
+    //검토(승인 / 수정 요청) 버튼을 보여줄지
+    pub can_review: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveDocumentRequest {
+    pub title: String,
+    pub body: String,
+    //편집을 시작할 때 받은 리비전 / 그 사이 다른 편집자가 저장했으면 409
+    pub revision: i32,
+    #[serde(default)]
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagsRequest {
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewRequest {
+    pub body: String,
+}
+
+//GET /admin/docs : 편집할 문서 목록 (목차 순서)
+pub async fn document_list(
+    req: HttpRequest,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let documents = list_documents(&pool).await.map_err(e500)?;
+    let rendered = DocumentListTemplate { documents }.render().map_err(e500)?;
+
+    Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+}
+
+//GET /admin/docs/{id}/edit : 편집기 (마크다운 입력, 미리보기, 리비전 목록, 게시 상태와 검토)
+pub async fn document_editor(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let email = require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let id = path.into_inner();
+    let document = find_document_by_id(id, &pool).await.map_err(e500)?
+        .ok_or_else(|| e404("Document not found"))?;
+    let template = DocumentEditorTemplate {
+        revisions: list_revisions(id, &pool).await.map_err(e500)?,
+        reviews: list_reviews(id, &pool).await.map_err(e500)?,
+        tags: document_tags(id, &pool).await.map_err(e500)?,
+        can_review: is_granted(&email, Permission::ReviewContent, &pool).await.map_err(e500)?,
+        document,
+    };
+    let rendered = template.render().map_err(e500)?;
+
+    Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+}
+
+//POST /admin/docs : 새 문서 (리비전 1)
+#[tracing::instrument(name = "Create document", skip(req, form, session, jwt_service, pool))]
+pub async fn add_document(
+    req: HttpRequest,
+    form: web::Json<NewDocument>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let author = require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let form = form.0;
+    //chapter, slug는 URL(/docs/{chapter}/{slug})에 그대로 들어간다.
+    if !is_path_segment(&form.chapter) || !is_path_segment(&form.slug) {
+        return Err(e400("chapter and slug must be lowercase letters, digits or _"));
+    }
+    if form.title.trim().is_empty() {
+        return Err(e400("title must not be empty"));
+    }
+    if find_document(&form.chapter, &form.slug, &pool).await.map_err(e500)?.is_some() {
+        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "success": false,
+            "error": "document_exists",
+        })));
+    }
+
+    let id = create_document(&pool, &form, &author).await.map_err(e500)?;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({
+        "id": id,
+        "revision": 1,
+        "path": document_path(&form.chapter, &form.slug),
+    })))
+}
+
+/*
+PUT /admin/docs/{id} : 저장
+    -> 요청의 revision이 현재 리비전과 같을 때만 저장하고 새 리비전 번호를 돌려준다.
+    -> 다르면 409와 현재 리비전 번호 / 편집기는 덮어쓰지 않고 사용자에게 다시 불러오라고 알린다.
+*/
+#[tracing::instrument(name = "Save document", skip(req, form, session, jwt_service, pool, cache))]
+pub async fn update_document(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    form: web::Json<SaveDocumentRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+    cache: web::Data<RenderCache>,
+) -> Result<HttpResponse> {
+    let author = require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let id = path.into_inner();
+    let form = form.0;
+    if form.title.trim().is_empty() {
+        return Err(e400("title must not be empty"));
+    }
+
+    let outcome = save_document(&pool, id, form.revision, &form.title, &form.body, &author, &form.message)
+        .await
+        .map_err(e500)?;
+    saved_response(outcome, id, &cache)
+}
+
+//저장 / 되돌리기 공통 응답
+pub(crate) fn saved_response(outcome: SaveOutcome, id: Uuid, cache: &RenderCache) -> Result<HttpResponse> {
+    match outcome {
+        SaveOutcome::Saved { revision } => {
+            cache.invalidate(id);
+            Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true, "revision": revision })))
+        }
+        SaveOutcome::Conflict { current_revision } => Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "success": false,
+            "error": "conflict",
+            "current_revision": current_revision,
+        }))),
+        SaveOutcome::NotFound => Err(e404("Document not found")),
+    }
+}
+
+//PUT /admin/docs/{id}/tags : 태그 바꾸기 (검토 없이 바로 반영)
+#[tracing::instrument(name = "Update document tags", skip(req, session, jwt_service, pool))]
+pub async fn update_document_tags(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    form: web::Json<TagsRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let id = path.into_inner();
+    let mut tags: Vec<String> = form.0.tags.iter().map(|tag| tag.trim().to_lowercase()).collect();
+    tags.sort();
+    tags.dedup();
+    if let Some(tag) = tags.iter().find(|tag| !is_valid_tag(tag)) {
+        return Err(e400(format!("invalid tag: {}", tag)));
+    }
+    find_document_by_id(id, &pool).await.map_err(e500)?
+        .ok_or_else(|| e404("Document not found"))?;
+
+    set_document_tags(&pool, id, &tags).await.map_err(e500)?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true, "tags": tags })))
+}
+
+//POST /admin/docs/preview : 저장하지 않고 렌더링 결과만 돌려준다. (문서 페이지와 같은 sanitize 적용)
+pub async fn preview_document(
+    req: HttpRequest,
+    form: web::Json<PreviewRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let rendered = render_markdown(&form.body);
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "html": rendered.html,
+        "headings": rendered.headings,
+    })))
+}
+
+fn is_path_segment(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
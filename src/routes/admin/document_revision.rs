@@ -0,0 +1,115 @@
+use actix_web::{http::header::ContentType, web, HttpRequest, HttpResponse, Result};
+use askama::Template;
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::auth::{JwtService, TypedSession};
+use crate::documents::{
+    find_document_by_id, find_revision, list_revisions, save_document, side_by_side, DiffRow, Document,
+    DocumentRevision, RenderCache,
+};
+use crate::error::{e400, e404, e500};
+use crate::routes::admin::document_editor::saved_response;
+use crate::routes::admin::guard::{require_permission, Permission};
+
+//GET /admin/docs/{id}/diff
+#[derive(Template)]
+#[template(path = "admin/document_diff.html")]
+pub struct DocumentDiffTemplate {
+    pub document: Document,
+    pub from: DocumentRevision,
+    pub to: DocumentRevision,
+    pub title_changed: bool,
+    pub rows: Vec<DiffRow>,
+}
+
+//to를 생략하면 현재 리비전과 비교한다.
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    pub from: i32,
+    pub to: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RollbackRequest {
+    //되돌릴 리비전
+    pub revision: i32,
+    //편집기에 열려 있던 리비전 (저장과 같은 낙관적 잠금)
+    pub expected_revision: i32,
+}
+
+//GET /admin/docs/{id}/revisions : 리비전 목록 (최신 순)
+pub async fn document_revisions(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let id = path.into_inner();
+    find_document_by_id(id, &pool).await.map_err(e500)?
+        .ok_or_else(|| e404("Document not found"))?;
+    let revisions = list_revisions(id, &pool).await.map_err(e500)?;
+
+    Ok(HttpResponse::Ok().json(revisions))
+}
+
+//GET /admin/docs/{id}/diff?from=&to= : 두 리비전의 본문을 나란히 비교한다.
+pub async fn document_diff(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    query: web::Query<DiffQuery>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let id = path.into_inner();
+    let document = find_document_by_id(id, &pool).await.map_err(e500)?
+        .ok_or_else(|| e404("Document not found"))?;
+    let from = find_revision(id, query.from, &pool).await.map_err(e500)?
+        .ok_or_else(|| e404("Revision not found"))?;
+    let to = find_revision(id, query.to.unwrap_or(document.revision), &pool).await.map_err(e500)?
+        .ok_or_else(|| e404("Revision not found"))?;
+
+    let template = DocumentDiffTemplate {
+        title_changed: from.title != to.title,
+        rows: side_by_side(&from.body, &to.body),
+        document,
+        from,
+        to,
+    };
+    let rendered = template.render().map_err(e500)?;
+
+    Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+}
+
+/*
+POST /admin/docs/{id}/rollback
+    -> 이전 리비전의 제목과 본문으로 새 리비전을 만든다. (기록은 지우지 않으므로 되돌리기도 다시 되돌릴 수 있다.)
+*/
+#[tracing::instrument(name = "Rollback document", skip(req, form, session, jwt_service, pool, cache))]
+pub async fn rollback_document(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    form: web::Json<RollbackRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+    cache: web::Data<RenderCache>,
+) -> Result<HttpResponse> {
+    let author = require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let id = path.into_inner();
+    if form.revision >= form.expected_revision {
+        return Err(e400("revision must be older than the current revision"));
+    }
+    let target = find_revision(id, form.revision, &pool).await.map_err(e500)?
+        .ok_or_else(|| e404("Revision not found"))?;
+
+    let message = format!("리비전 {}(으)로 되돌림", target.revision);
+    let outcome = save_document(&pool, id, form.expected_revision, &target.title, &target.body, &author, &message)
+        .await
+        .map_err(e500)?;
+    saved_response(outcome, id, &cache)
+}
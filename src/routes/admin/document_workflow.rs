@@ -0,0 +1,113 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::auth::{JwtService, TypedSession};
+use crate::documents::{self, list_reviews, review_document, submit_for_review, ReviewDecision, TransitionOutcome};
+use crate::error::{e400, e404, e500};
+use crate::routes::admin::guard::{require_permission, Permission};
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitRequest {
+    //지정하면 승인되더라도 이 시각까지는 게시하지 않는다.
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewRequest {
+    //검토한 리비전 / 검토하는 동안 다시 저장되었으면 409
+    pub revision: i32,
+    pub decision: ReviewDecision,
+    #[serde(default)]
+    pub comment: String,
+}
+
+//POST /admin/docs/{id}/submit : 검토 요청
+#[tracing::instrument(name = "Submit document", skip(req, form, session, jwt_service, pool))]
+pub async fn submit_document(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    form: web::Json<SubmitRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let outcome = submit_for_review(&pool, path.into_inner(), form.publish_at).await.map_err(e500)?;
+    transition_response(outcome)
+}
+
+/*
+POST /admin/docs/{id}/reviews : 승인 또는 수정 요청
+    -> content:review 권한이 필요하다.
+    -> 수정 요청에는 무엇을 고쳐야 하는지 comment가 있어야 한다.
+*/
+#[tracing::instrument(name = "Add document review", skip(req, form, session, jwt_service, pool))]
+pub async fn add_document_review(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    form: web::Json<ReviewRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let reviewer = require_permission(&req, &session, &jwt_service, &pool, Permission::ReviewContent).await?;
+    let form = form.0;
+    if form.decision == ReviewDecision::RequestChanges && form.comment.trim().is_empty() {
+        return Err(e400("comment is required when requesting changes"));
+    }
+
+    let outcome = review_document(&pool, path.into_inner(), form.revision, form.decision, form.comment.trim(), &reviewer)
+        .await
+        .map_err(e500)?;
+    transition_response(outcome)
+}
+
+//GET /admin/docs/{id}/reviews : 검토 기록 (최근 순)
+pub async fn document_reviews(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let reviews = list_reviews(path.into_inner(), &pool).await.map_err(e500)?;
+
+    Ok(HttpResponse::Ok().json(reviews))
+}
+
+//POST /admin/docs/{id}/archive : 보관 (방문자에게 보이지 않게 한다.)
+#[tracing::instrument(name = "Archive document", skip(req, session, jwt_service, pool))]
+pub async fn archive_document(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_permission(&req, &session, &jwt_service, &pool, Permission::EditContent).await?;
+    let outcome = documents::archive_document(&pool, path.into_inner()).await.map_err(e500)?;
+    transition_response(outcome)
+}
+
+fn transition_response(outcome: TransitionOutcome) -> Result<HttpResponse> {
+    match outcome {
+        TransitionOutcome::Changed { status } => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "status": status,
+        }))),
+        TransitionOutcome::InvalidState { status } => Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "success": false,
+            "error": "invalid_state",
+            "status": status,
+        }))),
+        TransitionOutcome::Conflict { current_revision } => Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "success": false,
+            "error": "conflict",
+            "current_revision": current_revision,
+        }))),
+        TransitionOutcome::NotFound => Err(e404("Document not found")),
+    }
+}
@@ -0,0 +1,48 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use crate::audit::{AuditAction, AuditEvent};
+use crate::auth::{JwtService, TypedSession};
+use crate::error::{e400, e500};
+use crate::routes::admin::guard::{require_recent_admin, user_role_query};
+
+#[derive(Debug, Deserialize)]
+pub struct ForceLogoutRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForceLogoutResponse {
+    pub success: bool,
+    //폐기된 access token 개수
+    pub revoked: usize,
+}
+
+//관리자 강제 로그아웃 / 대상 사용자의 access token을 폐기 목록에 올리고 refresh token을 모두 삭제한다.
+#[tracing::instrument(
+    name = "Force logout",
+    skip(req, form, session, jwt_service, pool),
+    fields(target = %form.email)
+)]
+pub async fn force_logout(
+    req: HttpRequest,
+    form: web::Json<ForceLogoutRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let actor = require_recent_admin(&req, &session, &jwt_service, &pool).await?;
+    let target = form.0.email;
+
+    if user_role_query(&target, &pool).await.map_err(e500)?.is_none() {
+        return Err(e400("No such user"));
+    }
+    let revoked = jwt_service.revoke_all_tokens(&target).await.map_err(e500)?;
+    AuditEvent::from_request(AuditAction::ForceLogout, &req)
+        .actor(actor)
+        .target(target)
+        .details(serde_json::json!({"revoked_access_tokens": revoked}))
+        .record(&pool);
+
+    Ok(HttpResponse::Ok().json(ForceLogoutResponse { success: true, revoked }))
+}
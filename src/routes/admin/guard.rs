@@ -0,0 +1,150 @@
+use actix_web::HttpRequest;
+use anyhow::Context;
+use sqlx::PgPool;
+use crate::auth::{JwtService, RequireRecentAuth, TypedSession};
+use crate::error::{e401, e403, e500, ApiError};
+
+/*
+관리자 권한 확인
+    -> 세션 모드면 세션의 email, JWT 모드면 access token의 email로 users.role을 조회한다.
+    -> 대리 로그인(impersonation) 중인 세션/토큰으로는 관리자 기능을 사용할 수 없다.
+*/
+pub async fn require_admin(
+    req: &HttpRequest,
+    session: &TypedSession,
+    jwt_service: &JwtService,
+    pool: &PgPool,
+) -> Result<String, actix_web::Error> {
+    let email = acting_email(req, session, jwt_service).await?;
+
+    match user_role_query(&email, pool).await.map_err(e500)? {
+        Some(role) if role == "admin" => Ok(email),
+        _ => Err(e403(ApiError::Forbidden("Admin role required".into()))),
+    }
+}
+
+//관리자가 아닌 사용자에게 나눠 줄 수 있는 권한 (user_permissions.permission)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Permission {
+    //문서 편집기 (/admin/docs)
+    EditContent,
+    //문서 검토 (승인 / 수정 요청)
+    ReviewContent,
+}
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::EditContent => "content:edit",
+            Permission::ReviewContent => "content:review",
+        }
+    }
+}
+
+//관리자는 모든 권한을 가진다. 그 외 사용자는 user_permissions에 부여된 권한만 사용할 수 있다.
+pub async fn require_permission(
+    req: &HttpRequest,
+    session: &TypedSession,
+    jwt_service: &JwtService,
+    pool: &PgPool,
+    permission: Permission,
+) -> Result<String, actix_web::Error> {
+    let email = acting_email(req, session, jwt_service).await?;
+    match is_granted(&email, permission, pool).await.map_err(e500)? {
+        true => Ok(email),
+        false => Err(e403(ApiError::Forbidden(format!("Permission required: {}", permission.as_str())))),
+    }
+}
+
+//관리 기능을 요청한 사용자의 email / 대리 로그인 중이면 403
+async fn acting_email(
+    req: &HttpRequest,
+    session: &TypedSession,
+    jwt_service: &JwtService,
+) -> Result<String, actix_web::Error> {
+    match session.get_email().map_err(e500)? {
+        Some(email) => {
+            if session.get_impersonation().map_err(e500)?.is_some() {
+                return Err(e403(ApiError::Forbidden("Impersonation session cannot use admin features".into())));
+            }
+            Ok(email)
+        }
+        None => {
+            let token = jwt_service.extract_access_token(req)
+                .ok_or_else(|| e401(ApiError::Unauthorized("Login required".into())))?;
+            let claims = jwt_service.verify_access_token(&token).await
+                .map_err(|e| e401(ApiError::Unauthorized(e.to_string())))?;
+            if claims.act.is_some() {
+                return Err(e403(ApiError::Forbidden("Impersonation token cannot use admin features".into())));
+            }
+            Ok(claims.email)
+        }
+    }
+}
+
+//상태를 바꾸는 관리자 기능(대리 로그인, 강제 로그아웃, OAuth 클라이언트 등록)은 최근에 인증한 경우에만 허용한다.
+pub async fn require_recent_admin(
+    req: &HttpRequest,
+    session: &TypedSession,
+    jwt_service: &JwtService,
+    pool: &PgPool,
+) -> Result<String, actix_web::Error> {
+    let email = require_admin(req, session, jwt_service, pool).await?;
+    RequireRecentAuth(jwt_service.policy.reauth_window())
+        .check(req, session, jwt_service)
+        .await?;
+    Ok(email)
+}
+
+#[tracing::instrument(name = "User Role Query", skip(pool))]
+pub async fn user_role_query(
+    email: &str,
+    pool: &PgPool,
+) -> Result<Option<String>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT role
+        FROM users
+        WHERE email = $1
+        "#,
+        email
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to perform a query")?
+    .map(|row| row.role);
+
+    Ok(row)
+}
+
+//관리자이거나 권한을 부여받았는지 (화면에서 버튼을 보여줄지 정할 때도 사용)
+pub async fn is_granted(
+    email: &str,
+    permission: Permission,
+    pool: &PgPool,
+) -> Result<bool, anyhow::Error> {
+    if user_role_query(email, pool).await?.as_deref() == Some("admin") {
+        return Ok(true);
+    }
+    has_permission(email, permission, pool).await
+}
+
+#[tracing::instrument(name = "User Permission Query", skip(pool))]
+pub async fn has_permission(
+    email: &str,
+    permission: Permission,
+    pool: &PgPool,
+) -> Result<bool, anyhow::Error> {
+    let granted = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM user_permissions WHERE email = $1 AND permission = $2) AS "granted!"
+        "#,
+        email,
+        permission.as_str()
+    )
+    .fetch_one(pool)
+    .await
+    .context("Failed to perform a query")?;
+
+    Ok(granted)
+}
@@ -0,0 +1,91 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use crate::audit::{AuditAction, AuditEvent};
+use crate::auth::{AccessTokenClaims, JwtService, TypedSession, ACCESS_TOKEN_COOKIE, IMPERSONATION_MINUTES};
+use crate::error::{e400, e500, see_other};
+use crate::routes::admin::guard::{require_recent_admin, user_role_query};
+
+#[derive(Debug, Deserialize)]
+pub struct ImpersonateRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImpersonateResponse {
+    pub success: bool,
+    pub redirect: String,
+}
+
+/*
+대리 로그인 시작
+    -> 세션 모드 : 관리자 세션에 대리 대상 email과 만료 시각을 추가한다.
+    -> JWT 모드 : act 클레임이 포함된 access token으로 쿠키를 교체한다. refresh token은 관리자 것을 그대로 두므로
+                  대리 토큰이 만료되면 다음 요청에서 관리자 본인으로 자동 복귀한다.
+*/
+#[tracing::instrument(
+    name = "Start impersonation",
+    skip(req, form, session, jwt_service, pool),
+    fields(subject = %form.email)
+)]
+pub async fn start_impersonation(
+    req: HttpRequest,
+    form: web::Json<ImpersonateRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let actor = require_recent_admin(&req, &session, &jwt_service, &pool).await?;
+    let subject = form.0.email;
+
+    if user_role_query(&subject, &pool).await.map_err(e500)?.is_none() {
+        return Err(e400("No such user"));
+    }
+    tracing::info!(actor = %actor, "Impersonation started");
+    AuditEvent::from_request(AuditAction::ImpersonationStart, &req)
+        .actor(actor.clone())
+        .target(subject.clone())
+        .record(&pool);
+
+    if session.get_email().map_err(e500)?.is_some() {
+        let expires_at = Utc::now().timestamp() + IMPERSONATION_MINUTES * 60;
+        session.start_impersonation(subject, expires_at).map_err(e500)?;
+
+        return Ok(HttpResponse::Ok().json(ImpersonateResponse {
+            success: true,
+            redirect: "/home_session".to_string(),
+        }));
+    }
+
+    let token = jwt_service.create_impersonation_token(&subject, &actor).await.map_err(e500)?;
+    let access_cookie = jwt_service.cookies.access_token(token, Duration::minutes(IMPERSONATION_MINUTES));
+
+    Ok(HttpResponse::Ok().cookie(access_cookie).json(ImpersonateResponse {
+        success: true,
+        redirect: "/home_jwt".to_string(),
+    }))
+}
+
+//대리 로그인 종료 / success.html 배너의 form에서 호출되므로 홈으로 리다이렉트한다.
+pub async fn stop_impersonation(
+    req: HttpRequest,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+) -> Result<HttpResponse> {
+    if session.get_impersonation().map_err(e500)?.is_some() {
+        session.stop_impersonation();
+        return Ok(see_other("/home_session"));
+    }
+
+    let mut response = see_other("/home_jwt");
+    if let Some(token) = jwt_service.extract_access_token(&req) {
+        if let Ok(claims @ AccessTokenClaims { act: Some(_), .. }) = jwt_service.verify_access_token(&token).await {
+            //쿠키만 지우면 토큰 자체는 만료 전까지 유효하므로 폐기 목록에 올린다.
+            jwt_service.revoke_access_token(&claims).await.map_err(e500)?;
+            response.add_cookie(&jwt_service.remove_token_cookie(ACCESS_TOKEN_COOKIE)).map_err(e500)?;
+        }
+    }
+
+    Ok(response)
+}
@@ -0,0 +1,130 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use chrono::{DateTime, Duration, Utc};
+use rand::distributions::{Alphanumeric, DistString};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use crate::auth::{JwtService, TypedSession};
+use crate::error::{e400, e500};
+use crate::routes::admin::guard::{require_admin, require_recent_admin};
+use crate::startup::ApplicationBaseUrl;
+
+//초대 코드 길이 / 사람이 옮겨 적을 수 있도록 영숫자만 사용한다.
+const INVITATION_CODE_LENGTH: usize = 16;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInvitationRequest {
+    //지정하면 이 이메일로만 가입할 수 있다.
+    pub email: Option<String>,
+    #[serde(default = "default_max_uses")]
+    pub max_uses: i32,
+    //생략하면 만료되지 않는다.
+    pub expires_in_hours: Option<i64>,
+}
+
+fn default_max_uses() -> i32 {
+    1
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvitationResponse {
+    pub code: String,
+    pub email: Option<String>,
+    pub max_uses: i32,
+    pub uses: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    //가입 화면에 코드가 미리 채워지는 링크
+    pub url: String,
+}
+
+//POST /admin/invitations : 초대 코드를 발급한다.
+#[tracing::instrument(name = "Create invitation", skip(req, form, session, jwt_service, pool, base_url))]
+pub async fn create_invitation(
+    req: HttpRequest,
+    form: web::Json<CreateInvitationRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+    base_url: web::Data<ApplicationBaseUrl>,
+) -> Result<HttpResponse> {
+    let actor = require_recent_admin(&req, &session, &jwt_service, &pool).await?;
+    let form = form.0;
+    if form.max_uses < 1 {
+        return Err(e400("max_uses must be at least 1"));
+    }
+    if matches!(form.expires_in_hours, Some(hours) if hours < 1) {
+        return Err(e400("expires_in_hours must be at least 1"));
+    }
+
+    let code = Alphanumeric.sample_string(&mut rand::thread_rng(), INVITATION_CODE_LENGTH);
+    let expires_at = form.expires_in_hours.map(|hours| Utc::now() + Duration::hours(hours));
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO invitations (code, email, max_uses, expires_at, created_by, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING code, email, max_uses, uses, expires_at, created_by, created_at
+        "#,
+        code,
+        form.email,
+        form.max_uses,
+        expires_at,
+        actor,
+        Utc::now()
+    )
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(e500)?;
+
+    Ok(HttpResponse::Created().json(InvitationResponse {
+        url: invitation_url(&base_url, &row.code),
+        code: row.code,
+        email: row.email,
+        max_uses: row.max_uses,
+        uses: row.uses,
+        expires_at: row.expires_at,
+        created_by: row.created_by,
+        created_at: row.created_at,
+    }))
+}
+
+//GET /admin/invitations : 발급한 초대 코드 목록 (최근 발급 순)
+pub async fn list_invitations(
+    req: HttpRequest,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+    base_url: web::Data<ApplicationBaseUrl>,
+) -> Result<HttpResponse> {
+    require_admin(&req, &session, &jwt_service, &pool).await?;
+    let rows = sqlx::query!(
+        r#"
+        SELECT code, email, max_uses, uses, expires_at, created_by, created_at
+        FROM invitations
+        ORDER BY created_at DESC
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(e500)?;
+
+    let invitations: Vec<InvitationResponse> = rows
+        .into_iter()
+        .map(|row| InvitationResponse {
+            url: invitation_url(&base_url, &row.code),
+            code: row.code,
+            email: row.email,
+            max_uses: row.max_uses,
+            uses: row.uses,
+            expires_at: row.expires_at,
+            created_by: row.created_by,
+            created_at: row.created_at,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(invitations))
+}
+
+fn invitation_url(base_url: &ApplicationBaseUrl, code: &str) -> String {
+    format!("{}/registration?invite={}", base_url.0, code)
+}
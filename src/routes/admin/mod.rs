@@ -0,0 +1,33 @@
+mod audit;
+mod document_editor;
+mod document_revision;
+mod document_workflow;
+mod force_logout;
+mod guard;
+mod impersonation;
+mod invitation;
+
+pub use audit::export_audit_events;
+pub use audit::list_audit_events;
+pub use document_editor::add_document;
+pub use document_editor::document_editor;
+pub use document_editor::document_list;
+pub use document_editor::preview_document;
+pub use document_editor::update_document;
+pub use document_editor::update_document_tags;
+pub use document_revision::document_diff;
+pub use document_revision::document_revisions;
+pub use document_revision::rollback_document;
+pub use document_workflow::add_document_review;
+pub use document_workflow::archive_document;
+pub use document_workflow::document_reviews;
+pub use document_workflow::submit_document;
+pub use force_logout::force_logout;
+pub use guard::{require_admin, user_role_query};
+pub use guard::require_permission;
+pub use guard::Permission;
+pub use guard::require_recent_admin;
+pub use impersonation::start_impersonation;
+pub use impersonation::stop_impersonation;
+pub use invitation::create_invitation;
+pub use invitation::list_invitations;
@@ -0,0 +1,88 @@
+use actix_web::{http::header::ContentType, web, HttpRequest, HttpResponse, Result};
+use askama::Template;
+use sqlx::PgPool;
+use std::sync::Arc;
+use crate::auth::{JwtService, TypedSession};
+use crate::documents::{
+    document_tags, find_published_document, link_glossary_terms, list_glossary_terms, list_published_documents, mark_read,
+    neighbours, related_documents, Document, ReadingProgress, RelatedDocument, RenderCache, RenderedMarkdown, Tag,
+};
+use crate::error::e500;
+use crate::routes::current_user_email;
+use crate::startup::NotFoundTemplate;
+
+//이전 / 다음 문서 링크
+pub struct DocumentLink {
+    pub title: String,
+    pub path: String,
+}
+
+impl From<&Document> for DocumentLink {
+    fn from(document: &Document) -> Self {
+        Self { title: document.title.clone(), path: document.path() }
+    }
+}
+
+//모든 문서가 공통으로 사용하는 레이아웃 (templates/docs/layout.html)
+#[derive(Template)]
+#[template(path = "docs/document.html")]
+pub struct DocumentTemplate {
+    pub document: Document,
+    //sanitize된 본문 HTML과 제목 목록
+    pub rendered: Arc<RenderedMarkdown>,
+    //용어 링크를 넣은 본문 HTML
+    pub body: String,
+    pub previous: Option<DocumentLink>,
+    pub next: Option<DocumentLink>,
+    pub tags: Vec<Tag>,
+    //함께 보면 좋은 문서 (공유 태그 + 검색어 유사도)
+    pub related: Vec<RelatedDocument>,
+    //로그인한 사용자의 진행 상황 / 있으면 읽는 동안 진행 상황을 보고하고, "이어서 읽기"로 오면 읽던 위치로 이동한다.
+    pub progress: Option<ReadingProgress>,
+}
+
+/*
+GET /docs/{chapter}/{slug}
+    -> documents 테이블의 마크다운 문서를 HTML로 변환해서 공통 레이아웃으로 렌더링한다.
+    -> 없거나 아직 게시되지 않은 문서면 404 페이지
+    -> 로그인한 사용자는 읽기 시작으로 기록한다. (읽은 위치, 시간, 완료는 페이지가 /api/progress로 보고한다.)
+    -> 용어집의 용어가 처음 나오는 곳은 용어 설명 링크가 된다.
+*/
+#[tracing::instrument(name = "Render document", skip(req, session, jwt_service, pool, cache))]
+pub async fn document(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+    cache: web::Data<RenderCache>,
+) -> Result<HttpResponse> {
+    let (chapter, slug) = path.into_inner();
+    let Some(document) = find_published_document(&chapter, &slug, &pool).await.map_err(e500)? else {
+        let rendered = NotFoundTemplate.render().map_err(e500)?;
+        return Ok(HttpResponse::NotFound().content_type(ContentType::html()).body(rendered));
+    };
+
+    let progress = match current_user_email(&req, &session, &jwt_service).await {
+        Some(email) => Some(mark_read(&email, document.id, &pool).await.map_err(e500)?),
+        None => None,
+    };
+
+    let documents = list_published_documents(&pool).await.map_err(e500)?;
+    let (previous, next) = neighbours(&documents, &document);
+    let rendered = cache.render(&document);
+    let glossary = list_glossary_terms(&pool).await.map_err(e500)?;
+    let template = DocumentTemplate {
+        body: link_glossary_terms(&rendered.html, &glossary),
+        rendered,
+        tags: document_tags(document.id, &pool).await.map_err(e500)?,
+        related: related_documents(&pool, &document).await.map_err(e500)?,
+        previous: previous.map(DocumentLink::from),
+        next: next.map(DocumentLink::from),
+        progress,
+        document,
+    };
+    let rendered = template.render().map_err(e500)?;
+
+    Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+}
@@ -0,0 +1,5 @@
+mod document;
+mod progress;
+
+pub use document::document;
+pub use progress::report_progress;
\ No newline at end of file
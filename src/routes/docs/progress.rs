@@ -0,0 +1,50 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::auth::{JwtService, TypedSession};
+use crate::documents::{find_published_document_by_id, record_progress};
+use crate::error::{e400, e401, e404, e500};
+use crate::routes::current_user_email;
+
+#[derive(Debug, Deserialize)]
+pub struct ProgressReport {
+    //지금 보고 있는 위치 (본문을 읽은 비율 0 ~ 1)
+    pub scroll: f32,
+    //지난 보고 이후 문서를 보고 있던 시간(초)
+    #[serde(default)]
+    pub seconds: i32,
+    //본문 끝까지 읽었는지
+    #[serde(default)]
+    pub completed: bool,
+}
+
+/*
+POST /api/progress/{document_id}
+    -> 문서 페이지가 읽는 동안(30초마다, 페이지를 떠날 때) 보내는 진행 상황을 기록한다.
+    -> 세션 로그인과 JWT(access token 쿠키) 로그인 모두 사용할 수 있다. 로그인하지 않았으면 401
+*/
+#[tracing::instrument(name = "Report reading progress", skip(req, session, jwt_service, pool))]
+pub async fn report_progress(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    form: web::Json<ProgressReport>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let email = current_user_email(&req, &session, &jwt_service).await
+        .ok_or_else(|| e401("Login required"))?;
+    let id = path.into_inner();
+    if !form.scroll.is_finite() || !(0.0..=1.0).contains(&form.scroll) {
+        return Err(e400("scroll must be between 0 and 1"));
+    }
+    find_published_document_by_id(id, &pool).await.map_err(e500)?
+        .ok_or_else(|| e404("Document not found"))?;
+
+    let progress = record_progress(&pool, &email, id, form.scroll, form.seconds, form.completed)
+        .await
+        .map_err(e500)?;
+
+    Ok(HttpResponse::Ok().json(progress))
+}
@@ -0,0 +1,161 @@
+use actix_web::{http::header::ContentType, web, HttpRequest, HttpResponse, Result};
+use anyhow::Context;
+use askama::Template;
+use serde::Deserialize;
+use sqlx::PgPool;
+use crate::auth::{JwtService, TypedSession};
+use crate::documents::{find_glossary_term, render_markdown, GlossaryTerm};
+use crate::error::{e400, e404, e500};
+use crate::routes::current_user_email;
+
+/*
+팝업 조각(fragment) 목록 / 여기 있는 이름만 GET /fragments/{name}으로 렌더링한다.
+    -> templates 디렉터리를 그대로 내보내지 않고, Askama로 렌더링한 결과만 돌려준다.
+    -> 조각은 문서 레이아웃 없이 showPopup이 #popup-basic 안에 넣는 popup-overlay 마크업이다.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fragment {
+    //로그인한 사용자 정보 / 로그인 링크
+    Account,
+    //용어 설명 (?term={slug})
+    GlossaryTerm,
+}
+
+impl Fragment {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "account" => Some(Fragment::Account),
+            "glossary-term" => Some(Fragment::GlossaryTerm),
+            _ => None,
+        }
+    }
+}
+
+//화면 언어 / Accept-Language에서 지원하는 언어를 순서대로 찾고, 없으면 한국어
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    Ko,
+    En,
+}
+
+impl Locale {
+    pub fn from_request(req: &HttpRequest) -> Self {
+        let header = req
+            .headers()
+            .get(actix_web::http::header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        Self::from_accept_language(header)
+    }
+
+    //"en-US,en;q=0.9,ko;q=0.8" -> En / q 값은 보지 않고 브라우저가 나열한 순서를 따른다.
+    pub fn from_accept_language(header: &str) -> Self {
+        header
+            .split(',')
+            .filter_map(|entry| {
+                let language = entry.split(';').next()?.trim();
+                let primary = language.split('-').next()?.to_ascii_lowercase();
+                match primary.as_str() {
+                    "ko" => Some(Locale::Ko),
+                    "en" => Some(Locale::En),
+                    _ => None,
+                }
+            })
+            .next()
+            .unwrap_or(Locale::Ko)
+    }
+
+    //<html lang="">
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Ko => "ko",
+            Locale::En => "en",
+        }
+    }
+
+    //템플릿 문구 / {{ locale.pick("닫기", "Close") }}
+    pub fn pick<'a>(&self, ko: &'a str, en: &'a str) -> &'a str {
+        match self {
+            Locale::Ko => ko,
+            Locale::En => en,
+        }
+    }
+}
+
+//모든 조각이 받는 공통 값
+pub struct FragmentContext {
+    //로그인한 사용자 (대리 로그인 중이면 없음)
+    pub user: Option<FragmentUser>,
+    pub locale: Locale,
+}
+
+pub struct FragmentUser {
+    pub email: String,
+    pub nickname: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FragmentQuery {
+    pub term: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "fragments/account.html")]
+struct AccountTemplate {
+    context: FragmentContext,
+}
+
+#[derive(Template)]
+#[template(path = "fragments/glossary_term.html")]
+struct GlossaryTermTemplate {
+    context: FragmentContext,
+    term: GlossaryTerm,
+    //렌더링된 정의(HTML)
+    definition: String,
+}
+
+/*
+GET /fragments/{name}
+    -> 허용 목록에 없는 이름은 404 (경로가 아니라 이름으로만 찾으므로 ../ 등으로 다른 파일을 읽을 수 없다.)
+    -> 사용자와 언어에 따라 내용이 달라지므로 캐시하지 않는다.
+*/
+#[tracing::instrument(name = "Render fragment", skip(req, query, session, jwt_service, pool))]
+pub async fn fragment(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<FragmentQuery>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let fragment = Fragment::from_name(&path.into_inner()).ok_or_else(|| e404("Fragment not found"))?;
+    let user = match current_user_email(&req, &session, &jwt_service).await {
+        Some(email) => find_fragment_user(&email, &pool).await.map_err(e500)?,
+        None => None,
+    };
+    let context = FragmentContext { user, locale: Locale::from_request(&req) };
+
+    let rendered = match fragment {
+        Fragment::Account => AccountTemplate { context }.render(),
+        Fragment::GlossaryTerm => {
+            let slug = query.term.as_deref().ok_or_else(|| e400("term is required"))?;
+            let term = find_glossary_term(slug, &pool).await.map_err(e500)?
+                .ok_or_else(|| e404("Glossary term not found"))?;
+            let definition = render_markdown(&term.definition).html;
+            GlossaryTermTemplate { context, term, definition }.render()
+        }
+    }
+    .map_err(e500)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .insert_header(("Cache-Control", "no-store"))
+        .body(rendered))
+}
+
+async fn find_fragment_user(email: &str, pool: &PgPool) -> Result<Option<FragmentUser>, anyhow::Error> {
+    sqlx::query_as!(FragmentUser, "SELECT email, nickname FROM users WHERE email = $1", email)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to query fragment user")
+}
@@ -0,0 +1,4 @@
+mod fragment;
+
+pub use fragment::fragment;
+pub use fragment::Locale;
@@ -0,0 +1,4 @@
+mod term;
+
+pub use term::glossary_json;
+pub use term::glossary_list;
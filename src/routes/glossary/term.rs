@@ -0,0 +1,38 @@
+use actix_web::{http::header::ContentType, web, HttpResponse, Result};
+use askama::Template;
+use sqlx::PgPool;
+use crate::documents::{list_glossary_terms, render_markdown, GlossaryTerm};
+use crate::error::e500;
+
+//용어와 렌더링된 정의(HTML)
+struct GlossaryEntry {
+    term: GlossaryTerm,
+    definition: String,
+}
+
+impl From<GlossaryTerm> for GlossaryEntry {
+    fn from(term: GlossaryTerm) -> Self {
+        Self { definition: render_markdown(&term.definition).html, term }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "glossary/list.html")]
+struct GlossaryTemplate {
+    entries: Vec<GlossaryEntry>,
+}
+
+//GET /glossary : 모든 용어 (용어 링크는 /glossary#{slug}로 온다.)
+pub async fn glossary_list(pool: web::Data<PgPool>) -> Result<HttpResponse> {
+    let entries = list_glossary_terms(&pool).await.map_err(e500)?.into_iter().map(GlossaryEntry::from).collect();
+    let rendered = GlossaryTemplate { entries }.render().map_err(e500)?;
+
+    Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+}
+
+//GET /api/glossary : 모든 용어 (정의는 마크다운)
+pub async fn glossary_json(pool: web::Data<PgPool>) -> Result<HttpResponse> {
+    let terms = list_glossary_terms(&pool).await.map_err(e500)?;
+
+    Ok(HttpResponse::Ok().json(terms))
+}
@@ -0,0 +1,26 @@
+use actix_web::{web, HttpResponse, Result};
+use serde::Deserialize;
+use crate::auth::{ChallengePurpose, ProofOfWork};
+use crate::error::e500;
+
+#[derive(Debug, Deserialize)]
+pub struct ChallengeQuery {
+    pub purpose: ChallengePurpose,
+}
+
+/*
+GET /api/challenge?purpose=register|login
+    -> 작업 증명 챌린지를 발급한다. 브라우저(static/js/common/pow.js)가 풀어서 가입 / 로그인 요청에 함께 보낸다.
+    -> 챌린지는 한 번만 쓸 수 있으므로 캐시하지 않는다.
+*/
+#[tracing::instrument(name = "Issue proof-of-work challenge", skip(pow))]
+pub async fn issue_challenge(
+    query: web::Query<ChallengeQuery>,
+    pow: web::Data<ProofOfWork>,
+) -> Result<HttpResponse> {
+    let challenge = pow.issue(query.purpose).await.map_err(e500)?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(challenge))
+}
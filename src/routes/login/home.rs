@@ -7,6 +7,7 @@ use tracing_log::log;
 use crate::error::{e401, e500};
 use crate::auth::{
     JwtService,
+    OidcClient,
     TypedSession,
 };
 use crate::routes::check_token;
@@ -21,23 +22,32 @@ use crate::{
 
 #[derive(Template)]
 #[template(path = "login/home.html")]
-struct HomeTemplate;
+pub struct HomeTemplate {
+    //소셜 로그인 버튼 (name, display_name)
+    pub oidc_providers: Vec<(String, String)>,
+}
 
 pub async fn home_session(
     session: TypedSession,
-    pool: web::Data<PgPool>
+    pool: web::Data<PgPool>,
+    oidc_client: web::Data<OidcClient>,
 ) -> Result<HttpResponse> {
     let email = session.get_email().unwrap_or(None).unwrap_or_default();
+    //대리 로그인 중이면 대상 사용자의 화면을 보여준다.
+    let impersonation = session.get_impersonation().unwrap_or(None);
 
     if email.is_empty() {
-        let template = HomeTemplate;
+        let template = HomeTemplate { oidc_providers: oidc_client.provider_links() };
         let rendered = template.render().map_err(|e| {
             e500(ApiError::InternalServerError(format!("InternalServerError : {}", e)))
         })?;
     
         Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
     }else {
-        get_user_information_session(&email, &pool).await.map_err(|e| e.into())
+        match impersonation {
+            Some((subject, actor)) => get_user_information_session(&subject, &pool, Some(actor)).await.map_err(|e| e.into()),
+            None => get_user_information_session(&email, &pool, None).await.map_err(|e| e.into()),
+        }
     }    
 }
 
@@ -45,37 +55,38 @@ pub async fn home_jwt(
     //HttpRequest는 모든 핸들러에서 자동으로 주입되는 타입이다.
     req: HttpRequest,
     jwt_service: web::Data<JwtService>,
-    pool: web::Data<PgPool>
+    pool: web::Data<PgPool>,
+    oidc_client: web::Data<OidcClient>,
 ) -> Result<HttpResponse> {
-    match check_token(&req, &jwt_service).await.map_err(|e|{
+    match check_token(&req, &jwt_service, &pool).await.map_err(|e|{
         e401(ApiError::Unauthorized(e.to_string()))
     })? {
-        CheckJwtToken::AccessValid { email } => {
-            return get_user_information_jwt(&email, &pool, None, None).await.map_err(|e| e.into())
+        CheckJwtToken::AccessValid { email, actor } => {
+            return get_user_information_jwt(&email, &pool, None, None, actor).await.map_err(|e| e.into())
         }
 
         CheckJwtToken::RefreshValid { email, access_cookie, refresh_cookie } => {
-            return get_user_information_jwt(&email, &pool, Some(access_cookie), Some(refresh_cookie)).await.map_err(|e| e.into())
+            return get_user_information_jwt(&email, &pool, Some(*access_cookie), Some(*refresh_cookie), None).await.map_err(|e| e.into())
         }
 
         CheckJwtToken::Guest => {
-            let template = HomeTemplate;
+            let template = HomeTemplate { oidc_providers: oidc_client.provider_links() };
             let rendered = template.render().map_err(|e| {
                 e500(ApiError::InternalServerError(format!("InternalServerError : {}", e)))
             })?;
         
-            return Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+            Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
         }
 
         CheckJwtToken::InvalidToken => {
             log::warn!("Invalid token detected from request: {:?}", req.peer_addr());
 
-            let template = HomeTemplate;
+            let template = HomeTemplate { oidc_providers: oidc_client.provider_links() };
             let rendered = template.render().map_err(|e| {
                 e500(ApiError::InternalServerError(format!("InternalServerError : {}", e)))
             })?;
         
-            return Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+            Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
         }
     }
 }
@@ -0,0 +1,132 @@
+use actix_web::{error::InternalError, web, HttpRequest, HttpResponse, Result};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use crate::{
+    audit::{AuditAction, AuditEvent},
+    login_history::LoginRecord,
+    auth::{JwtService, TypedSession, MAGIC_LINK_MINUTES},
+    error::{ApiError, JwtError},
+    mailer::{EmailMessage, Mailer},
+    routes::login::process::{
+        get_user_information_jwt, get_user_information_session, issue_jwt_cookies, login_redirect,
+        validate_email_query, LoginMode,
+    },
+    startup::ApplicationBaseUrl,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: String,
+    #[serde(default)]
+    pub mode: LoginMode,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MagicLinkResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkQuery {
+    pub token: String,
+    #[serde(default)]
+    pub mode: LoginMode,
+}
+
+/*
+POST /api/login/magic
+    -> 가입된 이메일이면 1회용 로그인 링크를 메일로 보낸다.
+    -> 가입 여부를 노출하지 않기 위해 결과와 상관없이 같은 응답을 돌려준다.
+*/
+#[tracing::instrument(
+    name = "Send magic link",
+    skip(form, pool, jwt_service, mailer, base_url),
+    fields(email = %form.email)
+)]
+pub async fn send_magic_link(
+    form: web::Json<MagicLinkRequest>,
+    pool: web::Data<PgPool>,
+    jwt_service: web::Data<JwtService>,
+    mailer: web::Data<dyn Mailer>,
+    base_url: web::Data<ApplicationBaseUrl>,
+) -> Result<HttpResponse, InternalError<ApiError>> {
+    let response = HttpResponse::Ok().json(MagicLinkResponse {
+        success: true,
+        message: "가입된 이메일이라면 로그인 링크가 발송되었습니다.".to_string(),
+    });
+
+    match validate_email_query(&form.email, &pool).await {
+        Ok(Some((email, _))) => {
+            let token = jwt_service.create_magic_link_token(&email).await
+                .map_err(|e| login_redirect(ApiError::UnexpectError(anyhow!(e.to_string()))))?;
+            let link = format!(
+                "{}/login/magic?token={}&mode={}",
+                base_url.0,
+                urlencoding::encode(&token),
+                form.mode.as_str()
+            );
+            let message = EmailMessage {
+                to: email,
+                subject: "[Rust Web] 로그인 링크".to_string(),
+                body: format!(
+                    "아래 링크를 클릭하면 로그인됩니다. 링크는 {}분 동안 한 번만 사용할 수 있습니다.\n\n{}",
+                    MAGIC_LINK_MINUTES, link
+                ),
+            };
+            mailer.send(&message).await.map_err(|e| login_redirect(ApiError::from(e)))?;
+
+            Ok(response)
+        }
+        Ok(None) => {
+            tracing::info!("Magic link requested for unknown email");
+            Ok(response)
+        }
+        Err(e) => Err(login_redirect(ApiError::from(e))),
+    }
+}
+
+//GET /login/magic?token=...&mode=... : 링크를 소비하고 validate_session / validate_jwt와 같은 방식으로 로그인시킨다.
+#[tracing::instrument(
+    name = "Consume magic link",
+    skip(req, query, pool, jwt_service, session, mailer)
+)]
+pub async fn consume_magic_link(
+    req: HttpRequest,
+    query: web::Query<MagicLinkQuery>,
+    pool: web::Data<PgPool>,
+    jwt_service: web::Data<JwtService>,
+    session: TypedSession,
+    mailer: web::Data<dyn Mailer>,
+) -> Result<HttpResponse, InternalError<ApiError>> {
+    let claims = match jwt_service.consume_magic_link_token(&query.token).await {
+        Ok(claims) => claims,
+        Err(e) => {
+            if let JwtError::TokenRevoked = e {
+                AuditEvent::from_request(AuditAction::TokenRevoked, &req)
+                    .details(serde_json::json!({"token": "magic_link"}))
+                    .record(&pool);
+            }
+            return Err(login_redirect(ApiError::AuthError(anyhow!(e.to_string()))));
+        }
+    };
+    AuditEvent::from_request(AuditAction::LoginSuccess, &req)
+        .actor(claims.email.clone())
+        .details(serde_json::json!({"mode": query.mode.as_str(), "method": "magic_link"}))
+        .record(&pool);
+    LoginRecord::from_request(&req, claims.email.clone(), "magic_link").record(&pool, &mailer).await;
+
+    match query.mode {
+        LoginMode::Session => {
+            session.renew();
+            session.insert_email(claims.email.clone(), false).map_err(|e| login_redirect(ApiError::UnexpectError(e.into())))?;
+            get_user_information_session(&claims.email, &pool, None).await
+        }
+        LoginMode::Jwt => {
+            let (access_cookie, refresh_cookie) = issue_jwt_cookies(&jwt_service, &pool, &claims.email, false).await
+                .map_err(|e| login_redirect(ApiError::UnexpectError(anyhow!(e.to_string()))))?;
+            get_user_information_jwt(&claims.email, &pool, Some(access_cookie), Some(refresh_cookie), None).await
+        }
+    }
+}
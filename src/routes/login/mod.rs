@@ -1,12 +1,32 @@
+mod challenge;
 mod home;
+mod magic_link;
+mod oidc;
+mod passkey;
+mod password;
 mod process;
+mod reauth;
 mod registration;
 mod validate_jwt;
 mod validate_session;
 
+pub use challenge::issue_challenge;
 pub use home::home_session;
 pub use home::home_jwt;
+pub use magic_link::send_magic_link;
+pub use magic_link::consume_magic_link;
+pub use oidc::oidc_start;
+pub use oidc::oidc_callback;
+pub use passkey::passkey_register_start;
+pub use passkey::passkey_register_finish;
+pub use passkey::passkey_login_start;
+pub use passkey::passkey_login_finish;
+pub use password::change_password;
 pub use process::logout;
+pub use process::current_user_email;
+pub use process::hash_password;
+pub use process::verify_password_hash;
+pub use reauth::reauthenticate;
 pub use registration::registration;
 pub use registration::register;
 pub use validate_session::validate_session;
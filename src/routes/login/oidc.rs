@@ -0,0 +1,235 @@
+use actix_web::{error::InternalError, web, HttpRequest, HttpResponse, Result};
+use anyhow::{anyhow, Context};
+use chrono::Utc;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::{
+    audit::{AuditAction, AuditEvent},
+    login_history::LoginRecord,
+    mailer::Mailer,
+    auth::{pkce_challenge, random_url_token, IdTokenClaims, JwtService, OidcClient, TypedSession},
+    error::{e404, e500, see_other, ApiError},
+    routes::login::{
+        process::{
+            get_user_information_jwt, get_user_information_session, hash_password, issue_jwt_cookies,
+            login_redirect, validate_email_query, LoginMode,
+        },
+        registration::insert_user,
+    },
+    configuration::{RegistrationMode, RegistrationSettings},
+    startup::ApplicationBaseUrl,
+    telemetry::spawn_blocking_with_tracing,
+};
+
+//start에서 세션에 저장하고 callback에서 검증하는 값들
+#[derive(Debug, Serialize, Deserialize)]
+struct OidcFlow {
+    provider: String,
+    state: String,
+    nonce: String,
+    code_verifier: String,
+    mode: LoginMode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcStartQuery {
+    #[serde(default)]
+    pub mode: LoginMode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+fn redirect_uri(base_url: &ApplicationBaseUrl, provider: &str) -> String {
+    format!("{}/auth/oidc/{}/callback", base_url.0, provider)
+}
+
+//GET /auth/oidc/{provider}/start : state/nonce/PKCE를 만들어 세션에 보관하고 공급자 로그인 화면으로 보낸다.
+#[tracing::instrument(name = "Start OIDC login", skip(query, session, oidc_client, base_url))]
+pub async fn oidc_start(
+    provider: web::Path<String>,
+    query: web::Query<OidcStartQuery>,
+    session: TypedSession,
+    oidc_client: web::Data<OidcClient>,
+    base_url: web::Data<ApplicationBaseUrl>,
+) -> Result<HttpResponse> {
+    let settings = oidc_client.provider(&provider).ok_or_else(|| e404("Unknown OIDC provider"))?;
+    let flow = OidcFlow {
+        provider: settings.name.clone(),
+        state: random_url_token(),
+        nonce: random_url_token(),
+        code_verifier: random_url_token(),
+        mode: query.mode,
+    };
+    let url = oidc_client
+        .authorization_url(
+            settings,
+            &redirect_uri(&base_url, &settings.name),
+            &flow.state,
+            &flow.nonce,
+            &pkce_challenge(&flow.code_verifier),
+        )
+        .map_err(e500)?;
+    session.insert_oidc_flow(&flow).map_err(e500)?;
+
+    Ok(see_other(&url))
+}
+
+/*
+GET /auth/oidc/{provider}/callback
+    1. 세션의 state와 비교 (CSRF 방지)
+    2. code + code_verifier로 토큰 교환
+    3. ID 토큰 검증 (JWKS 서명, iss, aud, exp, nonce)
+    4. user_identities로 기존 계정을 찾거나, 검증된 이메일로 연결/생성
+    5. validate_session / validate_jwt와 같은 방식으로 로그인 처리
+*/
+//핸들러 인자는 actix가 주입하는 추출기(extractor)라서, 묶으면 주입받는 값만 숨겨지고 얻는 것이 없다.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "OIDC callback", skip(req, query, session, pool, jwt_service, oidc_client, base_url, mailer, registration))]
+pub async fn oidc_callback(
+    req: HttpRequest,
+    provider: web::Path<String>,
+    query: web::Query<OidcCallbackQuery>,
+    session: TypedSession,
+    pool: web::Data<PgPool>,
+    jwt_service: web::Data<JwtService>,
+    oidc_client: web::Data<OidcClient>,
+    base_url: web::Data<ApplicationBaseUrl>,
+    mailer: web::Data<dyn Mailer>,
+    registration: web::Data<RegistrationSettings>,
+) -> Result<HttpResponse, InternalError<ApiError>> {
+    let auth_error = |message: &str| login_redirect(ApiError::AuthError(anyhow!(message.to_owned())));
+
+    let flow: OidcFlow = session.take_oidc_flow()
+        .map_err(|e| login_redirect(ApiError::UnexpectError(e.into())))?
+        .ok_or_else(|| auth_error("No OIDC login in progress"))?;
+    if let Some(error) = &query.error {
+        return Err(auth_error(&format!("Provider returned an error : {}", error)));
+    }
+    if flow.provider != *provider || query.state.as_deref() != Some(flow.state.as_str()) {
+        return Err(auth_error("Invalid state"));
+    }
+    let code = query.code.as_deref().ok_or_else(|| auth_error("Missing authorization code"))?;
+    let settings = oidc_client.provider(&provider).ok_or_else(|| auth_error("Unknown OIDC provider"))?;
+
+    let tokens = oidc_client
+        .exchange_code(settings, code, &redirect_uri(&base_url, &settings.name), &flow.code_verifier)
+        .await
+        .map_err(|e| login_redirect(ApiError::AuthError(e)))?;
+    let claims = oidc_client
+        .validate_id_token(settings, &tokens.id_token, &flow.nonce)
+        .await
+        .map_err(|e| login_redirect(ApiError::AuthError(e)))?;
+
+    let allow_signup = registration.mode == RegistrationMode::Open;
+    let email = link_or_create_user(&pool, &settings.name, &claims, allow_signup)
+        .await
+        .map_err(|e| login_redirect(ApiError::AuthError(e)))?;
+    AuditEvent::from_request(AuditAction::LoginSuccess, &req)
+        .actor(email.clone())
+        .details(serde_json::json!({"mode": flow.mode.as_str(), "method": "oidc", "provider": settings.name}))
+        .record(&pool);
+    LoginRecord::from_request(&req, email.clone(), "oidc").record(&pool, &mailer).await;
+
+    match flow.mode {
+        LoginMode::Session => {
+            session.renew();
+            session.insert_email(email.clone(), false).map_err(|e| login_redirect(ApiError::UnexpectError(e.into())))?;
+            get_user_information_session(&email, &pool, None).await
+        }
+        LoginMode::Jwt => {
+            let (access_cookie, refresh_cookie) = issue_jwt_cookies(&jwt_service, &pool, &email, false).await
+                .map_err(|e| login_redirect(ApiError::UnexpectError(anyhow!(e.to_string()))))?;
+            get_user_information_jwt(&email, &pool, Some(access_cookie), Some(refresh_cookie), None).await
+        }
+    }
+}
+
+/*
+계정 연결 규칙
+    -> 이미 연결된 (provider, sub)가 있으면 해당 계정
+    -> 공급자가 검증한(email_verified) 이메일이 있으면 같은 이메일 계정에 연결하고, 없으면 새로 만든다.
+    -> 검증되지 않은 이메일로는 기존 계정에 연결하지 않는다. (계정 탈취 방지)
+    -> 가입 방식이 open이 아니면(allow_signup = false) 새 계정은 만들지 않는다.
+*/
+async fn link_or_create_user(
+    pool: &PgPool,
+    provider: &str,
+    claims: &IdTokenClaims,
+    allow_signup: bool,
+) -> Result<String, anyhow::Error> {
+    if let Some(email) = identity_email_query(pool, provider, &claims.sub).await? {
+        return Ok(email);
+    }
+
+    let email = match (&claims.email, claims.email_verified) {
+        (Some(email), Some(true)) => email.clone(),
+        _ => return Err(anyhow!("Provider did not return a verified email")),
+    };
+
+    if validate_email_query(&email, pool).await?.is_none() {
+        if !allow_signup {
+            return Err(anyhow!("Registration is not open"));
+        }
+        let name = claims.name.clone().unwrap_or_else(|| email.clone());
+        //비밀번호 로그인은 사용하지 않으므로 아무도 모르는 무작위 비밀번호로 해시를 만든다.
+        //Argon2 해시는 CPU를 오래 쓰므로 블로킹 스레드에서 실행한다.
+        let password = Secret::new(Uuid::new_v4().to_string());
+        let password_hash = spawn_blocking_with_tracing(move || hash_password(&password))
+            .await
+            .context("Failed to spawn blocking task")??;
+        insert_user(pool, &email, &name, &name, &password_hash).await?;
+    }
+    insert_identity(pool, provider, &claims.sub, &email).await?;
+
+    Ok(email)
+}
+
+#[tracing::instrument(name = "User Identity Query", skip(pool))]
+async fn identity_email_query(
+    pool: &PgPool,
+    provider: &str,
+    subject: &str,
+) -> Result<Option<String>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT email
+        FROM user_identities
+        WHERE provider = $1 AND subject = $2
+        "#,
+        provider, subject
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to perform a query")?
+    .map(|row| row.email);
+
+    Ok(row)
+}
+
+async fn insert_identity(
+    pool: &PgPool,
+    provider: &str,
+    subject: &str,
+    email: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO user_identities (provider, subject, email, created_at)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        provider, subject, email, Utc::now()
+    )
+    .execute(pool)
+    .await
+    .context("Failed to link identity")?;
+
+    Ok(())
+}
+
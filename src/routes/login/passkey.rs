@@ -0,0 +1,291 @@
+use actix_web::{error::InternalError, web, HttpRequest, HttpResponse, Result};
+use anyhow::{anyhow, Context};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+use webauthn_rs::prelude::{
+    CredentialID, Passkey, PasskeyAuthentication, PasskeyRegistration, PublicKeyCredential,
+    RegisterPublicKeyCredential,
+};
+use webauthn_rs::Webauthn;
+use crate::{
+    audit::{AuditAction, AuditEvent},
+    login_history::LoginRecord,
+    mailer::Mailer,
+    auth::{JwtService, TypedSession},
+    error::{e400, e401, e500, ApiError},
+    routes::login::process::{
+        current_user_email, get_user_information_jwt, get_user_information_session, issue_jwt_cookies,
+        login_redirect, LoginMode,
+    },
+};
+
+//등록 ceremony 진행 상태 (세션 저장용)
+#[derive(Serialize, Deserialize)]
+struct RegistrationState {
+    email: String,
+    state: PasskeyRegistration,
+}
+
+//인증 ceremony 진행 상태 (세션 저장용)
+#[derive(Serialize, Deserialize)]
+struct AuthenticationState {
+    email: String,
+    mode: LoginMode,
+    remember: bool,
+    state: PasskeyAuthentication,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasskeyRegisterQuery {
+    //사용자가 구분하기 위한 이름 (ex. "맥북 Touch ID")
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasskeyLoginRequest {
+    pub email: String,
+    #[serde(default)]
+    pub mode: LoginMode,
+    //"로그인 상태 유지"
+    #[serde(default)]
+    pub remember: bool,
+}
+
+//POST /api/webauthn/register/start : 로그인한 사용자의 패스키 등록 challenge 발급
+#[tracing::instrument(name = "Start passkey registration", skip(req, session, jwt_service, pool, webauthn))]
+pub async fn passkey_register_start(
+    req: HttpRequest,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+    webauthn: web::Data<Webauthn>,
+) -> Result<HttpResponse> {
+    let email = current_user_email(&req, &session, &jwt_service).await
+        .ok_or_else(|| e401(ApiError::Unauthorized("Login required".into())))?;
+    let (user_handle, nickname) = user_handle_query(&pool, &email)
+        .await
+        .map_err(e500)?
+        .ok_or_else(|| e401(ApiError::Unauthorized("No such user".into())))?;
+
+    //이미 등록한 인증기로 중복 등록하지 않도록 제외 목록을 보낸다.
+    let exclude: Vec<CredentialID> = passkeys_query(&pool, &email)
+        .await
+        .map_err(e500)?
+        .iter()
+        .map(|p| p.cred_id().clone())
+        .collect();
+    let (challenge, state) = webauthn
+        .start_passkey_registration(user_handle, &email, &nickname, Some(exclude))
+        .map_err(e500)?;
+    session.insert_webauthn_state(&RegistrationState { email, state }).map_err(e500)?;
+
+    Ok(HttpResponse::Ok().json(challenge))
+}
+
+//POST /api/webauthn/register/finish : 인증기 응답을 검증하고 webauthn_credentials에 저장
+#[tracing::instrument(name = "Finish passkey registration", skip(req, credential, session, jwt_service, pool, webauthn))]
+pub async fn passkey_register_finish(
+    req: HttpRequest,
+    query: web::Query<PasskeyRegisterQuery>,
+    credential: web::Json<RegisterPublicKeyCredential>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+    webauthn: web::Data<Webauthn>,
+) -> Result<HttpResponse> {
+    let registration: RegistrationState = session.take_webauthn_state()
+        .map_err(e500)?
+        .ok_or_else(|| e400("No passkey registration in progress"))?;
+    if current_user_email(&req, &session, &jwt_service).await.as_deref() != Some(registration.email.as_str()) {
+        return Err(e401(ApiError::Unauthorized("Login required".into())));
+    }
+
+    let passkey = webauthn
+        .finish_passkey_registration(&credential, &registration.state)
+        .map_err(e400)?;
+    let name = query.0.name.unwrap_or_else(|| format!("Passkey {}", Utc::now().format("%Y-%m-%d")));
+    insert_passkey(&pool, &registration.email, &name, &passkey).await.map_err(e500)?;
+
+    Ok(HttpResponse::Created().json(serde_json::json!({"success": true})))
+}
+
+//POST /api/webauthn/login/start : 이메일에 등록된 패스키로 인증 challenge 발급
+#[tracing::instrument(name = "Start passkey login", skip(form, session, pool, webauthn), fields(email = %form.email))]
+pub async fn passkey_login_start(
+    form: web::Json<PasskeyLoginRequest>,
+    session: TypedSession,
+    pool: web::Data<PgPool>,
+    webauthn: web::Data<Webauthn>,
+) -> Result<HttpResponse, InternalError<ApiError>> {
+    let passkeys = passkeys_query(&pool, &form.email)
+        .await
+        .map_err(|e| login_redirect(ApiError::from(e)))?;
+    //가입하지 않은 이메일과 패스키가 없는 계정을 구분하지 않는다. (계정/패스키 존재 여부 노출 방지)
+    if passkeys.is_empty() {
+        return Err(login_redirect(ApiError::AuthError(anyhow!("No such user"))));
+    }
+
+    let (challenge, state) = webauthn
+        .start_passkey_authentication(&passkeys)
+        .map_err(|e| login_redirect(ApiError::UnexpectError(anyhow!(e.to_string()))))?;
+    let form = form.0;
+    session
+        .insert_webauthn_state(&AuthenticationState { email: form.email, mode: form.mode, remember: form.remember, state })
+        .map_err(|e| login_redirect(ApiError::UnexpectError(e.into())))?;
+
+    Ok(HttpResponse::Ok().json(challenge))
+}
+
+/*
+POST /api/webauthn/login/finish
+    -> 서명 검증 후 서명 카운터를 갱신하고, validate_session / validate_jwt와 같은 방식으로 로그인 처리한다.
+*/
+#[tracing::instrument(name = "Finish passkey login", skip(req, credential, session, pool, jwt_service, webauthn, mailer))]
+pub async fn passkey_login_finish(
+    req: HttpRequest,
+    credential: web::Json<PublicKeyCredential>,
+    session: TypedSession,
+    pool: web::Data<PgPool>,
+    jwt_service: web::Data<JwtService>,
+    webauthn: web::Data<Webauthn>,
+    mailer: web::Data<dyn Mailer>,
+) -> Result<HttpResponse, InternalError<ApiError>> {
+    let authentication: AuthenticationState = session.take_webauthn_state()
+        .map_err(|e| login_redirect(ApiError::UnexpectError(e.into())))?
+        .ok_or_else(|| login_redirect(ApiError::AuthError(anyhow!("No passkey login in progress"))))?;
+    let email = authentication.email;
+
+    let result = match webauthn.finish_passkey_authentication(&credential, &authentication.state) {
+        Ok(result) => result,
+        Err(e) => {
+            AuditEvent::from_request(AuditAction::LoginFailure, &req)
+                .actor(email)
+                .details(serde_json::json!({"mode": authentication.mode.as_str(), "method": "passkey", "reason": e.to_string()}))
+                .record(&pool);
+            return Err(login_redirect(ApiError::AuthError(anyhow!("Passkey verification failed"))));
+        }
+    };
+
+    //서명 카운터 등 인증기 상태 갱신 (복제된 인증기 탐지용)
+    let mut passkeys = passkeys_query(&pool, &email).await.map_err(|e| login_redirect(ApiError::from(e)))?;
+    if let Some(passkey) = passkeys.iter_mut().find(|p| p.cred_id() == result.cred_id()) {
+        passkey.update_credential(&result);
+        update_passkey(&pool, passkey).await.map_err(|e| login_redirect(ApiError::from(e)))?;
+    }
+    AuditEvent::from_request(AuditAction::LoginSuccess, &req)
+        .actor(email.clone())
+        .details(serde_json::json!({"mode": authentication.mode.as_str(), "method": "passkey"}))
+        .record(&pool);
+    LoginRecord::from_request(&req, email.clone(), "passkey").record(&pool, &mailer).await;
+
+    match authentication.mode {
+        LoginMode::Session => {
+            session.renew();
+            session.insert_email(email.clone(), authentication.remember).map_err(|e| login_redirect(ApiError::UnexpectError(e.into())))?;
+            get_user_information_session(&email, &pool, None).await
+        }
+        LoginMode::Jwt => {
+            let (access_cookie, refresh_cookie) = issue_jwt_cookies(&jwt_service, &pool, &email, authentication.remember).await
+                .map_err(|e| login_redirect(ApiError::UnexpectError(anyhow!(e.to_string()))))?;
+            get_user_information_jwt(&email, &pool, Some(access_cookie), Some(refresh_cookie), None).await
+        }
+    }
+}
+
+//CredentialID는 base64url 문자열로 직렬화되므로 그 값을 PK로 사용한다.
+fn credential_key(cred_id: &CredentialID) -> Result<String, anyhow::Error> {
+    serde_json::to_value(cred_id)?
+        .as_str()
+        .map(|s| s.to_owned())
+        .ok_or_else(|| anyhow!("Unexpected credential id format"))
+}
+
+#[tracing::instrument(name = "User Handle Query", skip(pool))]
+async fn user_handle_query(
+    pool: &PgPool,
+    email: &str,
+) -> Result<Option<(Uuid, String)>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT user_handle, nickname
+        FROM users
+        WHERE email = $1
+        "#,
+        email
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to perform a query")?
+    .map(|row| (row.user_handle, row.nickname));
+
+    Ok(row)
+}
+
+#[tracing::instrument(name = "Passkeys Query", skip(pool))]
+async fn passkeys_query(
+    pool: &PgPool,
+    email: &str,
+) -> Result<Vec<Passkey>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT passkey
+        FROM webauthn_credentials
+        WHERE email = $1
+        "#,
+        email
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to perform a query")?;
+
+    rows.into_iter()
+        .map(|row| serde_json::from_value::<Passkey>(row.passkey).context("Invalid stored passkey"))
+        .collect()
+}
+
+async fn insert_passkey(
+    pool: &PgPool,
+    email: &str,
+    name: &str,
+    passkey: &Passkey,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO webauthn_credentials (credential_id, email, name, passkey, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        credential_key(passkey.cred_id())?,
+        email,
+        name,
+        serde_json::to_value(passkey)?,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .context("Failed to insert passkey")?;
+
+    Ok(())
+}
+
+async fn update_passkey(
+    pool: &PgPool,
+    passkey: &Passkey,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE webauthn_credentials
+        SET passkey = $2, last_used_at = $3
+        WHERE credential_id = $1
+        "#,
+        credential_key(passkey.cred_id())?,
+        serde_json::to_value(passkey)?,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .context("Failed to update passkey")?;
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use anyhow::Context;
+use chrono::Utc;
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+use sqlx::PgPool;
+use crate::{
+    audit::{AuditAction, AuditEvent},
+    auth::{JwtService, RequireRecentAuth, TypedSession},
+    error::{e400, e401, e500, ApiError},
+    telemetry::spawn_blocking_with_tracing,
+    routes::login::process::{
+        hash_password, issue_jwt_cookies, validate_email_query, verify_password_hash,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: Secret<String>,
+    pub new_password: Secret<String>,
+}
+
+/*
+POST /api/password
+    -> 현재 비밀번호 확인 후 변경한다.
+    -> 탈취된 토큰을 끊기 위해 이 사용자의 모든 access/refresh token을 폐기하고, 요청한 클라이언트에는 새 토큰을 발급한다.
+*/
+#[tracing::instrument(name = "Change password", skip(req, form, session, jwt_service, pool))]
+pub async fn change_password(
+    req: HttpRequest,
+    form: web::Json<ChangePasswordRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let email = RequireRecentAuth(jwt_service.policy.reauth_window())
+        .check(&req, &session, &jwt_service)
+        .await?;
+    let form = form.0;
+    if form.new_password.expose_secret().len() < 8 {
+        return Err(e400("Password must be at least 8 characters"));
+    }
+
+    let (_, password_hash) = validate_email_query(&email, &pool)
+        .await
+        .map_err(e500)?
+        .ok_or_else(|| e401(ApiError::Unauthorized("No such user".into())))?;
+    spawn_blocking_with_tracing(move || verify_password_hash(password_hash, form.current_password))
+        .await
+        .map_err(e500)?
+        .map_err(e401)?;
+
+    let new_password = form.new_password;
+    let new_hash = spawn_blocking_with_tracing(move || hash_password(&new_password))
+        .await
+        .map_err(e500)?
+        .map_err(e500)?;
+    update_password_hash(&pool, &email, &new_hash).await.map_err(e500)?;
+
+    //새로 발급하는 토큰도 기존 로그인의 "로그인 상태 유지" 여부를 따른다.
+    let remember = match jwt_service.extract_refresh_token(&req) {
+        Some(token) => jwt_service.verify_refresh_token(&token).await
+            .map(|claims| claims.remember)
+            .unwrap_or(false),
+        None => false,
+    };
+    let revoked = jwt_service.revoke_all_tokens(&email).await.map_err(e500)?;
+    AuditEvent::from_request(AuditAction::PasswordChange, &req)
+        .actor(email.clone())
+        .details(serde_json::json!({"revoked_access_tokens": revoked}))
+        .record(&pool);
+
+    let mut response = HttpResponse::Ok();
+    if session.get_email().map_err(e500)?.is_some() {
+        session.renew();
+    } else {
+        let (access_cookie, refresh_cookie) = issue_jwt_cookies(&jwt_service, &pool, &email, remember).await.map_err(e500)?;
+        response.cookie(access_cookie).cookie(refresh_cookie);
+    }
+
+    Ok(response.json(serde_json::json!({"success": true})))
+}
+
+async fn update_password_hash(
+    pool: &PgPool,
+    email: &str,
+    password_hash: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE users
+        SET password_hash = $2, updated_at = $3
+        WHERE email = $1
+        "#,
+        email,
+        password_hash,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .context("Failed to update password")?;
+
+    Ok(())
+}
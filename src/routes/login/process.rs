@@ -2,7 +2,7 @@ use actix_web::HttpRequest;
 use actix_web::{
     error::InternalError,
     HttpResponse,
-    http::header::ContentType,
+    http::header::{ContentType, ACCEPT},
     //http::header::LOCATION,
     web,
     Result,
@@ -23,14 +23,70 @@ use secrecy::Secret;
 use secrecy::ExposeSecret;
 use crate::error::{
     ApiError,
+    ChallengeRequired,
+    JwtError,
     e500
 };
-use crate::auth::{JwtService};
+use crate::auth::{
+    ChallengePurpose, JwtService, OidcClient, ProofOfWork, TypedSession, ACCESS_TOKEN_COOKIE, REFRESH_TOKEN_COOKIE
+};
+use crate::routes::login::home::HomeTemplate;
+use crate::audit::{AuditAction, AuditEvent};
+use crate::routes::user_role_query;
+use crate::login_history::{recent_logins, LoginHistoryEntry};
+use crate::documents::{continue_reading, ContinueReading};
+
+//비로그인 상태에서 로그인 폼을 보여주는 화면
+pub const LOGIN_PAGE: &str = "/home_jwt";
 
 #[derive(Debug, Deserialize)]
 pub struct LogInRequest {
     pub email: String,
     pub password: Secret<String>,
+    //"로그인 상태 유지" 체크박스
+    #[serde(default, deserialize_with = "deserialize_checkbox")]
+    pub remember: bool,
+    //로그인 실패가 반복된 email이면 작업 증명 챌린지 풀이가 필요하다.
+    #[serde(default)]
+    pub pow_challenge: Option<String>,
+    #[serde(default)]
+    pub pow_nonce: Option<String>,
+}
+
+//HTML form 체크박스는 체크되면 "on"을, JSON은 true/false를 보내므로 둘 다 bool로 받는다.
+fn deserialize_checkbox<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Checkbox {
+        Bool(bool),
+        Text(String),
+    }
+
+    Ok(match Checkbox::deserialize(deserializer)? {
+        Checkbox::Bool(value) => value,
+        Checkbox::Text(value) => matches!(value.as_str(), "on" | "true" | "1"),
+    })
+}
+
+//비밀번호 외 수단(매직 링크, 소셜 로그인, 패스키)으로 로그인할 때 세션과 JWT 중 어떤 방식으로 발급할지 선택한다.
+#[derive(Debug, Clone, Copy, Default, Deserialize, serde::Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LoginMode {
+    Session,
+    #[default]
+    Jwt,
+}
+
+impl LoginMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginMode::Session => "session",
+            LoginMode::Jwt => "jwt",
+        }
+    }
 }
 
 pub struct Credentials {
@@ -44,6 +100,12 @@ pub struct LogInResponse {
     pub email: String,
     pub name: String,
     pub nickname: String,
+    //대리 로그인 중이면 관리자 email / 화면 상단에 배너를 표시한다.
+    pub impersonator: Option<String>,
+    //최근 로그인 기록
+    pub login_history: Vec<LoginHistoryEntry>,
+    //이어서 읽을 문서 (읽은 문서가 없으면 None)
+    pub continue_reading: Option<ContinueReading>,
 }
 
 #[tracing::instrument(
@@ -54,12 +116,15 @@ pub struct LogInResponse {
 pub async fn get_user_information_session(
     email: &str,
     pool: &PgPool,
+    impersonator: Option<String>,
 ) -> Result<HttpResponse, InternalError<ApiError>> {
-    match user_info_query(&email, &pool).await {
+    match user_info_query(email, pool).await {
         Ok(Some((email, name, nickname))) => {
+            let login_history = recent_logins(&email, pool).await.map_err(|e| login_redirect(ApiError::from(e)))?;
+            let continue_reading = continue_reading(&email, pool).await.map_err(|e| login_redirect(ApiError::from(e)))?;
             //템플릿 구조체로 데이터 저장
             let template = LogInResponse {
-                email, name, nickname
+                email, name, nickname, impersonator, login_history, continue_reading
             };
             //FromResidual 트레이트 : FromResidual 트레이트가 ? 연산자를 사용할 때 중요한 역할을 하는 트레이트이다. 에러 전파 또는 잔여(residual) 값을 상위 함수의 반환 타입으로 변환하는 방식을 정의
             let rendered = template.render().map_err(|e| {
@@ -69,7 +134,7 @@ pub async fn get_user_information_session(
             Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
         }
         Ok(None) => {
-            let e = ApiError::AuthError(anyhow!("No such user").into());
+            let e = ApiError::AuthError(anyhow!("No such user"));
             Err(login_redirect(e))
         }
         Err(e) => { 
@@ -88,13 +153,16 @@ pub async fn get_user_information_jwt(
     pool: &PgPool,
     access_cookie: Option<Cookie<'static>>,
     refresh_cookie: Option<Cookie<'static>>,
+    impersonator: Option<String>,
 ) -> Result<HttpResponse, InternalError<ApiError>> {
-    match user_info_query(&email, &pool).await {
+    match user_info_query(email, pool).await {
         Ok(Some((email, name, nickname))) => {
             //println!("access_token : {}", access_token);
+            let login_history = recent_logins(&email, pool).await.map_err(|e| login_redirect(ApiError::from(e)))?;
+            let continue_reading = continue_reading(&email, pool).await.map_err(|e| login_redirect(ApiError::from(e)))?;
             //템플릿 구조체로 데이터 저장
             let template = LogInResponse {
-                email, name, nickname
+                email, name, nickname, impersonator, login_history, continue_reading
             };
             //FromResidual 트레이트 : FromResidual 트레이트가 ? 연산자를 사용할 때 중요한 역할을 하는 트레이트이다. 에러 전파 또는 잔여(residual) 값을 상위 함수의 반환 타입으로 변환하는 방식을 정의
             let rendered = template.render().map_err(|e| {
@@ -113,7 +181,7 @@ pub async fn get_user_information_jwt(
             Ok(response)
         }
         Ok(None) => {
-            let e = ApiError::AuthError(anyhow!("No such user").into());
+            let e = ApiError::AuthError(anyhow!("No such user"));
             Err(login_redirect(e))
         }
         Err(e) => { 
@@ -122,6 +190,40 @@ pub async fn get_user_information_jwt(
     }
 }
 
+//세션 또는 access token 쿠키에서 로그인한 사용자 email을 찾는다. 대리 로그인 중이면 None을 반환한다.
+pub async fn current_user_email(
+    req: &HttpRequest,
+    session: &TypedSession,
+    jwt_service: &JwtService,
+) -> Option<String> {
+    if let Some(email) = session.get_email().ok().flatten() {
+        return match session.get_impersonation().ok().flatten() {
+            Some(_) => None,
+            None => Some(email),
+        };
+    }
+    let token = jwt_service.extract_access_token(req)?;
+    match jwt_service.verify_access_token(&token).await {
+        Ok(claims) if claims.act.is_none() => Some(claims.email),
+        _ => None,
+    }
+}
+
+//access token, refresh token을 발급하고 쿠키로 만든다. (validate_jwt와 비밀번호 외 로그인 수단이 공통으로 사용)
+pub async fn issue_jwt_cookies(
+    jwt_service: &JwtService,
+    pool: &PgPool,
+    email: &str,
+    remember: bool,
+) -> Result<(Cookie<'static>, Cookie<'static>), JwtError> {
+    //역할(role)은 DB에서 읽어온다.
+    let role = user_role_query(email, pool).await.map_err(|e| JwtError::Other(e.to_string()))?;
+    let (refresh_token, refresh_claims) = jwt_service.create_refresh_token(email, remember).await?;
+    let access_token = jwt_service.create_access_token(email, role, refresh_claims.session_exp, refresh_claims.auth_time).await?;
+
+    Ok(jwt_service.token_cookies(access_token, refresh_token, &refresh_claims))
+}
+
 /*
 cntn 반환타입이 Option<String>인 이유는 해당 컬럼이 Null값을 허용하기 때문이다.
 */
@@ -205,31 +307,151 @@ pub fn login_redirect(e: ApiError) -> InternalError<ApiError> {
     let response = HttpResponse::Unauthorized()
         .json(serde_json::json!({
             "error": e.to_string(),
-            "redirect": "/home"
+            "redirect": LOGIN_PAGE
         }));
     InternalError::from_response(e, response)
 }
 
-#[derive(Template)]
-#[template(path = "login/home.html")]
-struct LogOutResponse;
+//428 challenge_required 응답 (로그인, 회원 가입 공통)
+pub fn challenge_required(purpose: ChallengePurpose) -> InternalError<ApiError> {
+    let e = ChallengeRequired { purpose: purpose.as_str() };
+    let response = actix_web::ResponseError::error_response(&e);
+    InternalError::from_response(ApiError::Forbidden(e.to_string()), response)
+}
 
+/*
+비밀번호 로그인 전 작업 증명 확인
+    -> 이 email의 최근 로그인 실패가 기준 횟수 이상이면 챌린지 풀이가 있어야 비밀번호를 확인한다.
+    -> 풀이가 없거나 틀리면 428 challenge_required로 응답한다.
+*/
+pub async fn check_login_challenge(
+    req: &HttpRequest,
+    pow: &ProofOfWork,
+    form: &LogInRequest,
+    pool: &PgPool,
+    mode: LoginMode,
+) -> Result<(), InternalError<ApiError>> {
+    let required = pow.login_challenge_required(&form.email)
+        .await
+        .map_err(|e| login_redirect(ApiError::from(e)))?;
+    if !required {
+        return Ok(());
+    }
+    let solved = pow.verify(ChallengePurpose::Login, form.pow_challenge.as_deref(), form.pow_nonce.as_deref())
+        .await
+        .map_err(|e| login_redirect(ApiError::from(e)))?;
+    if solved {
+        return Ok(());
+    }
+    AuditEvent::from_request(AuditAction::LoginFailure, req)
+        .actor(form.email.clone())
+        .details(serde_json::json!({"mode": mode.as_str(), "reason": "challenge_required"}))
+        .record(pool);
+    Err(challenge_required(ChallengePurpose::Login))
+}
+
+//비밀번호 로그인 실패 횟수를 센다. / 저장소 오류로 로그인 실패 응답까지 막지는 않는다.
+pub async fn record_login_failure(pow: &ProofOfWork, email: &str) {
+    if let Err(e) = pow.record_login_failure(email).await {
+        tracing::error!(error.cause_chain = ?e, "Failed to record login failure");
+    }
+}
+
+pub async fn reset_login_failures(pow: &ProofOfWork, email: &str) {
+    if let Err(e) = pow.reset_login_failures(email).await {
+        tracing::error!(error.cause_chain = ?e, "Failed to reset login failures");
+    }
+}
+
+/*
+POST /logout
+    -> 세션 모드, JWT 모드 구분 없이 요청에 남아있는 인증 수단을 모두 정리한다.
+        1. Redis 세션 purge
+        2. refresh token Redis 키 삭제
+        3. access token jti 폐기 목록 등록
+        4. 토큰 쿠키 삭제
+    -> 만료되었거나 깨진 토큰은 이미 쓸 수 없으므로 건너뛰고, Redis 통신 오류만 500으로 응답한다.
+    -> Accept 헤더에 application/json이 있으면 JSON, 없으면 로그인 화면 HTML로 응답한다.
+*/
+#[tracing::instrument(name = "Logout", skip(req, session, jwt_service, oidc_client, pool))]
 pub async fn logout(
-    jwt_service: web::Data<JwtService>,
     req: HttpRequest,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    oidc_client: web::Data<OidcClient>,
+    pool: web::Data<PgPool>,
 ) -> Result<HttpResponse> {
-    let template = LogOutResponse;
+    let mut actor: Option<String> = None;
+    let mut cleared: Vec<&str> = Vec::new();
+
+    //1. 세션
+    if let Some(email) = session.get_email().unwrap_or(None) {
+        actor = Some(email);
+        cleared.push("session");
+    }
+    session.delete_email();
+
+    //2. refresh token
+    if let Some(refresh_token) = jwt_service.extract_refresh_token(&req) {
+        if let Ok(claims) = jwt_service.verify_refresh_token(&refresh_token).await {
+            actor.get_or_insert(claims.email);
+        }
+        match jwt_service.remove_refresh_token(&refresh_token).await {
+            Ok(()) => cleared.push("refresh_token"),
+            Err(e @ JwtError::StoreError(_)) => return Err(e500(e)),
+            Err(e) => tracing::debug!("Skip unusable refresh token : {}", e),
+        }
+    }
+
+    //3. access token
+    if let Some(access_token) = jwt_service.extract_access_token(&req) {
+        match jwt_service.verify_access_token(&access_token).await {
+            Ok(claims) => {
+                jwt_service.revoke_access_token(&claims).await.map_err(e500)?;
+                actor.get_or_insert(claims.email);
+                cleared.push("access_token");
+            }
+            Err(e @ JwtError::StoreError(_)) => return Err(e500(e)),
+            Err(e) => tracing::debug!("Skip unusable access token : {}", e),
+        }
+    }
+
+    if !cleared.is_empty() {
+        let mut event = AuditEvent::from_request(AuditAction::Logout, &req)
+            .details(serde_json::json!({"cleared": cleared}));
+        if let Some(actor) = actor {
+            event = event.actor(actor);
+        }
+        event.record(&pool);
+    }
+
+    //4. 쿠키
+    let mut response = HttpResponse::Ok();
+    response
+        .cookie(jwt_service.remove_token_cookie(ACCESS_TOKEN_COOKIE))
+        .cookie(jwt_service.remove_token_cookie(REFRESH_TOKEN_COOKIE));
+
+    if wants_json(&req) {
+        //세션으로 로그인했던 사용자는 세션 로그인 화면으로 돌려보낸다.
+        let redirect = if cleared.contains(&"session") { "/home_session" } else { LOGIN_PAGE };
+        return Ok(response.json(serde_json::json!({
+            "success": true,
+            "redirect": redirect
+        })));
+    }
+    let template = HomeTemplate { oidc_providers: oidc_client.provider_links() };
     let rendered = template.render().map_err(|e| {
         e500(ApiError::InternalServerError(format!("InternalServerError : {}", e)))
     })?;
-    let refresh_token = jwt_service.extract_refresh_token(&req).expect("Fail to load jwt(refresh)");
-    let access_cookie = jwt_service.remove_token_cookie("access_token");
-    let refresh_cookie = jwt_service.remove_token_cookie("refresh_token");
 
-    jwt_service.remove_refresh_token(&refresh_token)
-        .map_err(|e| {
-                e500(ApiError::InternalServerError(format!("InternalServerError : {}", e)))
-        })?;
+    Ok(response.content_type(ContentType::html()).body(rendered))
+}
 
-    Ok(HttpResponse::Ok().content_type(ContentType::html()).cookie(access_cookie).cookie(refresh_cookie).body(rendered))
-}
\ No newline at end of file
+//Accept 헤더로 JSON 응답을 원하는 요청인지 확인한다. (fetch API 호출 vs 브라우저 form 전송)
+pub fn wants_json(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.contains("application/json"))
+        .unwrap_or(false)
+}
@@ -0,0 +1,77 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use secrecy::Secret;
+use serde::Deserialize;
+use sqlx::PgPool;
+use crate::{
+    audit::{AuditAction, AuditEvent},
+    auth::{JwtService, TypedSession},
+    error::{e401, e500, ApiError},
+    telemetry::spawn_blocking_with_tracing,
+    routes::{
+        login::process::{current_user_email, validate_email_query, verify_password_hash},
+        user_role_query,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ReauthRequest {
+    pub password: Secret<String>,
+}
+
+/*
+POST /api/reauth
+    -> 로그인한 사용자가 비밀번호를 다시 확인해서 auth_time을 지금으로 갱신한다. (로그아웃 없이 step-up)
+    -> 세션 모드는 세션의 auth_time을, JWT 모드는 refresh token을 rotate해서 새 auth_time이 담긴 토큰을 발급한다.
+    -> 로그인 만료 시각(idle / absolute timeout)은 바뀌지 않는다.
+*/
+#[tracing::instrument(name = "Re-authenticate", skip(req, form, session, jwt_service, pool))]
+pub async fn reauthenticate(
+    req: HttpRequest,
+    form: web::Json<ReauthRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let email = current_user_email(&req, &session, &jwt_service).await
+        .ok_or_else(|| e401(ApiError::Unauthorized("Login required".into())))?;
+
+    let (_, password_hash) = validate_email_query(&email, &pool)
+        .await
+        .map_err(e500)?
+        .ok_or_else(|| e401(ApiError::Unauthorized("No such user".into())))?;
+    let password = form.0.password;
+    if let Err(e) = spawn_blocking_with_tracing(move || verify_password_hash(password_hash, password))
+        .await
+        .map_err(e500)?
+    {
+        AuditEvent::from_request(AuditAction::Reauthentication, &req)
+            .actor(email)
+            .details(serde_json::json!({"success": false}))
+            .record(&pool);
+        return Err(e401(e));
+    }
+
+    let mut response = HttpResponse::Ok();
+    if session.get_email().map_err(e500)?.is_some() {
+        session.mark_authenticated().map_err(e500)?;
+    } else {
+        let refresh_token = jwt_service.extract_refresh_token(&req)
+            .ok_or_else(|| e401(ApiError::Unauthorized("Refresh token missing".into())))?;
+        let (refresh_token, refresh_claims) = jwt_service.reauthenticate_refresh_token(&refresh_token).await
+            .map_err(|e| e401(ApiError::Unauthorized(e.to_string())))?;
+        //역할(role)은 DB에서 읽어온다.
+        let role = user_role_query(&email, &pool).await.map_err(e500)?;
+        let access_token = jwt_service
+            .create_access_token(&email, role, refresh_claims.session_exp, refresh_claims.auth_time)
+            .await
+            .map_err(e500)?;
+        let (access_cookie, refresh_cookie) = jwt_service.token_cookies(access_token, refresh_token, &refresh_claims);
+        response.cookie(access_cookie).cookie(refresh_cookie);
+    }
+    AuditEvent::from_request(AuditAction::Reauthentication, &req)
+        .actor(email)
+        .details(serde_json::json!({"success": true}))
+        .record(&pool);
+
+    Ok(response.json(serde_json::json!({"success": true})))
+}
@@ -1,14 +1,18 @@
-use actix_web::{error::InternalError, http::header::ContentType, web, HttpResponse, Result};
+use actix_web::{error::InternalError, http::{header::ContentType, StatusCode}, web, HttpRequest, HttpResponse, Result};
 use actix_web_flash_messages::IncomingFlashMessages;
 use askama::Template; 
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
 use secrecy::Secret;
+use crate::configuration::{RegistrationMode, RegistrationSettings};
+use crate::auth::{ChallengePurpose, ProofOfWork};
 use crate::routes::login::process::{
+    challenge_required,
     hash_password,
     login_redirect
 };
 use crate::error::ApiError;
+use crate::audit::{AuditAction, AuditEvent};
 
 #[derive(Debug, Deserialize)]
 pub struct RegisterRequest {
@@ -16,6 +20,20 @@ pub struct RegisterRequest {
     pub name: String,
     pub nickname: String,
     pub password: Secret<String>,
+    //초대 전용(invite) 모드에서만 필요하다.
+    #[serde(default)]
+    pub invite_code: Option<String>,
+    //작업 증명 챌린지 풀이 (challenge.enabled일 때 필수)
+    #[serde(default)]
+    pub pow_challenge: Option<String>,
+    #[serde(default)]
+    pub pow_nonce: Option<String>,
+}
+
+//GET /registration?invite=... : 초대 링크로 들어오면 초대 코드를 미리 채운다.
+#[derive(Debug, Deserialize)]
+pub struct RegistrationQuery {
+    pub invite: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -27,16 +45,28 @@ pub struct RegisterResponse {
 #[derive(Template)]
 #[template(path = "login/registration.html")]
 struct RegisterTemplate{
-    message: String
+    message: String,
+    //초대 코드 입력란 표시 여부
+    invite_required: bool,
+    invite_code: String,
+    //가입이 닫혀 있으면 폼 대신 안내 문구를 보여준다.
+    closed: bool,
 }
 
-pub async fn registration(flash_message: IncomingFlashMessages) -> Result<HttpResponse> {
+pub async fn registration(
+    flash_message: IncomingFlashMessages,
+    query: web::Query<RegistrationQuery>,
+    registration: web::Data<RegistrationSettings>,
+) -> Result<HttpResponse> {
     let mut message = String::new();
     for m in flash_message.iter() {
         message = m.content().to_string();
     }
     let template = RegisterTemplate{
         message,
+        invite_required: registration.mode == RegistrationMode::Invite,
+        invite_code: query.0.invite.unwrap_or_default(),
+        closed: registration.mode == RegistrationMode::Closed,
     };
     let rendered = template.render().map_err(|e| {
         actix_web::error::ErrorInternalServerError(e)
@@ -45,31 +75,92 @@ pub async fn registration(flash_message: IncomingFlashMessages) -> Result<HttpRe
     Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
 }
 
+/*
+회원 가입
+    -> closed면 거부, invite면 초대 코드를 소비한 뒤 가입시킨다.
+    -> 작업 증명 챌린지가 켜져 있으면 풀이가 맞아야 가입을 진행한다. (봇 대량 가입 방지)
+    -> 초대 코드 소비와 사용자 생성은 한 트랜잭션으로 처리해서, 가입이 실패(이메일 중복 등)하면 사용 횟수도 되돌린다.
+*/
 #[tracing::instrument(
     name = "Register new user",
-    skip(form, pool),
+    skip(req, form, pool, registration, pow),
     fields (
         email = %form.email,
         nickname = %form.nickname
     )
 )]
-
 pub async fn register(
+    req: HttpRequest,
     form: web::Json<RegisterRequest>,
     pool: web::Data<PgPool>,
+    registration: web::Data<RegistrationSettings>,
+    pow: web::Data<ProofOfWork>,
 ) -> Result<HttpResponse, InternalError<ApiError>> {
+    let rejected = |status: StatusCode, reason: &str, message: &str| {
+        AuditEvent::from_request(AuditAction::RegistrationFailure, &req)
+            .actor(form.email.clone())
+            .details(serde_json::json!({"reason": reason}))
+            .record(&pool);
+        InternalError::from_response(
+            ApiError::Forbidden(reason.to_string()),
+            HttpResponse::build(status).json(RegisterResponse {
+                success: false,
+                message: message.to_string(),
+            })
+        )
+    };
+    if registration.mode == RegistrationMode::Closed {
+        return Err(rejected(StatusCode::FORBIDDEN, "registration_closed", "현재 회원 가입을 받지 않습니다."));
+    }
+    if pow.enabled() {
+        let solved = pow.verify(ChallengePurpose::Register, form.pow_challenge.as_deref(), form.pow_nonce.as_deref())
+            .await
+            .map_err(|e| login_redirect(ApiError::from(e)))?;
+        if !solved {
+            AuditEvent::from_request(AuditAction::RegistrationFailure, &req)
+                .actor(form.email.clone())
+                .details(serde_json::json!({"reason": "challenge_required"}))
+                .record(&pool);
+            return Err(challenge_required(ChallengePurpose::Register));
+        }
+    }
+
     let password_hash = hash_password(&form.password).map_err(|e| 
         login_redirect(ApiError::from(e))
     )?;
 
-    insert_user(&pool, &form.email, &form.name, &form.nickname, &password_hash)
-        .await
-        .map(|_| HttpResponse::Ok().json(RegisterResponse {
-            success: true,
-            message: "회원 가입 성공".to_string()
-        }))
+    let mut transaction = pool.begin().await.map_err(|e| login_redirect(ApiError::UnexpectError(e.into())))?;
+    if registration.mode == RegistrationMode::Invite {
+        let code = form.invite_code.as_deref().unwrap_or_default().trim();
+        let consumed = consume_invitation(&mut transaction, code, &form.email)
+            .await
+            .map_err(|e| login_redirect(ApiError::from(e)))?;
+        if !consumed {
+            return Err(rejected(StatusCode::BAD_REQUEST, "invalid_invitation", "유효하지 않은 초대 코드입니다."));
+        }
+    }
+
+    let inserted = match insert_user(&mut transaction, &form.email, &form.name, &form.nickname, &password_hash).await {
+        Ok(()) => transaction.commit().await.map_err(anyhow::Error::from),
+        Err(e) => Err(e),
+    };
+    inserted
+        .map(|_| {
+            AuditEvent::from_request(AuditAction::Registration, &req)
+                .actor(form.email.clone())
+                .details(serde_json::json!({"mode": registration.mode.as_str()}))
+                .record(&pool);
+            HttpResponse::Ok().json(RegisterResponse {
+                success: true,
+                message: "회원 가입 성공".to_string()
+            })
+        })
         .map_err(|e| {
             tracing::error!("유저 회원가입 실패 : {:?}", e);
+            AuditEvent::from_request(AuditAction::RegistrationFailure, &req)
+                .actor(form.email.clone())
+                .details(serde_json::json!({"error": e.to_string()}))
+                .record(&pool);
 
             let error_message = if e.to_string().contains("duplicate") {
                 "이미 사용중인 이메일 있습니다."
@@ -87,8 +178,37 @@ pub async fn register(
         })
 }
 
-async fn insert_user(
-    pool: &PgPool,
+/*
+초대 코드 소비 / 사용 가능하면 사용 횟수를 1 늘리고 true를 반환한다.
+    -> 조건 확인과 증가를 UPDATE 한 문장으로 처리해서 동시에 가입해도 max_uses를 넘지 않는다.
+*/
+#[tracing::instrument(name = "Consume invitation", skip(transaction, code))]
+async fn consume_invitation(
+    transaction: &mut Transaction<'_, Postgres>,
+    code: &str,
+    email: &str,
+) -> Result<bool, anyhow::Error> {
+    let consumed = sqlx::query!(
+        r#"
+        UPDATE invitations
+        SET uses = uses + 1
+        WHERE code = $1
+            AND uses < max_uses
+            AND (expires_at IS NULL OR expires_at > now())
+            AND (email IS NULL OR lower(email) = lower($2))
+        RETURNING code
+        "#,
+        code,
+        email
+    )
+    .fetch_optional(transaction)
+    .await?;
+
+    Ok(consumed.is_some())
+}
+
+pub async fn insert_user(
+    executor: impl PgExecutor<'_>,
     email: &str,
     name: &str,
     nickname: &str,
@@ -101,7 +221,7 @@ async fn insert_user(
         "#,
         email, name, nickname, password_hash
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -1,25 +1,34 @@
 use actix_web::{
-    HttpRequest, HttpResponse, Result, cookie::{Cookie, SameSite, time::Duration}, error::InternalError, web
+    HttpRequest, HttpResponse, Result, cookie::Cookie, error::InternalError, web
 };
 use sqlx::PgPool;
 //anyhow의 확장 트레이트를 스코프 안으로 가져온다.
 use anyhow::anyhow;
 use crate::{
-    auth::JwtService, error::{ApiError, JwtError}, routes::login::process::{
-        Credentials, LogInRequest, get_user_information_jwt, login_redirect, validate_email_query, verify_password_hash
-    }, telemetry::spawn_blocking_with_tracing 
+    audit::{AuditAction, AuditEvent},
+    login_history::LoginRecord,
+    mailer::Mailer,
+    auth::{JwtService, ProofOfWork}, error::{ApiError, JwtError}, routes::login::process::{
+        Credentials, LogInRequest, LoginMode, get_user_information_jwt, issue_jwt_cookies, login_redirect,
+        validate_email_query, verify_password_hash, check_login_challenge, record_login_failure, reset_login_failures
+    }, routes::user_role_query, telemetry::spawn_blocking_with_tracing 
 };
 
 #[tracing::instrument(
     name="Validate Credentials(JWT)",
-    skip(form, pool),
+    skip(req, form, pool, jwt_service, mailer, pow),
     fields(email=tracing::field::Empty, password=tracing::field::Empty)
 )]
 pub async fn validate_jwt(
+    req: HttpRequest,
     form: web::Form<LogInRequest>,
     pool: web::Data<PgPool>,
-    jwt_service: web::Data<JwtService>
+    jwt_service: web::Data<JwtService>,
+    mailer: web::Data<dyn Mailer>,
+    pow: web::Data<ProofOfWork>,
 ) -> Result<HttpResponse, InternalError<ApiError>> {
+    check_login_challenge(&req, &pow, &form, &pool, LoginMode::Jwt).await?;
+    let remember = form.remember;
     let credentials = Credentials {
         email: form.0.email,
         password: form.0.password
@@ -28,39 +37,43 @@ pub async fn validate_jwt(
     match validate_email_query(&credentials.email, &pool).await {
         Ok(Some((_email, password_hash))) => {
             //비밀번호 체크
-            spawn_blocking_with_tracing(move || {
+            let verified = spawn_blocking_with_tracing(move || {
                 verify_password_hash(password_hash, credentials.password)
             })
             .await
-            .map_err(|e| login_redirect(ApiError::from(e)))?
-            .map_err(|e| login_redirect(e))?;
+            .map_err(|e| login_redirect(ApiError::from(e)))?;
+            if let Err(e) = verified {
+                AuditEvent::from_request(AuditAction::LoginFailure, &req)
+                    .actor(credentials.email.clone())
+                    .details(serde_json::json!({"mode": "jwt", "reason": "invalid_password"}))
+                    .record(&pool);
+                record_login_failure(&pow, &credentials.email).await;
+                return Err(login_redirect(e));
+            }
 
             //jwt 토큰 생성
-            let access_token = jwt_service.create_access_token(&credentials.email, Some("admin".to_string())).expect("Failed to load jwt(access)");
-            let refresh_token = jwt_service.create_refresh_token(&credentials.email).expect("Faile to loat jwt(refresh)");
-
-            let access_cookie = Cookie::build("access_token", access_token.clone())
-                .path("/")
-                .max_age(Duration::minutes(15))
-                .http_only(true)
-                .same_site(SameSite::Lax)
-                .finish();
-            let refresh_cookie = Cookie::build("refresh_token", refresh_token.clone())
-                .path("/")
-                .max_age(Duration::days(7))
-                .http_only(true)
-                .secure(true)
-                .same_site(SameSite::Strict)
-                .finish();
+            let (access_cookie, refresh_cookie) = issue_jwt_cookies(&jwt_service, &pool, &credentials.email, remember).await
+                .map_err(|e| login_redirect(ApiError::UnexpectError(anyhow!(e.to_string()))))?;
+            AuditEvent::from_request(AuditAction::LoginSuccess, &req)
+                .actor(credentials.email.clone())
+                .details(serde_json::json!({"mode": "jwt", "remember": remember}))
+                .record(&pool);
+            LoginRecord::from_request(&req, credentials.email.clone(), "password").record(&pool, &mailer).await;
+            reset_login_failures(&pow, &credentials.email).await;
             //println!("access_token : {}, refresh_token : {}", access_token, refresh_token);
             //async fn은 호출 즉시 실행되지 않고 Future를 반환한다. 실제로 실행하려면 .await가 필요하다.
-            let response = get_user_information_jwt(&credentials.email, &pool, Some(access_cookie), Some(refresh_cookie)).await?;
+            let response = get_user_information_jwt(&credentials.email, &pool, Some(access_cookie), Some(refresh_cookie), None).await?;
 
             Ok(response)
 
         }
         Ok(None) => {
-            let e = ApiError::AuthError(anyhow!("No such user").into());
+            AuditEvent::from_request(AuditAction::LoginFailure, &req)
+                .actor(credentials.email.clone())
+                .details(serde_json::json!({"mode": "jwt", "reason": "no_such_user"}))
+                .record(&pool);
+            record_login_failure(&pow, &credentials.email).await;
+            let e = ApiError::AuthError(anyhow!("No such user"));
             Err(login_redirect(e))
         }
         Err(e) => {
@@ -72,25 +85,28 @@ pub async fn validate_jwt(
 #[derive(Debug)]
 pub enum CheckJwtToken {
     Guest,
-    AccessValid {email: String},
+    //actor : 대리 로그인 토큰이면 관리자 email
+    AccessValid {email: String, actor: Option<String>},
     RefreshValid {
         email: String,
-        access_cookie: Cookie<'static>,
-        refresh_cookie: Cookie<'static>
+        //쿠키는 크기가 커서 Box로 감싼다. (다른 variant까지 쿠키 크기만큼 커지지 않게)
+        access_cookie: Box<Cookie<'static>>,
+        refresh_cookie: Box<Cookie<'static>>
     },
     InvalidToken
 }
 
 pub async fn check_token (
     req: &HttpRequest,
-    jwt_service: &JwtService
+    jwt_service: &JwtService,
+    pool: &PgPool,
 ) -> Result<CheckJwtToken, JwtError> {
     //1. access_token 시도
     //println!("jwt_service.extract_access_token(&req) : {:?}", jwt_service.extract_access_token(&req));
     if let Some(access_token) = jwt_service.extract_access_token(req) {
         //println!("acces_token verify start");
-        match jwt_service.verify_access_token(&access_token) {
-            Ok(claims) => return Ok(CheckJwtToken::AccessValid { email: claims.email }),
+        match jwt_service.verify_access_token(&access_token).await {
+            Ok(claims) => return Ok(CheckJwtToken::AccessValid { email: claims.email, actor: claims.act.map(|a| a.sub) }),
             Err(JwtError::ExpiredToken) => {
 
             }
@@ -104,34 +120,37 @@ pub async fn check_token (
     //println!("jwt_service.extract_refresh_token(&req) : {:?}", jwt_service.extract_refresh_token(&req));
     if let Some(refresh_token) = jwt_service.extract_refresh_token(req) {
         //println!("refresh_token verify start");
-        match jwt_service.verify_refresh_token(&refresh_token) {
+        match jwt_service.verify_refresh_token(&refresh_token).await {
             Ok(claims) => {
-                println!("claims(refresh) : {}", claims.email);
-                let new_access_token = jwt_service.create_access_token(&claims.email, Some("admin".to_string())).expect("Faile to loat jwt(access)");
-                let new_refresh_token = jwt_service.rotate_refresh_token(&refresh_token).expect("Faile to load jwt(refresh)");
-                
-                let access_cookie = Cookie::build("access_token", new_access_token.clone())
-                .path("/")
-                .max_age(Duration::minutes(15))
-                .http_only(true)
-                .same_site(SameSite::Lax)
-                .finish();
-                let refresh_cookie = Cookie::build("refresh_token", new_refresh_token.clone())
-                    .path("/")
-                    .max_age(Duration::days(7))
-                    .http_only(true)
-                    .secure(true)
-                    .same_site(SameSite::Strict)
-                    .finish();
+                AuditEvent::from_request(AuditAction::TokenRefresh, req)
+                    .actor(claims.email.clone())
+                    .record(pool);
+                let (new_refresh_token, refresh_claims) = match jwt_service.rotate_refresh_token(&refresh_token).await {
+                    Ok(rotated) => rotated,
+                    //idle / absolute timeout이 지난 로그인
+                    Err(JwtError::ExpiredToken) => return Ok(CheckJwtToken::InvalidToken),
+                    Err(e) => return Err(e),
+                };
+                let role = user_role_query(&claims.email, pool).await.map_err(|e| JwtError::Other(e.to_string()))?;
+                let new_access_token = jwt_service.create_access_token(&claims.email, role, refresh_claims.session_exp, refresh_claims.auth_time).await?;
+                let (access_cookie, refresh_cookie) = jwt_service.token_cookies(new_access_token, new_refresh_token, &refresh_claims);
 
-                return Ok(CheckJwtToken::RefreshValid { email: claims.email, access_cookie, refresh_cookie })
+                return Ok(CheckJwtToken::RefreshValid { email: claims.email, access_cookie: Box::new(access_cookie), refresh_cookie: Box::new(refresh_cookie) })
             }
             Err(JwtError::ExpiredToken) => {
-                println!("JwtError::ExpiredToken");
+                tracing::debug!("Refresh token expired");
+                return Ok(CheckJwtToken::InvalidToken);
+            }
+            //이미 rotate 되었거나 삭제된 refresh token 재사용 -> 탈취 가능성이 있으므로 감사 로그에 남긴다.
+            Err(JwtError::TokenRevoked) => {
+                tracing::warn!("Revoked refresh token used from {:?}", req.peer_addr());
+                AuditEvent::from_request(AuditAction::TokenRevoked, req)
+                    .details(serde_json::json!({"token": "refresh"}))
+                    .record(pool);
                 return Ok(CheckJwtToken::InvalidToken);
             }
             Err(JwtError::InvalidSignature) | Err(JwtError::InvalidToken) => {
-                println!("JwtError::InvalidSignature");
+                tracing::debug!("Invalid refresh token signature");
                 return Ok(CheckJwtToken::InvalidToken);
             }
             Err(_) => return Ok(CheckJwtToken::InvalidToken)
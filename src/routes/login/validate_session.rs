@@ -1,5 +1,6 @@
 use actix_web::{
     error::InternalError,
+    HttpRequest,
     HttpResponse,
     web,
     Result,
@@ -8,7 +9,10 @@ use sqlx::PgPool;
 //anyhow의 확장 트레이트를 스코프 안으로 가져온다.
 use anyhow::anyhow;
 use crate::{
-    auth::TypedSession,
+    audit::{AuditAction, AuditEvent},
+    login_history::LoginRecord,
+    mailer::Mailer,
+    auth::{ProofOfWork, TypedSession},
     error::ApiError,
     telemetry::spawn_blocking_with_tracing,
     routes::login::process::{
@@ -17,20 +21,29 @@ use crate::{
         verify_password_hash, 
         login_redirect, 
         get_user_information_session, 
-        validate_email_query
+        validate_email_query,
+        check_login_challenge,
+        record_login_failure,
+        reset_login_failures,
+        LoginMode
     }
 };
 
 #[tracing::instrument(
     name="Validate Credentials",
-    skip(form, pool, session),
+    skip(req, form, pool, session, mailer, pow),
     fields(email=tracing::field::Empty, password=tracing::field::Empty)
 )]
 pub async fn validate_session(
+    req: HttpRequest,
     form: web::Json<LogInRequest>,
     pool: web::Data<PgPool>,
     session: TypedSession,
+    mailer: web::Data<dyn Mailer>,
+    pow: web::Data<ProofOfWork>,
 ) -> Result<HttpResponse, InternalError<ApiError>> {
+    check_login_challenge(&req, &pow, &form, &pool, LoginMode::Session).await?;
+    let remember = form.remember;
     let credentials =  Credentials { 
         email: form.0.email, 
         password: form.0.password 
@@ -39,21 +52,39 @@ pub async fn validate_session(
     match validate_email_query(&credentials.email, &pool).await {
         Ok(Some((email,password_hash))) => {
             //비밀번호 체크
-            spawn_blocking_with_tracing(move || {
+            let verified = spawn_blocking_with_tracing(move || {
                 verify_password_hash(password_hash, credentials.password)
             })
             .await
-            .map_err(|e| login_redirect(ApiError::from(e)))?
-            .map_err(|e| login_redirect(e))?;
+            .map_err(|e| login_redirect(ApiError::from(e)))?;
+            if let Err(e) = verified {
+                AuditEvent::from_request(AuditAction::LoginFailure, &req)
+                    .actor(credentials.email.clone())
+                    .details(serde_json::json!({"mode": "session", "reason": "invalid_password"}))
+                    .record(&pool);
+                record_login_failure(&pow, &credentials.email).await;
+                return Err(login_redirect(e));
+            }
             //세션 정보 저장
             session.renew();
-            session.insert_email(email).map_err(|e| login_redirect(ApiError::UnexpectError(e.into())))?;
+            session.insert_email(email, remember).map_err(|e| login_redirect(ApiError::UnexpectError(e.into())))?;
+            AuditEvent::from_request(AuditAction::LoginSuccess, &req)
+                .actor(credentials.email.clone())
+                .details(serde_json::json!({"mode": "session", "remember": remember}))
+                .record(&pool);
+            LoginRecord::from_request(&req, credentials.email.clone(), "password").record(&pool, &mailer).await;
+            reset_login_failures(&pow, &credentials.email).await;
 
             //async fn은 호출 즉시 실행되지 않고 Future를 반환한다. 실제로 실행하려면 .await가 필요하다.
-            get_user_information_session(&credentials.email, &pool).await
+            get_user_information_session(&credentials.email, &pool, None).await
         }
         Ok(None) => {
-            let e = ApiError::AuthError(anyhow!("No such user").into());
+            AuditEvent::from_request(AuditAction::LoginFailure, &req)
+                .actor(credentials.email.clone())
+                .details(serde_json::json!({"mode": "session", "reason": "no_such_user"}))
+                .record(&pool);
+            record_login_failure(&pow, &credentials.email).await;
+            let e = ApiError::AuthError(anyhow!("No such user"));
             Err(login_redirect(e))
         }
         Err(e) => { 
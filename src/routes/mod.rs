@@ -1,5 +1,19 @@
+mod admin;
+mod docs;
+mod fragments;
+mod glossary;
 mod login;
+mod oauth;
+mod search;
 mod table_contents;
+mod tags;
 
+pub use admin::*;
+pub use docs::*;
+pub use fragments::*;
+pub use glossary::*;
 pub use login::*;
+pub use oauth::*;
+pub use search::*;
 pub use table_contents::*;
+pub use tags::*;
@@ -0,0 +1,205 @@
+use actix_web::{http::header::ContentType, web, HttpRequest, HttpResponse, Result};
+use anyhow::Context;
+use askama::Template;
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use crate::auth::{random_url_token, JwtService, TypedSession};
+use crate::error::{e400, e500, see_other};
+use crate::routes::login::current_user_email;
+use crate::routes::oauth::client::{find_client, OAuthClient};
+
+//authorization code 유효 시간(분)
+const AUTHORIZATION_CODE_MINUTES: i64 = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorizeQuery {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorizeDecision {
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub state: Option<String>,
+    pub code_challenge: String,
+    pub consent_token: String,
+    //"approve" | "deny"
+    pub decision: String,
+}
+
+#[derive(Template)]
+#[template(path = "oauth/consent.html")]
+struct ConsentTemplate {
+    client_name: String,
+    email: String,
+    scopes: Vec<String>,
+    client_id: String,
+    redirect_uri: String,
+    scope: String,
+    state: String,
+    code_challenge: String,
+    consent_token: String,
+}
+
+/*
+client_id와 redirect_uri 검증
+    -> redirect_uri가 등록된 값과 정확히 일치하지 않으면 리다이렉트하지 않고 400을 돌려준다. (open redirect 방지)
+*/
+async fn load_client_for_redirect(
+    pool: &PgPool,
+    client_id: &str,
+    redirect_uri: &str,
+) -> Result<OAuthClient> {
+    let client = find_client(pool, client_id)
+        .await
+        .map_err(e500)?
+        .ok_or_else(|| e400("Unknown client"))?;
+    if !client.redirect_uris.iter().any(|uri| uri == redirect_uri) {
+        return Err(e400("redirect_uri is not registered for this client"));
+    }
+    if !client.allows_grant("authorization_code") {
+        return Err(e400("Client is not allowed to use authorization_code"));
+    }
+    Ok(client)
+}
+
+//redirect_uri에 code 또는 error를 붙여서 돌려보낸다.
+fn redirect_with(redirect_uri: &str, params: &[(&str, &str)], state: Option<&str>) -> Result<HttpResponse> {
+    let mut params = params.to_vec();
+    if let Some(state) = state {
+        params.push(("state", state));
+    }
+    let url = reqwest::Url::parse_with_params(redirect_uri, &params).map_err(e400)?;
+    Ok(see_other(url.as_str()))
+}
+
+//GET /oauth/authorize : 요청을 검증하고 로그인한 사용자에게 동의 화면을 보여준다.
+#[tracing::instrument(name = "OAuth authorize", skip(req, query, session, jwt_service, pool), fields(client_id = %query.client_id))]
+pub async fn authorize(
+    req: HttpRequest,
+    query: web::Query<AuthorizeQuery>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let client = load_client_for_redirect(&pool, &query.client_id, &query.redirect_uri).await?;
+    let state = query.state.as_deref();
+
+    if query.response_type != "code" {
+        return redirect_with(&query.redirect_uri, &[("error", "unsupported_response_type")], state);
+    }
+    //PKCE(S256)는 필수
+    let code_challenge = match (&query.code_challenge, query.code_challenge_method.as_deref()) {
+        (Some(challenge), Some("S256")) => challenge.clone(),
+        _ => return redirect_with(
+            &query.redirect_uri,
+            &[("error", "invalid_request"), ("error_description", "PKCE with S256 is required")],
+            state,
+        ),
+    };
+    let scope = match client.resolve_scope(query.scope.as_deref()) {
+        Ok(scope) => scope,
+        Err(e) => return redirect_with(&query.redirect_uri, &[("error", e.error)], state),
+    };
+
+    let email = match current_user_email(&req, &session, &jwt_service).await {
+        Some(email) => email,
+        None => return Ok(see_other("/home_jwt")),
+    };
+
+    let consent_token = random_url_token();
+    session.insert_consent_token(consent_token.clone()).map_err(e500)?;
+    let template = ConsentTemplate {
+        client_name: client.name,
+        email,
+        scopes: scope.split_whitespace().map(|s| s.to_owned()).collect(),
+        client_id: client.client_id,
+        redirect_uri: query.redirect_uri.clone(),
+        scope,
+        state: query.state.clone().unwrap_or_default(),
+        code_challenge,
+        consent_token,
+    };
+    let rendered = template.render().map_err(e500)?;
+
+    Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+}
+
+//POST /oauth/authorize : 동의 화면의 승인/거부 처리 후 redirect_uri로 돌려보낸다.
+#[tracing::instrument(name = "OAuth authorize decision", skip(req, form, session, jwt_service, pool), fields(client_id = %form.client_id))]
+pub async fn authorize_decision(
+    req: HttpRequest,
+    form: web::Form<AuthorizeDecision>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let client = load_client_for_redirect(&pool, &form.client_id, &form.redirect_uri).await?;
+    let state = form.state.as_deref().filter(|s| !s.is_empty());
+
+    let expected = session.take_consent_token().map_err(e500)?;
+    if expected.as_deref() != Some(form.consent_token.as_str()) {
+        return Err(e400("Invalid consent token"));
+    }
+    let email = match current_user_email(&req, &session, &jwt_service).await {
+        Some(email) => email,
+        None => return Ok(see_other("/home_jwt")),
+    };
+    if form.decision != "approve" {
+        return redirect_with(&form.redirect_uri, &[("error", "access_denied")], state);
+    }
+    let scope = match client.resolve_scope(Some(form.scope.as_str())) {
+        Ok(scope) => scope,
+        Err(e) => return redirect_with(&form.redirect_uri, &[("error", e.error)], state),
+    };
+
+    let code = random_url_token();
+    insert_authorization_code(&pool, &code, &client.client_id, &email, &form.redirect_uri, &scope, &form.code_challenge)
+        .await
+        .map_err(e500)?;
+
+    redirect_with(&form.redirect_uri, &[("code", code.as_str())], state)
+}
+
+pub fn hash_code(code: &str) -> String {
+    hex::encode(Sha256::digest(code.as_bytes()))
+}
+
+async fn insert_authorization_code(
+    pool: &PgPool,
+    code: &str,
+    client_id: &str,
+    email: &str,
+    redirect_uri: &str,
+    scope: &str,
+    code_challenge: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO oauth_authorization_codes (code_hash, client_id, email, redirect_uri, scope, code_challenge, expires_at, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        hash_code(code),
+        client_id,
+        email,
+        redirect_uri,
+        scope,
+        code_challenge,
+        Utc::now() + Duration::minutes(AUTHORIZATION_CODE_MINUTES),
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .context("Failed to insert authorization code")?;
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use actix_web::{http::header::AUTHORIZATION, web, HttpRequest, HttpResponse, Result};
+use anyhow::Context;
+use chrono::Utc;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::auth::{random_url_token, JwtService, TypedSession};
+use crate::error::{e400, e500, OAuthError};
+use crate::routes::admin::require_recent_admin;
+use crate::routes::login::{hash_password, verify_password_hash};
+use crate::telemetry::spawn_blocking_with_tracing;
+
+pub const SUPPORTED_GRANT_TYPES: [&str; 3] = ["authorization_code", "refresh_token", "client_credentials"];
+
+#[derive(Debug)]
+pub struct OAuthClient {
+    pub client_id: String,
+    pub client_secret_hash: Option<String>,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub grant_types: Vec<String>,
+    pub scopes: Vec<String>,
+}
+
+impl OAuthClient {
+    pub fn is_confidential(&self) -> bool {
+        self.client_secret_hash.is_some()
+    }
+
+    pub fn allows_grant(&self, grant_type: &str) -> bool {
+        self.grant_types.iter().any(|g| g == grant_type)
+    }
+
+    //요청 scope(공백 구분)가 등록된 scope 안에 있는지 확인하고 정규화된 문자열로 돌려준다. 비어 있으면 등록된 전체 scope
+    pub fn resolve_scope(&self, requested: Option<&str>) -> Result<String, OAuthError> {
+        let requested: Vec<&str> = match requested {
+            Some(scope) if !scope.trim().is_empty() => scope.split_whitespace().collect(),
+            _ => self.scopes.iter().map(|s| s.as_str()).collect(),
+        };
+        if let Some(unknown) = requested.iter().find(|s| !self.scopes.iter().any(|allowed| allowed == *s)) {
+            return Err(OAuthError::invalid_scope(format!("Scope not allowed : {}", unknown)));
+        }
+        Ok(requested.join(" "))
+    }
+}
+
+#[tracing::instrument(name = "OAuth Client Query", skip(pool))]
+pub async fn find_client(
+    pool: &PgPool,
+    client_id: &str,
+) -> Result<Option<OAuthClient>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        OAuthClient,
+        r#"
+        SELECT client_id, client_secret_hash, name, redirect_uris, grant_types, scopes
+        FROM oauth_clients
+        WHERE client_id = $1
+        "#,
+        client_id
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to perform a query")?;
+
+    Ok(row)
+}
+
+/*
+토큰/introspection/revocation 엔드포인트의 클라이언트 인증
+    -> HTTP Basic(client_id:client_secret) 또는 form의 client_id, client_secret
+    -> public client는 client_id만 확인하고, confidential client는 secret을 argon2 해시와 비교한다.
+*/
+pub async fn authenticate_client(
+    req: &HttpRequest,
+    form_client_id: Option<&str>,
+    form_client_secret: Option<&str>,
+    pool: &PgPool,
+) -> Result<OAuthClient, OAuthError> {
+    let (client_id, client_secret) = match basic_credentials(req) {
+        Some((id, secret)) => (id, Some(secret)),
+        None => (
+            form_client_id.ok_or_else(|| OAuthError::invalid_client("Missing client_id"))?.to_owned(),
+            form_client_secret.map(|s| s.to_owned()),
+        ),
+    };
+    let client = find_client(pool, &client_id)
+        .await
+        .map_err(OAuthError::server_error)?
+        .ok_or_else(|| OAuthError::invalid_client("Unknown client"))?;
+
+    if let Some(secret_hash) = &client.client_secret_hash {
+        let secret = client_secret.ok_or_else(|| OAuthError::invalid_client("Missing client_secret"))?;
+        let secret_hash = Secret::new(secret_hash.clone());
+        spawn_blocking_with_tracing(move || verify_password_hash(secret_hash, Secret::new(secret)))
+            .await
+            .map_err(OAuthError::server_error)?
+            .map_err(|_| OAuthError::invalid_client("Invalid client credentials"))?;
+    }
+
+    Ok(client)
+}
+
+fn basic_credentials(req: &HttpRequest) -> Option<(String, String)> {
+    let header = req.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let encoded = header.strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(base64::decode(encoded).ok()?).ok()?;
+    let (id, secret) = decoded.split_once(':')?;
+    //RFC 6749 2.3.1 : client_id, client_secret은 form-urlencoded 된 뒤 Basic 인코딩된다.
+    Some((urlencoding::decode(id).ok()?.into_owned(), urlencoding::decode(secret).ok()?.into_owned()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterClientRequest {
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub grant_types: Vec<String>,
+    pub scopes: Vec<String>,
+    //false면 secret 없이 PKCE만 사용하는 public client
+    #[serde(default = "default_confidential")]
+    pub confidential: bool,
+}
+
+fn default_confidential() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegisterClientResponse {
+    pub client_id: String,
+    //발급 시 한 번만 보여준다. DB에는 해시만 저장된다.
+    pub client_secret: Option<String>,
+}
+
+//POST /admin/oauth/clients : 관리자가 내부 도구용 클라이언트를 등록한다.
+#[tracing::instrument(name = "Register OAuth client", skip(req, form, session, jwt_service, pool), fields(name = %form.name))]
+pub async fn register_oauth_client(
+    req: HttpRequest,
+    form: web::Json<RegisterClientRequest>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    require_recent_admin(&req, &session, &jwt_service, &pool).await?;
+
+    if let Some(unknown) = form.grant_types.iter().find(|g| !SUPPORTED_GRANT_TYPES.contains(&g.as_str())) {
+        return Err(e400(format!("Unsupported grant type : {}", unknown)));
+    }
+    if !form.confidential && form.grant_types.iter().any(|g| g == "client_credentials") {
+        return Err(e400("client_credentials requires a confidential client"));
+    }
+    if form.grant_types.iter().any(|g| g == "authorization_code") && form.redirect_uris.is_empty() {
+        return Err(e400("authorization_code requires at least one redirect_uri"));
+    }
+
+    let client_id = Uuid::new_v4().to_string();
+    let client_secret = form.confidential.then(random_url_token);
+    let client_secret_hash = match &client_secret {
+        Some(secret) => Some(hash_password(&Secret::new(secret.clone())).map_err(e500)?),
+        None => None,
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO oauth_clients (client_id, client_secret_hash, name, redirect_uris, grant_types, scopes, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+        client_id,
+        client_secret_hash,
+        form.name,
+        &form.redirect_uris,
+        &form.grant_types,
+        &form.scopes,
+        Utc::now()
+    )
+    .execute(pool.get_ref())
+    .await
+    .map_err(e500)?;
+
+    Ok(HttpResponse::Created().json(RegisterClientResponse { client_id, client_secret }))
+}
@@ -0,0 +1,68 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool;
+use crate::auth::JwtService;
+use crate::error::OAuthError;
+use crate::routes::oauth::client::authenticate_client;
+
+#[derive(Debug, Deserialize)]
+pub struct TokenForm {
+    pub token: String,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+/*
+POST /oauth/introspect (RFC 7662)
+    -> 유효하지 않은 토큰은 이유와 상관없이 {"active": false}만 돌려준다.
+*/
+#[tracing::instrument(name = "OAuth introspect", skip(req, form, pool, jwt_service))]
+pub async fn introspect(
+    req: HttpRequest,
+    form: web::Form<TokenForm>,
+    pool: web::Data<PgPool>,
+    jwt_service: web::Data<JwtService>,
+) -> Result<HttpResponse, OAuthError> {
+    authenticate_client(&req, form.client_id.as_deref(), form.client_secret.as_deref(), &pool).await?;
+
+    let body = match jwt_service.verify_oauth_token(&form.token).await {
+        Ok(claims) => serde_json::json!({
+            "active": true,
+            "sub": claims.sub,
+            "client_id": claims.client_id,
+            "scope": claims.scope,
+            "token_type": if claims.token_use == "refresh" { "refresh_token" } else { "access_token" },
+            "exp": claims.exp,
+            "iat": claims.iat,
+            "jti": claims.jti,
+        }),
+        Err(_) => serde_json::json!({"active": false}),
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(body))
+}
+
+/*
+POST /oauth/revoke (RFC 7009)
+    -> 이미 무효이거나 알 수 없는 토큰이어도 200을 돌려준다.
+    -> 다른 클라이언트에게 발급된 토큰은 폐기하지 않는다.
+*/
+#[tracing::instrument(name = "OAuth revoke", skip(req, form, pool, jwt_service))]
+pub async fn revoke(
+    req: HttpRequest,
+    form: web::Form<TokenForm>,
+    pool: web::Data<PgPool>,
+    jwt_service: web::Data<JwtService>,
+) -> Result<HttpResponse, OAuthError> {
+    let client = authenticate_client(&req, form.client_id.as_deref(), form.client_secret.as_deref(), &pool).await?;
+
+    if let Ok(claims) = jwt_service.verify_oauth_token(&form.token).await {
+        if claims.client_id == client.client_id {
+            jwt_service.revoke_oauth_token(&claims).await.map_err(OAuthError::server_error)?;
+        }
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
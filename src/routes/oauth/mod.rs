@@ -0,0 +1,11 @@
+mod authorize;
+mod client;
+mod introspection;
+mod token;
+
+pub use authorize::authorize;
+pub use authorize::authorize_decision;
+pub use client::register_oauth_client;
+pub use introspection::introspect;
+pub use introspection::revoke;
+pub use token::token;
@@ -0,0 +1,193 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use crate::auth::{pkce_challenge, JwtService, OAUTH_ACCESS_TOKEN_MINUTES};
+use crate::error::OAuthError;
+use crate::routes::oauth::authorize::hash_code;
+use crate::routes::oauth::client::{authenticate_client, OAuthClient};
+
+#[derive(Debug, Deserialize)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    pub scope: String,
+}
+
+struct AuthorizationCode {
+    client_id: String,
+    email: String,
+    redirect_uri: String,
+    scope: String,
+    code_challenge: String,
+    expires_at: DateTime<Utc>,
+}
+
+//POST /oauth/token : authorization_code(+PKCE), refresh_token, client_credentials
+#[tracing::instrument(name = "OAuth token", skip(req, form, pool, jwt_service), fields(grant_type = %form.grant_type))]
+pub async fn token(
+    req: HttpRequest,
+    form: web::Form<TokenRequest>,
+    pool: web::Data<PgPool>,
+    jwt_service: web::Data<JwtService>,
+) -> Result<HttpResponse, OAuthError> {
+    let client = authenticate_client(&req, form.client_id.as_deref(), form.client_secret.as_deref(), &pool).await?;
+    if !client.allows_grant(&form.grant_type) {
+        return Err(match form.grant_type.as_str() {
+            "authorization_code" | "refresh_token" | "client_credentials" => {
+                OAuthError::unauthorized_client("Grant type not allowed for this client")
+            }
+            _ => OAuthError::unsupported_grant_type(form.grant_type.clone()),
+        });
+    }
+
+    let response = match form.grant_type.as_str() {
+        "authorization_code" => authorization_code_grant(&form, &client, &pool, &jwt_service).await?,
+        "refresh_token" => refresh_token_grant(&form, &client, &jwt_service).await?,
+        "client_credentials" => client_credentials_grant(&form, &client, &jwt_service).await?,
+        other => return Err(OAuthError::unsupported_grant_type(other.to_owned())),
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(response))
+}
+
+async fn authorization_code_grant(
+    form: &TokenRequest,
+    client: &OAuthClient,
+    pool: &PgPool,
+    jwt_service: &JwtService,
+) -> Result<TokenResponse, OAuthError> {
+    let code = form.code.as_deref().ok_or_else(|| OAuthError::invalid_request("Missing code"))?;
+    let code_verifier = form.code_verifier.as_deref().ok_or_else(|| OAuthError::invalid_request("Missing code_verifier"))?;
+
+    //DELETE ... RETURNING으로 꺼내므로 같은 code는 한 번만 사용할 수 있다.
+    let stored = take_authorization_code(pool, code)
+        .await
+        .map_err(OAuthError::server_error)?
+        .ok_or_else(|| OAuthError::invalid_grant("Invalid authorization code"))?;
+    if stored.client_id != client.client_id
+        || Some(stored.redirect_uri.as_str()) != form.redirect_uri.as_deref()
+        || stored.expires_at < Utc::now()
+    {
+        return Err(OAuthError::invalid_grant("Invalid authorization code"));
+    }
+    if pkce_challenge(code_verifier) != stored.code_challenge {
+        return Err(OAuthError::invalid_grant("PKCE verification failed"));
+    }
+
+    issue_tokens(jwt_service, client, &stored.email, &stored.scope).await
+}
+
+async fn refresh_token_grant(
+    form: &TokenRequest,
+    client: &OAuthClient,
+    jwt_service: &JwtService,
+) -> Result<TokenResponse, OAuthError> {
+    let refresh_token = form.refresh_token.as_deref().ok_or_else(|| OAuthError::invalid_request("Missing refresh_token"))?;
+    let claims = jwt_service.verify_oauth_token(refresh_token).await
+        .map_err(|e| OAuthError::invalid_grant(e.to_string()))?;
+    if claims.token_use != "refresh" || claims.client_id != client.client_id {
+        return Err(OAuthError::invalid_grant("Invalid refresh token"));
+    }
+    //scope는 줄이는 것만 허용한다.
+    let scope = match form.scope.as_deref() {
+        Some(requested) => {
+            let granted: Vec<&str> = claims.scope.split_whitespace().collect();
+            if requested.split_whitespace().any(|s| !granted.contains(&s)) {
+                return Err(OAuthError::invalid_scope("Requested scope exceeds the original grant"));
+            }
+            requested.split_whitespace().collect::<Vec<_>>().join(" ")
+        }
+        None => claims.scope.clone(),
+    };
+    //rotate : 사용한 refresh token은 원자적으로 소비한다. 이미 사용된 토큰이면 거부한다.
+    if !jwt_service.consume_oauth_refresh_token(&claims).await.map_err(OAuthError::server_error)? {
+        return Err(OAuthError::invalid_grant("Refresh token already used"));
+    }
+
+    issue_tokens(jwt_service, client, &claims.sub, &scope).await
+}
+
+async fn client_credentials_grant(
+    form: &TokenRequest,
+    client: &OAuthClient,
+    jwt_service: &JwtService,
+) -> Result<TokenResponse, OAuthError> {
+    if !client.is_confidential() {
+        return Err(OAuthError::unauthorized_client("client_credentials requires a confidential client"));
+    }
+    let scope = client.resolve_scope(form.scope.as_deref())?;
+    let (access_token, _) = jwt_service.create_oauth_token(&client.client_id, &client.client_id, &scope, "access").await
+        .map_err(OAuthError::server_error)?;
+
+    Ok(TokenResponse {
+        access_token,
+        token_type: "Bearer",
+        expires_in: OAUTH_ACCESS_TOKEN_MINUTES * 60,
+        refresh_token: None,
+        scope,
+    })
+}
+
+async fn issue_tokens(
+    jwt_service: &JwtService,
+    client: &OAuthClient,
+    sub: &str,
+    scope: &str,
+) -> Result<TokenResponse, OAuthError> {
+    let (access_token, _) = jwt_service.create_oauth_token(sub, &client.client_id, scope, "access").await
+        .map_err(OAuthError::server_error)?;
+    let refresh_token = if client.allows_grant("refresh_token") {
+        let (token, _) = jwt_service.create_oauth_token(sub, &client.client_id, scope, "refresh").await
+            .map_err(OAuthError::server_error)?;
+        Some(token)
+    } else {
+        None
+    };
+
+    Ok(TokenResponse {
+        access_token,
+        token_type: "Bearer",
+        expires_in: OAUTH_ACCESS_TOKEN_MINUTES * 60,
+        refresh_token,
+        scope: scope.to_owned(),
+    })
+}
+
+async fn take_authorization_code(
+    pool: &PgPool,
+    code: &str,
+) -> Result<Option<AuthorizationCode>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        AuthorizationCode,
+        r#"
+        DELETE FROM oauth_authorization_codes
+        WHERE code_hash = $1
+        RETURNING client_id, email, redirect_uri, scope, code_challenge, expires_at
+        "#,
+        hash_code(code)
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to perform a query")?;
+
+    Ok(row)
+}
@@ -0,0 +1,4 @@
+mod page;
+
+pub use page::search_json;
+pub use page::search_page;
\ No newline at end of file
@@ -0,0 +1,44 @@
+use actix_web::{http::header::ContentType, web, HttpResponse, Result};
+use askama::Template;
+use serde::Deserialize;
+use sqlx::PgPool;
+use crate::documents::{search_documents, SearchResults};
+use crate::error::e500;
+
+//한 번에 보여주는 검색 결과 수
+const SEARCH_LIMIT: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+    //facet 링크로 챕터를 좁힌 경우
+    pub chapter: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "search.html")]
+struct SearchTemplate {
+    results: SearchResults,
+}
+
+//GET /search?q= : 검색 결과 페이지
+pub async fn search_page(
+    query: web::Query<SearchQuery>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let results = search_documents(&pool, &query.q, query.chapter.as_deref(), SEARCH_LIMIT).await.map_err(e500)?;
+    let rendered = SearchTemplate { results }.render().map_err(e500)?;
+
+    Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+}
+
+//GET /api/search?q= : 같은 결과를 JSON으로
+pub async fn search_json(
+    query: web::Query<SearchQuery>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let results = search_documents(&pool, &query.q, query.chapter.as_deref(), SEARCH_LIMIT).await.map_err(e500)?;
+
+    Ok(HttpResponse::Ok().json(results))
+}
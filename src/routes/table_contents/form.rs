@@ -1,14 +1,90 @@
-use actix_web::{HttpResponse, Result, http::header::ContentType,};
+use actix_web::{web, HttpRequest, HttpResponse, Result, http::header::ContentType,};
 use askama::Template; 
+use serde::Deserialize;
+use sqlx::PgPool;
+use crate::auth::{JwtService, TypedSession};
+use crate::documents::{
+    build_toc, find_tag, list_published_documents, list_tags, reading_progress, tagged_document_ids, RenderCache,
+    TableOfContents, Tag, TagCount,
+};
+use crate::error::e500;
+use crate::routes::current_user_email;
 
 #[derive(Template)]
 #[template(path = "table_contents.html")]
-struct TableContentsTemplate;
+struct TableContentsTemplate {
+    toc: TableOfContents,
+    //로그인했으면 읽음 표시를 보여준다.
+    logged_in: bool,
+    //목차를 거르는 태그 목록과 지금 선택한 태그
+    tags: Vec<TagCount>,
+    tag: Option<Tag>,
+}
 
-pub async fn contents() -> Result<HttpResponse> {
-    let template = TableContentsTemplate;
+//?tag=tracing : 그 태그가 붙은 문서만으로 목차를 만든다.
+#[derive(Debug, Deserialize)]
+pub struct ContentsQuery {
+    pub tag: Option<String>,
+}
+
+/*
+GET /
+    -> documents 테이블의 문서와 각 문서의 제목으로 목차를 만든다. (문서를 추가 / 수정하면 목차도 바뀐다.)
+    -> 로그인한 사용자는 끝까지 읽은 문서에 완료 표시, 읽고 있는 문서에 읽는 중 표시, 챕터마다 진행률을 보여준다.
+    -> ?tag= 로 태그가 붙은 문서만 볼 수 있다. (없는 태그면 빈 목차)
+*/
+pub async fn contents(
+    req: HttpRequest,
+    query: web::Query<ContentsQuery>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+    cache: web::Data<RenderCache>,
+) -> Result<HttpResponse> {
+    let (toc, email) = load_toc(&req, query.tag.as_deref(), &session, &jwt_service, &pool, &cache).await?;
+    let tag = match &query.tag {
+        Some(tag) => find_tag(tag, &pool).await.map_err(e500)?,
+        None => None,
+    };
+    let tags = list_tags(&pool).await.map_err(e500)?.into_iter().filter(|tag| tag.count > 0).collect();
+    let template = TableContentsTemplate { toc, logged_in: email.is_some(), tags, tag };
     let rendered = template.render().map_err(|e| {
         actix_web::error::ErrorInternalServerError(e)
     })?;
     Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
-}
\ No newline at end of file
+}
+
+//GET /api/contents : 같은 목차를 JSON으로 (프론트엔드용)
+pub async fn contents_json(
+    req: HttpRequest,
+    query: web::Query<ContentsQuery>,
+    session: TypedSession,
+    jwt_service: web::Data<JwtService>,
+    pool: web::Data<PgPool>,
+    cache: web::Data<RenderCache>,
+) -> Result<HttpResponse> {
+    let (toc, _) = load_toc(&req, query.tag.as_deref(), &session, &jwt_service, &pool, &cache).await?;
+    Ok(HttpResponse::Ok().json(toc))
+}
+
+async fn load_toc(
+    req: &HttpRequest,
+    tag: Option<&str>,
+    session: &TypedSession,
+    jwt_service: &JwtService,
+    pool: &PgPool,
+    cache: &RenderCache,
+) -> Result<(TableOfContents, Option<String>)> {
+    let mut documents = list_published_documents(pool).await.map_err(e500)?;
+    if let Some(tag) = tag {
+        let tagged = tagged_document_ids(tag, pool).await.map_err(e500)?;
+        documents.retain(|document| tagged.contains(&document.id));
+    }
+    let email = current_user_email(req, session, jwt_service).await;
+    let progress = match &email {
+        Some(email) => reading_progress(email, pool).await.map_err(e500)?,
+        None => Default::default(),
+    };
+
+    Ok((build_toc(&documents, cache, &progress), email))
+}
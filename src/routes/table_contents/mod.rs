@@ -1,3 +1,4 @@
 mod form;
 
-pub use form::contents;
\ No newline at end of file
+pub use form::contents;
+pub use form::contents_json;
\ No newline at end of file
@@ -0,0 +1,5 @@
+mod tag;
+
+pub use tag::tag_list;
+pub use tag::tag_page;
+pub use tag::tags_json;
\ No newline at end of file
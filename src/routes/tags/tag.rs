@@ -0,0 +1,54 @@
+use actix_web::{http::header::ContentType, web, HttpResponse, Result};
+use askama::Template;
+use sqlx::PgPool;
+use crate::documents::{find_tag, list_published_documents, list_tags, tagged_document_ids, Document, Tag, TagCount};
+use crate::error::e500;
+use crate::startup::NotFoundTemplate;
+
+#[derive(Template)]
+#[template(path = "tags/list.html")]
+struct TagListTemplate {
+    tags: Vec<TagCount>,
+}
+
+#[derive(Template)]
+#[template(path = "tags/tag.html")]
+struct TagTemplate {
+    tag: Tag,
+    //목차 순서
+    documents: Vec<Document>,
+}
+
+//GET /tags : 태그 목록 (게시된 문서가 있는 태그만)
+pub async fn tag_list(pool: web::Data<PgPool>) -> Result<HttpResponse> {
+    let tags = list_tags(&pool).await.map_err(e500)?.into_iter().filter(|tag| tag.count > 0).collect();
+    let rendered = TagListTemplate { tags }.render().map_err(e500)?;
+
+    Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+}
+
+//GET /api/tags : 모든 태그와 게시된 문서 수
+pub async fn tags_json(pool: web::Data<PgPool>) -> Result<HttpResponse> {
+    let tags = list_tags(&pool).await.map_err(e500)?;
+
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+//GET /tags/{slug} : 태그가 붙은 게시된 문서
+pub async fn tag_page(
+    path: web::Path<String>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse> {
+    let slug = path.into_inner();
+    let Some(tag) = find_tag(&slug, &pool).await.map_err(e500)? else {
+        let rendered = NotFoundTemplate.render().map_err(e500)?;
+        return Ok(HttpResponse::NotFound().content_type(ContentType::html()).body(rendered));
+    };
+
+    let tagged = tagged_document_ids(&tag.slug, &pool).await.map_err(e500)?;
+    let mut documents = list_published_documents(&pool).await.map_err(e500)?;
+    documents.retain(|document| tagged.contains(&document.id));
+    let rendered = TagTemplate { tag, documents }.render().map_err(e500)?;
+
+    Ok(HttpResponse::Ok().content_type(ContentType::html()).body(rendered))
+}
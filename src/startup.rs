@@ -5,6 +5,7 @@ use actix_session::{
 };
 use actix_web::cookie::time::Duration;
 //use actix_web::middleware::from_fn;
+use actix_web_lab::middleware::from_fn;
 use actix_web::{
     web, App, HttpServer, HttpResponse,
     dev::Server,
@@ -15,17 +16,29 @@ use actix_web_flash_messages::{
     FlashMessagesFramework,
     storage::CookieMessageStore
 };
-use secrecy::{
-    ExposeSecret,
-    Secret,
-};
+use secrecy::{ExposeSecret, Secret};
 use std::net::TcpListener;
+use std::sync::Arc;
 use tracing_actix_web::TracingLogger;
 use sqlx::{postgres::PgPoolOptions, PgPool};
-use crate::auth::JwtService;
-use crate::configuration::{DatabaseSettings, Settings};
+use crate::auth::{
+    AuthCookies, JwtService, OidcClient, ProofOfWork, build_webauthn, impersonation_audit,
+    TokenStore, StoreBackend, SessionBackend, RedisTokenStore, PostgresTokenStore, MemoryTokenStore,
+};
+use crate::configuration::{DatabaseSettings, Settings, StoreSettings};
+use crate::documents::{reindex_documents, spawn_publish_scheduler, RenderCache};
+use crate::mailer::{LocalOutboxMailer, Mailer};
 use crate::routes::{
-    contents, home_session, home_jwt, validate_session, validate_jwt, logout, register, registration
+    contents, contents_json, home_session, home_jwt, validate_session, validate_jwt, logout, register, registration,
+    start_impersonation, stop_impersonation, list_audit_events, export_audit_events,
+    send_magic_link, consume_magic_link, oidc_start, oidc_callback,
+    authorize, authorize_decision, token, introspect, revoke, register_oauth_client,
+    passkey_register_start, passkey_register_finish, passkey_login_start, passkey_login_finish,
+    change_password, force_logout, reauthenticate, create_invitation, list_invitations, issue_challenge,
+    document, document_list, document_editor, add_document, update_document, preview_document,
+    document_revisions, document_diff, rollback_document, submit_document, add_document_review, document_reviews,
+    archive_document, search_page, search_json, tag_list, tag_page, tags_json, update_document_tags,
+    glossary_list, glossary_json, fragment, report_progress,
 };
 use askama::Template;
 
@@ -38,15 +51,19 @@ pub struct Application {
 impl Application {
     //build 함수를 Application에 대한 생성자로 변환 / 비동기 함수이다. -> 초기화 실수 없이 안전하게 실행 환경을 만들 수 있다.
     pub async fn build(configuration: Settings) -> Result<Self, anyhow::Error> {
+        configuration.cookie.validate().map_err(anyhow::Error::msg)?;
         let connection_pool = get_connection_pool(&configuration.database);
         let address = format!("{}:{}", configuration.application.host, configuration.application.port);
         //TCP 네트워크 서버를 구현할 때, 특정 IP주소와 포트로 들어오는 클라이언트의 TCP연결 요청을 받아들이고 대기하는 역할을 하는 표준 라이브러리의 구조체 이다.
         let listener = TcpListener::bind(&address)?;
         let port = listener.local_addr().unwrap().port();
-        let server = run(
-            listener, connection_pool, configuration.application.base_url, configuration.application.hmac_secret,
-            configuration.redis_uri, configuration.jwt.jwt_secret,
-        ).await?;
+        //승인된 예약 문서를 publish_at에 게시한다.
+        spawn_publish_scheduler(connection_pool.clone());
+        //테이블에 직접 넣거나 고친 문서도 검색되도록 시작할 때 색인을 다시 만든다.
+        if let Err(e) = reindex_documents(&connection_pool).await {
+            tracing::error!(error.cause_chain = ?e, "Failed to reindex documents");
+        }
+        let server = run(listener, connection_pool, configuration).await?;
 
         Ok(Self{port, server})
     }
@@ -65,17 +82,65 @@ impl Application {
 //Actix Web의 web::Data로 등로되어 여러 핸들러에서 공유 가능한 상태로 만든다. 이 값을 통해 기본 URL(ex. APi 서버의 도메인) 정보를 전달한다.
 pub struct ApplicationBaseUrl(pub String);
 
+/*
+설정(store.backend)에 따라 토큰 저장소와 세션 저장소를 만든다.
+    -> 두 저장소는 항상 같은 백엔드를 사용한다.
+    -> postgres는 만료된 행을 스스로 지우지 않으므로 주기적으로 정리하는 백그라운드 작업을 띄운다.
+*/
+pub async fn build_stores(
+    settings: &StoreSettings, redis_uri: &Secret<String>, db_pool: &PgPool,
+) -> Result<(Arc<dyn TokenStore>, SessionBackend), anyhow::Error> {
+    let stores: (Arc<dyn TokenStore>, SessionBackend) = match settings.backend {
+        StoreBackend::Redis => (
+            Arc::new(RedisTokenStore::new(redis_uri.expose_secret()).await?),
+            SessionBackend::Redis(RedisSessionStore::new(redis_uri.expose_secret()).await?),
+        ),
+        StoreBackend::Postgres => {
+            let store = PostgresTokenStore::new(db_pool.clone());
+            let purger = store.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = purger.purge_expired().await {
+                        tracing::error!(error.cause_chain = ?e, "Failed to purge expired tokens");
+                    }
+                }
+            });
+            (Arc::new(store), SessionBackend::Postgres(db_pool.clone()))
+        }
+        StoreBackend::Memory => (Arc::new(MemoryTokenStore::new()), SessionBackend::memory()),
+    };
+
+    Ok(stores)
+}
+
+//설정 항목마다 인자를 늘리지 않도록 설정 전체를 받아서 필요한 값을 꺼내 쓴다.
 async fn run(
-    listener: TcpListener, db_pool: PgPool, base_url: String, hamc_secret: Secret<String>, redis_uri: Secret<String>, jwt_secret: Secret<String>,
+    listener: TcpListener, db_pool: PgPool, configuration: Settings,
 ) -> Result<Server, anyhow::Error> {
+    let Settings { application, redis_uri, jwt, email, oidc, webauthn, auth_policy, store, cookie, registration: registration_settings, challenge, .. } = configuration;
+    let (base_url, hamc_secret, jwt_secret) = (application.base_url, application.hmac_secret, jwt.jwt_secret);
+    let (token_store, session_backend) = build_stores(&store, &redis_uri, &db_pool).await?;
     let db_pool = web::Data::new(db_pool);
     let base_url = web::Data::new(ApplicationBaseUrl(base_url));
     let secret_key = Key::from(hamc_secret.expose_secret().as_bytes());
     let message_store = CookieMessageStore::builder(secret_key.clone()).build();
     let message_framework = FlashMessagesFramework::builder(message_store).build();
-    let redis_store = RedisSessionStore::new(redis_uri.expose_secret()).await?;
-    let redis_client = redis::Client::open(redis_uri.expose_secret().as_str()).expect("Failed to create Redis client");
-    let jwt_service = web::Data::new(JwtService::new(jwt_secret.expose_secret().clone(), redis_client.clone()));
+    let auth_cookies = AuthCookies::new(cookie);
+    let jwt_service = web::Data::new(JwtService::new(jwt_secret.expose_secret().clone(), token_store.clone(), auth_policy, auth_cookies.clone()));
+    let pow = web::Data::new(ProofOfWork::new(token_store.clone(), challenge));
+    //카운터 등 토큰 외의 용도로도 쓸 수 있도록 저장소 자체도 등록한다.
+    let token_store: web::Data<dyn TokenStore> = web::Data::from(token_store);
+    let oidc_client = web::Data::new(OidcClient::new(&oidc));
+    //TypedSession이 세션 만료(idle / absolute timeout)를 판단할 때 사용한다.
+    let session_ttl = Duration::seconds(auth_policy.session_store_ttl().num_seconds());
+    let auth_policy = web::Data::new(auth_policy);
+    let webauthn = web::Data::new(build_webauthn(&webauthn)?);
+    let registration_settings = web::Data::new(registration_settings);
+    let render_cache = web::Data::new(RenderCache::new());
+    //트레이트 객체로 등록해서 핸들러는 web::Data<dyn Mailer>로 주입받는다.
+    let mailer: web::Data<dyn Mailer> = web::Data::from(std::sync::Arc::new(LocalOutboxMailer::new(&email)) as std::sync::Arc<dyn Mailer>);
     /*
     HttpServer::new 클로저 내에서 App::new()를 만들고 미들웨어, 라우트, 공유 상태를 설정한다.s
     클로저를 인자로 받아 실행 하는 이유
@@ -85,12 +150,14 @@ async fn run(
     */
     let server = HttpServer::new(move || {
         App::new()
+            //대리 로그인 감사 로그 / 세션 미들웨어보다 안쪽에 있어야 세션을 읽을 수 있다.
+            .wrap(from_fn(impersonation_audit))
             .wrap(message_framework.clone())
             .wrap(
                 //버전이 0.10이 되면서 빌더 패턴이 도입이 되었음. 그래서 SessionMiddlewareBuilder의 메서드로 옮겨짐.
-                SessionMiddleware::builder(redis_store.clone(), secret_key.clone())
+                auth_cookies.configure_session(SessionMiddleware::builder(session_backend.clone(), secret_key.clone()))
                         .session_lifecycle(
-                            PersistentSession::default().session_ttl(Duration::days(7))
+                            PersistentSession::default().session_ttl(session_ttl)
                         )
                         .build()
             )
@@ -99,19 +166,68 @@ async fn run(
             //정적 파일
             .service(actix_files::Files::new("/css", "./static/css"))
             .service(actix_files::Files::new("/js", "./static/js"))
-            .service(actix_files::Files::new("/templates", "./templates"))
             //동적 라우트
             .route("/", web::get().to(contents))
+            .route("/api/contents", web::get().to(contents_json))
             //.route("/tracing_basic", web::get().to(tracing_basic))
             //404 처리
             .default_service(web::route().to(not_found))
             .route("/home_session", web::get().to(home_session))
+            .route("/docs/{chapter}/{slug}", web::get().to(document))
+            .route("/api/progress/{id}", web::post().to(report_progress))
+            .route("/search", web::get().to(search_page))
+            .route("/api/search", web::get().to(search_json))
+            .route("/tags", web::get().to(tag_list))
+            .route("/tags/{slug}", web::get().to(tag_page))
+            .route("/api/tags", web::get().to(tags_json))
+            .route("/glossary", web::get().to(glossary_list))
+            .route("/api/glossary", web::get().to(glossary_json))
+            //팝업 조각 (templates 디렉터리는 그대로 내보내지 않는다.)
+            .route("/fragments/{name}", web::get().to(fragment))
             .route("/home_jwt", web::get().to(home_jwt))
             .route("/registration", web::get().to(registration))
             .route("/logout", web::post().to(logout))
             .route("/api/login_session", web::post().to(validate_session))
             .route("/api/login_jwt", web::post().to(validate_jwt))
             .route("/api/register", web::post().to(register))
+            .route("/api/password", web::post().to(change_password))
+            .route("/api/reauth", web::post().to(reauthenticate))
+            .route("/api/challenge", web::get().to(issue_challenge))
+            .route("/api/login/magic", web::post().to(send_magic_link))
+            .route("/login/magic", web::get().to(consume_magic_link))
+            .route("/auth/oidc/{provider}/start", web::get().to(oidc_start))
+            .route("/auth/oidc/{provider}/callback", web::get().to(oidc_callback))
+            .route("/api/webauthn/register/start", web::post().to(passkey_register_start))
+            .route("/api/webauthn/register/finish", web::post().to(passkey_register_finish))
+            .route("/api/webauthn/login/start", web::post().to(passkey_login_start))
+            .route("/api/webauthn/login/finish", web::post().to(passkey_login_finish))
+            .route("/admin/impersonate", web::post().to(start_impersonation))
+            .route("/admin/impersonate/stop", web::post().to(stop_impersonation))
+            .route("/admin/force_logout", web::post().to(force_logout))
+            .route("/admin/invitations", web::post().to(create_invitation))
+            .route("/admin/invitations", web::get().to(list_invitations))
+            .route("/admin/audit_events", web::get().to(list_audit_events))
+            .route("/admin/audit_events/export", web::get().to(export_audit_events))
+            .route("/admin/oauth/clients", web::post().to(register_oauth_client))
+            .route("/admin/docs", web::get().to(document_list))
+            .route("/admin/docs", web::post().to(add_document))
+            .route("/admin/docs/preview", web::post().to(preview_document))
+            .route("/admin/docs/{id}", web::put().to(update_document))
+            .route("/admin/docs/{id}/edit", web::get().to(document_editor))
+            .route("/admin/docs/{id}/revisions", web::get().to(document_revisions))
+            .route("/admin/docs/{id}/diff", web::get().to(document_diff))
+            .route("/admin/docs/{id}/rollback", web::post().to(rollback_document))
+            .route("/admin/docs/{id}/submit", web::post().to(submit_document))
+            .route("/admin/docs/{id}/reviews", web::post().to(add_document_review))
+            .route("/admin/docs/{id}/reviews", web::get().to(document_reviews))
+            .route("/admin/docs/{id}/archive", web::post().to(archive_document))
+            .route("/admin/docs/{id}/tags", web::put().to(update_document_tags))
+            //OAuth2 인가 서버 (내부 도구용)
+            .route("/oauth/authorize", web::get().to(authorize))
+            .route("/oauth/authorize", web::post().to(authorize_decision))
+            .route("/oauth/token", web::post().to(token))
+            .route("/oauth/introspect", web::post().to(introspect))
+            .route("/oauth/revoke", web::post().to(revoke))
             /*
             DB풀과 베이스 URL정보를 애플리케이션 상태에 추가한다.
             Actix Web에서 애플리케이션 전역 상태를 주입하는 메서드이다.
@@ -119,6 +235,14 @@ async fn run(
             .app_data(db_pool.clone())
             .app_data(base_url.clone())
             .app_data(jwt_service.clone())
+            .app_data(token_store.clone())
+            .app_data(mailer.clone())
+            .app_data(oidc_client.clone())
+            .app_data(webauthn.clone())
+            .app_data(auth_policy.clone())
+            .app_data(registration_settings.clone())
+            .app_data(pow.clone())
+            .app_data(render_cache.clone())
     })
     .listen(listener)?
     .run();
@@ -132,7 +256,7 @@ pub fn get_connection_pool(configuration: &DatabaseSettings) -> PgPool {
 
 #[derive(Template)]
 #[template(path = "404.html")]
-struct NotFoundTemplate;
+pub struct NotFoundTemplate;
 
 //404 처리 HTML
 pub async fn not_found() -> Result<HttpResponse> {
@@ -141,5 +265,5 @@ pub async fn not_found() -> Result<HttpResponse> {
         actix_web::error::ErrorInternalServerError(e)
     })?;
 
-    Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(rendered))
+    Ok(HttpResponse::NotFound().content_type("text/html; charset=utf-8").body(rendered))
 }
\ No newline at end of file
@@ -78,4 +78,21 @@ document.querySelectorAll('.popup-overlay').forEach(overlay => {
             closePopup(this.id);
         }
     });
-});
\ No newline at end of file
+});
+
+// 용어 링크 / 마우스를 잠시 올려 두면 용어 설명 팝업을 연다. (클릭하면 용어집 페이지로 이동)
+let glossaryTimer = null;
+document.addEventListener('mouseover', function(e) {
+    const link = e.target.closest ? e.target.closest('a.glossary-term') : null;
+    if (!link || !link.dataset.popup) {
+        return;
+    }
+    clearTimeout(glossaryTimer);
+    glossaryTimer = setTimeout(() => showPopup(link.dataset.popup, 'popup-basic'), 400);
+});
+
+document.addEventListener('mouseout', function(e) {
+    if (e.target.closest && e.target.closest('a.glossary-term')) {
+        clearTimeout(glossaryTimer);
+    }
+});
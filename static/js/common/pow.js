@@ -0,0 +1,36 @@
+/*
+작업 증명(proof-of-work) 챌린지 풀이
+    -> 서버에서 챌린지를 받아 sha256("{challenge}:{nonce}")의 앞 difficulty 비트가 0이 되는 nonce를 찾는다.
+    -> 서버 검증(src/auth/challenge/proof_of_work.rs)과 같은 계산이어야 한다.
+*/
+
+// 해시의 선행 0 비트 수
+function leadingZeroBits(bytes) {
+    let bits = 0;
+    for (const byte of bytes) {
+        if (byte === 0) {
+            bits += 8;
+            continue;
+        }
+        bits += Math.clz32(byte) - 24;
+        break;
+    }
+    return bits;
+}
+
+// purpose : 'register' 또는 'login' / 요청 본문에 그대로 합칠 수 있는 { pow_challenge, pow_nonce }를 반환
+async function solveChallenge(purpose) {
+    const response = await fetch(`/api/challenge?purpose=${purpose}`);
+    if (!response.ok) {
+        throw new Error('챌린지를 받을 수 없습니다.');
+    }
+    const { challenge, difficulty } = await response.json();
+
+    const encoder = new TextEncoder();
+    for (let nonce = 0; ; nonce++) {
+        const digest = await crypto.subtle.digest('SHA-256', encoder.encode(`${challenge}:${nonce}`));
+        if (leadingZeroBits(new Uint8Array(digest)) >= difficulty) {
+            return { pow_challenge: challenge, pow_nonce: String(nonce) };
+        }
+    }
+}
@@ -0,0 +1,157 @@
+/*
+문서 편집기 (/admin/docs)
+    -> 본문을 입력하면 잠시 뒤 서버에서 렌더링한 미리보기를 보여준다. (문서 페이지와 같은 변환, sanitize)
+    -> 저장할 때 편집을 시작한 리비전을 함께 보내고, 409면 다른 편집자가 먼저 저장한 것이므로 덮어쓰지 않는다.
+*/
+const PREVIEW_DELAY_MS = 400;
+let previewTimer = null;
+
+function editorForm() {
+    return document.getElementById('editor-form');
+}
+
+async function sendJson(method, url, body) {
+    const response = await fetch(url, {
+        method,
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(body),
+    });
+    const data = await response.json().catch(() => ({}));
+    return { response, data };
+}
+
+function schedulePreview() {
+    clearTimeout(previewTimer);
+    previewTimer = setTimeout(renderPreview, PREVIEW_DELAY_MS);
+}
+
+async function renderPreview() {
+    const form = editorForm();
+    const { response, data } = await sendJson('POST', '/admin/docs/preview', { body: form.body.value });
+    if (response.ok) {
+        document.getElementById('editor-preview').innerHTML = data.html;
+    }
+}
+
+// 저장 / 되돌리기 응답 처리
+function handleSaved(response, data) {
+    if (response.status === 409) {
+        alert(`다른 편집자가 먼저 저장했습니다. (현재 리비전 ${data.current_revision})\n내용을 복사해 둔 뒤 페이지를 새로 고쳐 주세요.`);
+        return;
+    }
+    if (!response.ok) {
+        alert('저장하지 못했습니다.');
+        return;
+    }
+    // 리비전 목록을 다시 그리기 위해 새로 고침
+    location.reload();
+}
+
+async function saveDocument(event) {
+    event.preventDefault();
+    const form = editorForm();
+    const { response, data } = await sendJson('PUT', `/admin/docs/${form.dataset.id}`, {
+        title: form.title.value,
+        body: form.body.value,
+        message: form.message.value,
+        revision: Number(form.dataset.revision),
+    });
+    handleSaved(response, data);
+}
+
+async function rollbackDocument(revision) {
+    if (!confirm(`리비전 ${revision}(으)로 되돌릴까요?`)) {
+        return;
+    }
+    const form = editorForm();
+    const { response, data } = await sendJson('POST', `/admin/docs/${form.dataset.id}/rollback`, {
+        revision,
+        expected_revision: Number(form.dataset.revision),
+    });
+    handleSaved(response, data);
+}
+
+async function createDocument(event) {
+    event.preventDefault();
+    const form = event.target;
+    const { response, data } = await sendJson('POST', '/admin/docs', {
+        section: form.section.value,
+        chapter: form.chapter.value,
+        slug: form.slug.value,
+        position: Number(form.position.value),
+        title: form.title.value,
+        body: `# ${form.title.value}\n`,
+    });
+    if (response.status === 409) {
+        alert('같은 챕터에 같은 slug의 문서가 있습니다.');
+        return;
+    }
+    if (!response.ok) {
+        alert('문서를 만들지 못했습니다.');
+        return;
+    }
+    location.href = `/admin/docs/${data.id}/edit`;
+}
+
+document.addEventListener('DOMContentLoaded', () => {
+    if (editorForm()) {
+        renderPreview();
+    }
+});
+
+// 게시 흐름 (검토 요청, 승인 / 수정 요청, 보관) 응답 처리
+function handleTransition(response, data) {
+    if (response.status === 409) {
+        alert(data.error === 'conflict'
+            ? `검토하는 동안 문서가 다시 저장되었습니다. (현재 리비전 ${data.current_revision})`
+            : `지금 상태(${data.status})에서는 할 수 없습니다.`);
+        return;
+    }
+    if (!response.ok) {
+        alert('처리하지 못했습니다.');
+        return;
+    }
+    location.reload();
+}
+
+async function submitDocument() {
+    const form = editorForm();
+    const publishAt = document.getElementById('publish-at').value;
+    const { response, data } = await sendJson('POST', `/admin/docs/${form.dataset.id}/submit`, {
+        publish_at: publishAt ? new Date(publishAt).toISOString() : null,
+    });
+    handleTransition(response, data);
+}
+
+async function reviewDocument(decision) {
+    const form = editorForm();
+    const { response, data } = await sendJson('POST', `/admin/docs/${form.dataset.id}/reviews`, {
+        revision: Number(form.dataset.revision),
+        decision,
+        comment: document.getElementById('review-comment').value,
+    });
+    handleTransition(response, data);
+}
+
+async function archiveDocument() {
+    if (!confirm('문서를 보관하면 방문자에게 보이지 않습니다. 보관할까요?')) {
+        return;
+    }
+    const form = editorForm();
+    const { response, data } = await sendJson('POST', `/admin/docs/${form.dataset.id}/archive`, {});
+    handleTransition(response, data);
+}
+
+async function saveTags() {
+    const form = editorForm();
+    const tags = document.getElementById('document-tags').value
+        .split(',')
+        .map((tag) => tag.trim())
+        .filter((tag) => tag.length > 0);
+    const { response } = await sendJson('PUT', `/admin/docs/${form.dataset.id}/tags`, { tags });
+    if (!response.ok) {
+        alert('태그는 영문 소문자, 숫자, - 만 사용할 수 있습니다.');
+        return;
+    }
+    location.reload();
+}
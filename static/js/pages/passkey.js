@@ -0,0 +1,107 @@
+//WebAuthn은 바이너리(ArrayBuffer)를 사용하고, 서버(webauthn-rs)는 base64url 문자열을 사용하므로 서로 변환한다.
+function base64urlToBuffer(value) {
+    const base64 = value.replace(/-/g, '+').replace(/_/g, '/');
+    const padded = base64 + '='.repeat((4 - base64.length % 4) % 4);
+    return Uint8Array.from(atob(padded), c => c.charCodeAt(0)).buffer;
+}
+
+function bufferToBase64url(buffer) {
+    const bytes = String.fromCharCode(...new Uint8Array(buffer));
+    return btoa(bytes).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+}
+
+//패스키 등록 (로그인 상태에서 호출)
+async function registerPasskey() {
+    try {
+        const startResponse = await fetch('/api/webauthn/register/start', { method: 'POST' });
+        if(!startResponse.ok) {
+            alert('로그인 후 패스키를 등록할 수 있습니다.');
+            return;
+        }
+        const { publicKey } = await startResponse.json();
+        publicKey.challenge = base64urlToBuffer(publicKey.challenge);
+        publicKey.user.id = base64urlToBuffer(publicKey.user.id);
+        (publicKey.excludeCredentials || []).forEach(c => c.id = base64urlToBuffer(c.id));
+
+        const credential = await navigator.credentials.create({ publicKey });
+        const name = prompt('패스키 이름을 입력하세요', '내 기기') || '';
+        const finishResponse = await fetch('/api/webauthn/register/finish?name=' + encodeURIComponent(name), {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({
+                id: credential.id,
+                rawId: bufferToBase64url(credential.rawId),
+                type: credential.type,
+                response: {
+                    attestationObject: bufferToBase64url(credential.response.attestationObject),
+                    clientDataJSON: bufferToBase64url(credential.response.clientDataJSON),
+                },
+                extensions: credential.getClientExtensionResults(),
+            })
+        });
+        if(!finishResponse.ok) {
+            throw new Error('패스키 등록에 실패했습니다.');
+        }
+        alert('패스키가 등록되었습니다.');
+    } catch(error) {
+        console.error('Passkey Error : ', error);
+        alert(error);
+    }
+}
+
+//패스키 로그인
+async function passkeyLogin(event, mode) {
+    event.preventDefault();
+
+    const email = document.getElementById('email').value;
+    const errorMsg = document.getElementById('login-error');
+    errorMsg.innerText = '';
+    if(!email) {
+        errorMsg.innerText = '이메일을 입력하세요.';
+        return;
+    }
+    try {
+        const startResponse = await fetch('/api/webauthn/login/start', {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({ email: email, mode: mode })
+        });
+        if(!startResponse.ok) {
+            const errorText = await startResponse.json();
+            errorMsg.innerText = errorText.error;
+            return;
+        }
+        const { publicKey } = await startResponse.json();
+        publicKey.challenge = base64urlToBuffer(publicKey.challenge);
+        (publicKey.allowCredentials || []).forEach(c => c.id = base64urlToBuffer(c.id));
+
+        const assertion = await navigator.credentials.get({ publicKey });
+        const finishResponse = await fetch('/api/webauthn/login/finish', {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({
+                id: assertion.id,
+                rawId: bufferToBase64url(assertion.rawId),
+                type: assertion.type,
+                response: {
+                    authenticatorData: bufferToBase64url(assertion.response.authenticatorData),
+                    clientDataJSON: bufferToBase64url(assertion.response.clientDataJSON),
+                    signature: bufferToBase64url(assertion.response.signature),
+                    userHandle: assertion.response.userHandle ? bufferToBase64url(assertion.response.userHandle) : null,
+                },
+                extensions: assertion.getClientExtensionResults(),
+            })
+        });
+        if(!finishResponse.ok) {
+            const errorText = await finishResponse.json();
+            errorMsg.innerText = errorText.error;
+            return;
+        }
+        //서버에서 온 것: HTML구문 -> HTML문자열을 그대로 받음
+        const html = await finishResponse.text();
+        document.documentElement.innerHTML = html;
+    } catch(error) {
+        console.error('Passkey Error : ', error);
+        alert(error);
+    }
+}
@@ -15,12 +15,22 @@ async function sessionLogin(event) {
     errorMsg.innerText = '';
     try{
         //실제 API호출
-        const response = await fetch('/api/login_session', {
+        let response = await fetch('/api/login_session', {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
             body: JSON.stringify(formData)
         });
 
+        // 로그인 실패가 반복되면 작업 증명 챌린지를 풀고 다시 시도한다.
+        if(response.status === 428) {
+            Object.assign(formData, await solveChallenge('login'));
+            response = await fetch('/api/login_session', {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify(formData)
+            });
+        }
+
         if(response.status === 401) {
             const errorText = await response.json();
             errorMsg.innerText = errorText.error;
@@ -43,6 +53,47 @@ async function sessionLogin(event) {
     }
 }
 
+//JWT 로그인 form 전송 / 챌린지가 필요하다는 응답(428)을 받으면 풀이를 hidden input에 채워서 다시 전송한다.
+async function jwtLogin(event) {
+    event.preventDefault();
+    const form = event.target;
+    const errorMsg = document.getElementById('login-error');
+    errorMsg.innerText = '';
+
+    try {
+        const response = await fetch(form.action, {
+            method: 'POST',
+            body: new URLSearchParams(new FormData(form))
+        });
+
+        if(response.status === 428) {
+            errorMsg.innerText = '자동 입력 방지 확인 중입니다...';
+            const solution = await solveChallenge('login');
+            form.querySelector('input[name="pow_challenge"]').value = solution.pow_challenge;
+            form.querySelector('input[name="pow_nonce"]').value = solution.pow_nonce;
+            // form.submit()은 submit 이벤트를 다시 발생시키지 않으므로 브라우저가 결과 화면으로 바로 이동한다.
+            form.submit();
+            return;
+        }
+
+        if(response.status === 401) {
+            const errorText = await response.json();
+            errorMsg.innerText = errorText.error;
+            return;
+        }
+
+        if(!response.ok) {
+            const errorText = await response.json();
+            throw new Error(errorText.error || 'Network Error');
+        }
+        const html = await response.text();
+        document.documentElement.innerHTML = html;
+    }catch(error){
+        console.error('Network Error : ', error);
+        alert(error);
+    }
+}
+
 /*
 // 뒤로가기 시 토큰으로 다시 로드
 window.addEventListener('popstate', async () => {
@@ -0,0 +1,68 @@
+/*
+읽기 진행 상황 (로그인한 사용자의 문서 페이지)
+    -> 본문을 어디까지 읽었는지(0 ~ 1)와 페이지를 보고 있던 시간을 REPORT_INTERVAL_MS마다, 그리고 페이지를 떠날 때 보고한다.
+    -> 탭이 보이지 않는 동안의 시간은 세지 않는다.
+    -> "이어서 읽기"(#continue)로 들어오면 마지막으로 보던 위치로 이동한다.
+*/
+const REPORT_INTERVAL_MS = 30000;
+
+const progressArticle = document.querySelector('.docs-article[data-document-id]');
+let visibleSince = document.visibilityState === 'visible' ? Date.now() : null;
+let pendingMs = 0;
+let lastReported = null;
+
+// 본문 끝이 화면 아래에 닿은 정도
+function scrollRatio() {
+    const rect = progressArticle.getBoundingClientRect();
+    if (rect.height <= 0) {
+        return 1;
+    }
+    const read = (window.innerHeight - rect.top) / rect.height;
+    return Math.min(1, Math.max(0, read));
+}
+
+function takeSeconds() {
+    if (visibleSince !== null) {
+        pendingMs += Date.now() - visibleSince;
+        visibleSince = document.visibilityState === 'visible' ? Date.now() : null;
+    }
+    const seconds = Math.floor(pendingMs / 1000);
+    pendingMs -= seconds * 1000;
+    return seconds;
+}
+
+function reportProgress() {
+    const report = { scroll: Number(scrollRatio().toFixed(3)), seconds: takeSeconds() };
+    if (lastReported && report.seconds === 0 && report.scroll === lastReported.scroll) {
+        return;
+    }
+    lastReported = report;
+    // keepalive : 페이지를 닫는 중에도 요청을 끝까지 보낸다.
+    fetch(`/api/progress/${progressArticle.dataset.documentId}`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(report),
+        keepalive: true,
+    }).catch(error => console.log('Error reporting progress:', error));
+}
+
+if (progressArticle) {
+    if (window.location.hash === '#continue') {
+        const saved = Number(progressArticle.dataset.scroll || 0);
+        const rect = progressArticle.getBoundingClientRect();
+        window.scrollTo(0, window.scrollY + rect.top + rect.height * saved - window.innerHeight);
+    }
+
+    setInterval(reportProgress, REPORT_INTERVAL_MS);
+
+    document.addEventListener('visibilitychange', () => {
+        if (document.visibilityState === 'hidden') {
+            reportProgress();
+            visibleSince = null;
+        } else {
+            visibleSince = Date.now();
+        }
+    });
+
+    window.addEventListener('pagehide', reportProgress);
+}
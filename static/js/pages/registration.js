@@ -125,10 +125,19 @@ async function handleSignup(event) {
         nickname: document.getElementById('nickname').value,
         password: password
     };
+
+    // 초대 전용 가입이면 초대 코드도 보낸다.
+    const inviteCode = document.getElementById('inviteCode');
+    if (inviteCode) {
+        formData.invite_code = inviteCode.value.trim();
+    }
     
     //console.log('회원가입 데이터:', formData);
     
     try {
+        // 자동 가입 방지 : 작업 증명 챌린지를 풀어서 함께 보낸다.
+        Object.assign(formData, await solveChallenge('register'));
+
         // 실제 API 호출
         const response = await fetch('/api/register', {
             method: 'POST',
@@ -0,0 +1,333 @@
+use crate::helpers::spawn_app;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn impersonate_requires_login() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let body = serde_json::json!({
+        "email": app.test_user.email,
+    });
+    let response = app.post_impersonate(&body).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+//관리자로 로그인한 상태에서 대리 로그인 대상 사용자를 하나 더 만든다.
+async fn register_subject(app: &crate::helpers::TestApp) -> String {
+    let email = Uuid::new_v4().to_string();
+    let body = serde_json::json!({
+        "email": email,
+        "password": "random_password",
+        "name": "random_name",
+        "nickname": "random_nickname",
+    });
+    app.post_register(&body).await;
+    email
+}
+
+//JWT의 payload를 서명 검증 없이 꺼낸다.
+fn decode_jwt_payload(token: &str) -> serde_json::Value {
+    let payload = token.split('.').nth(1).expect("Malformed jwt");
+    let bytes = base64::decode_config(payload, base64::URL_SAFE_NO_PAD).expect("Malformed jwt payload");
+    serde_json::from_slice(&bytes).expect("Malformed jwt payload")
+}
+
+#[tokio::test]
+async fn non_admin_cannot_impersonate() {
+    //Arrange
+    let app = spawn_app().await;
+    let subject = register_subject(&app).await;
+    app.post_login_json(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    }))
+    .await;
+
+    //Act
+    let response = app.post_impersonate(&serde_json::json!({ "email": subject })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn admin_can_start_and_end_impersonation_in_session_mode() {
+    //Arrange
+    let app = spawn_app().await;
+    let subject = register_subject(&app).await;
+    app.promote_test_user_to_admin().await;
+    app.post_login_json(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    }))
+    .await;
+
+    //Act 1 - 대리 로그인 시작
+    let response = app.post_impersonate(&serde_json::json!({ "email": subject })).await;
+
+    //Assert 1 - 대상 사용자의 화면과 배너가 보인다.
+    assert_eq!(response.status().as_u16(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["redirect"], "/home_session");
+    let html = app.get_home_session_html().await;
+    assert!(html.contains("impersonation-banner"));
+    assert!(html.contains(&subject));
+    assert!(html.contains(&app.test_user.email));
+
+    //Act 2 - 대리 로그인 종료
+    let response = app.post_stop_impersonation().await;
+
+    //Assert 2 - 관리자 본인 화면으로 돌아오고 배너가 사라진다.
+    assert_eq!(response.status().as_u16(), 303);
+    assert_eq!(response.headers().get("Location").unwrap(), "/home_session");
+    let html = app.get_home_session_html().await;
+    assert!(!html.contains("impersonation-banner"));
+    assert!(!html.contains(&subject));
+    assert!(html.contains(&app.test_user.email));
+}
+
+#[tokio::test]
+async fn impersonation_token_carries_act_claim_and_expires_in_30_minutes() {
+    //Arrange
+    let app = spawn_app().await;
+    let subject = register_subject(&app).await;
+    app.promote_test_user_to_admin().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    }))
+    .await;
+
+    //Act
+    let response = app.post_impersonate(&serde_json::json!({ "email": subject })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let cookie = response.cookies().find(|c| c.name() == "access_token").expect("No access token cookie");
+    assert_eq!(cookie.max_age(), Some(std::time::Duration::from_secs(30 * 60)));
+    let claims = decode_jwt_payload(cookie.value());
+    assert_eq!(claims["email"], subject.as_str());
+    assert_eq!(claims["act"]["sub"], app.test_user.email.as_str());
+    let lifetime = claims["exp"].as_i64().unwrap() - claims["iat"].as_i64().unwrap();
+    assert_eq!(lifetime, 30 * 60);
+}
+
+#[tokio::test]
+async fn admin_can_start_and_end_impersonation_in_jwt_mode() {
+    //Arrange
+    let app = spawn_app().await;
+    let subject = register_subject(&app).await;
+    app.promote_test_user_to_admin().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    }))
+    .await;
+
+    //Act 1 - 대리 로그인 시작
+    app.post_impersonate(&serde_json::json!({ "email": subject })).await;
+
+    //Assert 1
+    let html = app.get_home_jwt_html().await;
+    assert!(html.contains("impersonation-banner"));
+    assert!(html.contains(&subject));
+
+    //Act 2 - 대리 로그인 종료 / 대리 토큰이 지워지고 관리자 refresh token으로 복귀한다.
+    let response = app.post_stop_impersonation().await;
+
+    //Assert 2
+    assert_eq!(response.status().as_u16(), 303);
+    assert_eq!(response.headers().get("Location").unwrap(), "/home_jwt");
+    let html = app.get_home_jwt_html().await;
+    assert!(!html.contains("impersonation-banner"));
+    assert!(html.contains(&app.test_user.email));
+}
+
+#[tokio::test]
+async fn impersonation_cannot_use_admin_features() {
+    //Arrange
+    let app = spawn_app().await;
+    let subject = register_subject(&app).await;
+    app.promote_test_user_to_admin().await;
+    app.post_login_json(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    }))
+    .await;
+    app.post_impersonate(&serde_json::json!({ "email": subject })).await;
+
+    //Act - 대리 로그인 중에 관리자 기능 호출
+    let response = app.post_impersonate(&serde_json::json!({ "email": app.test_user.email })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn impersonated_request_is_recorded_in_audit_events() {
+    //Arrange
+    let app = spawn_app().await;
+    let subject = register_subject(&app).await;
+    app.promote_test_user_to_admin().await;
+    app.post_login_json(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    }))
+    .await;
+    app.post_impersonate(&serde_json::json!({ "email": subject })).await;
+
+    //Act
+    app.get_home_session_html().await;
+
+    //Assert
+    let row = app.wait_for_audit_event("impersonated_request", &app.test_user.email).await;
+    assert_eq!(row.action, "impersonated_request");
+    assert_eq!(row.actor.as_deref(), Some(app.test_user.email.as_str()));
+    assert_eq!(row.target.as_deref(), Some(subject.as_str()));
+    assert_eq!(row.details["method"], "GET");
+    assert_eq!(row.details["path"], "/home_session");
+}
+
+#[tokio::test]
+async fn audit_events_requires_login() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app.get_audit_events("action=login_failure").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn audit_events_requires_admin_role() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_json(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    }))
+    .await;
+
+    //Act
+    let response = app.get_audit_events("action=login_failure").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn registration_is_recorded_in_audit_events() {
+    //Arrange
+    let app = spawn_app().await;
+    let email = Uuid::new_v4().to_string();
+
+    //Act
+    let register_body = serde_json::json!({
+        "email": email,
+        "password": "random_password",
+        "name": "random_name",
+        "nickname": "random_nickname",
+    });
+    app.post_register(&register_body).await;
+
+    //Assert
+    let row = app.wait_for_audit_event("registration", &email).await;
+    assert_eq!(row.action, "registration");
+}
+
+//감사 로그를 직접 INSERT한다. (조회 조건 테스트용)
+async fn insert_audit_event(app: &crate::helpers::TestApp, occurred_at: &str, actor: &str, action: &str, target: &str) {
+    let occurred_at = chrono::DateTime::parse_from_rfc3339(occurred_at).unwrap().with_timezone(&chrono::Utc);
+    sqlx::query!(
+        "INSERT INTO audit_events (id, occurred_at, actor, action, target) VALUES ($1, $2, $3, $4, $5)",
+        Uuid::new_v4(),
+        occurred_at,
+        actor,
+        action,
+        target
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to insert audit event.");
+}
+
+async fn login_as_admin(app: &crate::helpers::TestApp) {
+    app.promote_test_user_to_admin().await;
+    app.post_login_json(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    }))
+    .await;
+}
+
+#[tokio::test]
+async fn audit_events_are_filtered_by_query_parameters() {
+    //Arrange
+    let app = spawn_app().await;
+    let actor = Uuid::new_v4().to_string();
+    insert_audit_event(&app, "2025-01-01T00:00:00Z", &actor, "login_failure", "first").await;
+    insert_audit_event(&app, "2025-01-02T00:00:00Z", &actor, "login_failure", "second").await;
+    insert_audit_event(&app, "2025-01-03T00:00:00Z", &actor, "login_failure", "third").await;
+    insert_audit_event(&app, "2025-01-02T00:00:00Z", &actor, "logout", "second").await;
+    login_as_admin(&app).await;
+
+    let targets = |events: serde_json::Value| -> Vec<String> {
+        events.as_array().unwrap().iter().map(|e| e["target"].as_str().unwrap().to_owned()).collect()
+    };
+
+    //Act & Assert 1 - actor + action (최신순)
+    let response = app.get_audit_events(&format!("actor={}&action=login_failure", actor)).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(targets(response.json().await.unwrap()), vec!["third", "second", "first"]);
+
+    //Act & Assert 2 - target
+    let response = app.get_audit_events(&format!("actor={}&target=second", actor)).await;
+    let events: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(events.as_array().unwrap().len(), 2);
+
+    //Act & Assert 3 - from 이상, to 미만
+    let response = app.get_audit_events(&format!(
+        "actor={}&action=login_failure&from=2025-01-02T00:00:00Z&to=2025-01-03T00:00:00Z", actor
+    )).await;
+    assert_eq!(targets(response.json().await.unwrap()), vec!["second"]);
+
+    //Act & Assert 4 - limit / offset
+    let response = app.get_audit_events(&format!("actor={}&action=login_failure&limit=1&offset=1", actor)).await;
+    assert_eq!(targets(response.json().await.unwrap()), vec!["second"]);
+}
+
+#[tokio::test]
+async fn audit_events_export_is_csv_with_formula_guard() {
+    //Arrange
+    let app = spawn_app().await;
+    let actor = "=HYPERLINK(\"http://evil.example\")";
+    insert_audit_event(&app, "2025-01-01T00:00:00Z", actor, "login_failure", "@target").await;
+    login_as_admin(&app).await;
+
+    //Act
+    let response = app.api_client
+        .get(format!("{}/admin/audit_events/export", &app.address))
+        .query(&[("actor", actor)])
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers()["Content-Type"].to_str().unwrap().starts_with("text/csv"));
+    assert!(response.headers()["Content-Disposition"].to_str().unwrap().contains("audit_events.csv"));
+    let csv = response.text().await.unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("id,occurred_at,actor,action,target,ip,user_agent,request_id,details"));
+    let row = lines.next().expect("No exported row");
+    //수식으로 시작하는 값 앞에 '가 붙고, 내부 큰따옴표는 두 번 쓴다.
+    assert!(row.contains(r#""'=HYPERLINK(""http://evil.example"")""#));
+    assert!(row.contains(r#""'@target""#));
+    assert!(lines.next().is_none());
+}
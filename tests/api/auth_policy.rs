@@ -0,0 +1,121 @@
+use rust_web::configuration::AuthPolicy;
+use crate::helpers::{spawn_app, spawn_app_with};
+
+fn policy(sliding_renewal: bool) -> AuthPolicy {
+    AuthPolicy {
+        access_token_minutes: 15,
+        idle_timeout_minutes: 60,
+        absolute_timeout_hours: 12,
+        sliding_renewal,
+        remember_me_days: 30,
+        reauth_minutes: 10,
+    }
+}
+
+const LOGIN_AT: i64 = 1_700_000_000;
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+
+#[test]
+fn initial_expiry_uses_idle_timeout() {
+    let policy = policy(true);
+    assert_eq!(policy.initial_expiry(LOGIN_AT, false), LOGIN_AT + HOUR);
+    assert_eq!(policy.login_deadline(LOGIN_AT, false), LOGIN_AT + 12 * HOUR);
+}
+
+#[test]
+fn remember_me_uses_remember_me_days() {
+    let policy = policy(true);
+    assert_eq!(policy.initial_expiry(LOGIN_AT, true), LOGIN_AT + 30 * DAY);
+    assert_eq!(policy.login_deadline(LOGIN_AT, true), LOGIN_AT + 30 * DAY);
+}
+
+#[test]
+fn idle_timeout_boundary() {
+    let policy = policy(true);
+    let expiry = policy.initial_expiry(LOGIN_AT, false);
+    let deadline = policy.login_deadline(LOGIN_AT, false);
+
+    //만료 1초 전 활동은 연장, 만료 시각 정각은 만료
+    assert_eq!(policy.renewed_expiry(expiry - 1, expiry, deadline, false), Some(expiry - 1 + HOUR));
+    assert_eq!(policy.renewed_expiry(expiry, expiry, deadline, false), None);
+}
+
+#[test]
+fn sliding_renewal_never_passes_absolute_timeout() {
+    let policy = policy(true);
+    let deadline = policy.login_deadline(LOGIN_AT, false);
+
+    //계속 활동해도 absolute timeout을 넘지 않는다.
+    let now = deadline - 10 * MINUTE;
+    assert_eq!(policy.renewed_expiry(now, now + 30 * MINUTE, deadline, false), Some(deadline));
+    assert_eq!(policy.renewed_expiry(deadline, deadline + HOUR, deadline, false), None);
+}
+
+#[test]
+fn without_sliding_renewal_expiry_is_kept() {
+    let policy = policy(false);
+    let expiry = policy.initial_expiry(LOGIN_AT, false);
+    let deadline = policy.login_deadline(LOGIN_AT, false);
+
+    assert_eq!(policy.renewed_expiry(LOGIN_AT + 30 * MINUTE, expiry, deadline, false), Some(expiry));
+}
+
+#[test]
+fn access_token_never_outlives_login() {
+    let policy = policy(true);
+    let deadline = policy.login_deadline(LOGIN_AT, false);
+
+    assert_eq!(policy.access_token_expiry(LOGIN_AT, deadline), LOGIN_AT + 15 * MINUTE);
+    assert_eq!(policy.access_token_expiry(deadline - MINUTE, deadline), deadline);
+}
+
+#[tokio::test]
+async fn remember_me_issues_persistent_refresh_cookie() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act - form 체크박스는 "on"으로 전송된다.
+    let response = app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+        "remember": "on",
+    })).await;
+
+    //Assert
+    let refresh = response.cookies().find(|c| c.name() == "refresh_token").expect("No refresh_token cookie");
+    let max_age = refresh.max_age().expect("Refresh cookie should be persistent");
+    assert!(max_age.as_secs() > 29 * DAY as u64);
+}
+
+#[tokio::test]
+async fn refresh_cookie_is_a_session_cookie_without_remember_me() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Assert
+    let refresh = response.cookies().find(|c| c.name() == "refresh_token").expect("No refresh_token cookie");
+    assert!(refresh.max_age().is_none());
+}
+
+#[tokio::test]
+async fn session_expires_after_idle_timeout() {
+    //Arrange - idle timeout 0분 : 로그인 직후 바로 만료
+    let app = spawn_app_with(|c| c.auth_policy.idle_timeout_minutes = 0).await;
+
+    //Act
+    app.post_login_session(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Assert
+    assert!(!app.get_home_session_html().await.contains(&app.test_user.nickname));
+}
@@ -0,0 +1,213 @@
+use rust_web::auth::{leading_zero_bits, solves};
+use uuid::Uuid;
+use crate::helpers::{spawn_app_with, TestApp};
+
+//테스트에서는 빨리 풀리도록 난이도를 낮춘다.
+const TEST_DIFFICULTY: u32 = 8;
+const TEST_FAILURE_THRESHOLD: i64 = 2;
+
+async fn spawn_app_with_challenge() -> TestApp {
+    spawn_app_with(|c| {
+        c.challenge.enabled = true;
+        c.challenge.difficulty = TEST_DIFFICULTY;
+        c.challenge.login_failure_threshold = TEST_FAILURE_THRESHOLD;
+    })
+    .await
+}
+
+//챌린지를 받아서 풀고 요청 본문에 넣을 (pow_challenge, pow_nonce)를 반환한다.
+async fn solve(app: &TestApp, purpose: &str) -> (String, String) {
+    let body: serde_json::Value = app.get_challenge(purpose).await.json().await.unwrap();
+    let challenge = body["challenge"].as_str().unwrap().to_string();
+    let difficulty = body["difficulty"].as_u64().unwrap() as u32;
+    let nonce = (0u64..)
+        .map(|n| n.to_string())
+        .find(|nonce| solves(&challenge, nonce, difficulty))
+        .unwrap();
+    (challenge, nonce)
+}
+
+fn register_body(email: &str, solution: Option<&(String, String)>) -> serde_json::Value {
+    serde_json::json!({
+        "email": email,
+        "password": "random_password",
+        "name": "random_name",
+        "nickname": "random_nickname",
+        "pow_challenge": solution.map(|(challenge, _)| challenge),
+        "pow_nonce": solution.map(|(_, nonce)| nonce),
+    })
+}
+
+#[test]
+fn leading_zero_bits_counts_across_bytes() {
+    assert_eq!(leading_zero_bits(&[0x00, 0x00, 0xff]), 16);
+    assert_eq!(leading_zero_bits(&[0x00, 0x1f]), 11);
+    assert_eq!(leading_zero_bits(&[0x80]), 0);
+    assert_eq!(leading_zero_bits(&[0x00, 0x00]), 16);
+}
+
+#[tokio::test]
+async fn challenge_endpoint_issues_a_challenge_with_the_configured_difficulty() {
+    //Arrange
+    let app = spawn_app_with_challenge().await;
+
+    //Act
+    let response = app.get_challenge("register").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers().get("Cache-Control").unwrap(), "no-store");
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["challenge"].as_str().unwrap().len() >= 16);
+    assert_eq!(body["difficulty"], TEST_DIFFICULTY);
+}
+
+#[tokio::test]
+async fn registration_without_a_solution_is_rejected() {
+    //Arrange
+    let app = spawn_app_with_challenge().await;
+
+    //Act
+    let response = app.post_register(&register_body(&Uuid::new_v4().to_string(), None)).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 428);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "challenge_required");
+    assert_eq!(body["success"], false);
+}
+
+#[tokio::test]
+async fn registration_with_a_solution_succeeds() {
+    //Arrange
+    let app = spawn_app_with_challenge().await;
+    let solution = solve(&app, "register").await;
+
+    //Act
+    let response = app.post_register(&register_body(&Uuid::new_v4().to_string(), Some(&solution))).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn a_solution_cannot_be_replayed() {
+    //Arrange
+    let app = spawn_app_with_challenge().await;
+    let solution = solve(&app, "register").await;
+    let first = app.post_register(&register_body(&Uuid::new_v4().to_string(), Some(&solution))).await;
+    assert_eq!(first.status().as_u16(), 200);
+
+    //Act
+    let replayed = app.post_register(&register_body(&Uuid::new_v4().to_string(), Some(&solution))).await;
+
+    //Assert
+    assert_eq!(replayed.status().as_u16(), 428);
+}
+
+#[tokio::test]
+async fn a_wrong_nonce_is_rejected() {
+    //Arrange
+    let app = spawn_app_with_challenge().await;
+    let (challenge, _) = solve(&app, "register").await;
+    let wrong_nonce = (0u64..)
+        .map(|n| n.to_string())
+        .find(|nonce| !solves(&challenge, nonce, TEST_DIFFICULTY))
+        .unwrap();
+
+    //Act
+    let response = app.post_register(&register_body(&Uuid::new_v4().to_string(), Some(&(challenge, wrong_nonce)))).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 428);
+}
+
+#[tokio::test]
+async fn a_login_challenge_cannot_be_used_for_registration() {
+    //Arrange
+    let app = spawn_app_with_challenge().await;
+    let solution = solve(&app, "login").await;
+
+    //Act
+    let response = app.post_register(&register_body(&Uuid::new_v4().to_string(), Some(&solution))).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 428);
+}
+
+#[tokio::test]
+async fn login_does_not_require_a_challenge_before_repeated_failures() {
+    //Arrange
+    let app = spawn_app_with_challenge().await;
+    let wrong = serde_json::json!({"email": &app.test_user.email, "password": "wrong-password"});
+    for _ in 0..TEST_FAILURE_THRESHOLD - 1 {
+        assert_eq!(app.post_login_session(&wrong).await.status().as_u16(), 401);
+    }
+
+    //Act
+    let response = app.post_login_session(&serde_json::json!({
+        "email": &app.test_user.email,
+        "password": &app.test_user.password,
+    })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn login_requires_a_challenge_after_repeated_failures() {
+    //Arrange
+    let app = spawn_app_with_challenge().await;
+    let wrong = serde_json::json!({"email": &app.test_user.email, "password": "wrong-password"});
+    for _ in 0..TEST_FAILURE_THRESHOLD {
+        assert_eq!(app.post_login_session(&wrong).await.status().as_u16(), 401);
+    }
+
+    //Act - 1. 비밀번호가 맞아도 풀이가 없으면 거부
+    let response = app.post_login_jwt(&serde_json::json!({
+        "email": &app.test_user.email,
+        "password": &app.test_user.password,
+    })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 428);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["challenge"], "/api/challenge?purpose=login");
+
+    //Act - 2. 풀이와 함께 보내면 로그인
+    let (challenge, nonce) = solve(&app, "login").await;
+    let response = app.post_login_jwt(&serde_json::json!({
+        "email": &app.test_user.email,
+        "password": &app.test_user.password,
+        "pow_challenge": challenge,
+        "pow_nonce": nonce,
+    })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn a_successful_login_resets_the_failure_count() {
+    //Arrange
+    let app = spawn_app_with_challenge().await;
+    let wrong = serde_json::json!({"email": &app.test_user.email, "password": "wrong-password"});
+    let correct = serde_json::json!({"email": &app.test_user.email, "password": &app.test_user.password});
+    for _ in 0..TEST_FAILURE_THRESHOLD {
+        app.post_login_session(&wrong).await;
+    }
+    let (challenge, nonce) = solve(&app, "login").await;
+    let response = app.post_login_session(&serde_json::json!({
+        "email": &app.test_user.email,
+        "password": &app.test_user.password,
+        "pow_challenge": challenge,
+        "pow_nonce": nonce,
+    })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    //Act
+    let response = app.post_login_session(&correct).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+}
@@ -0,0 +1,99 @@
+use rust_web::auth::AuthCookies;
+use rust_web::configuration::{CookieSameSite, CookieSettings};
+use crate::helpers::{spawn_app, spawn_app_with};
+
+fn production_settings() -> CookieSettings {
+    CookieSettings {
+        domain: None,
+        secure: true,
+        same_site: CookieSameSite::Lax,
+        host_prefix: true,
+        path: "/".to_string(),
+    }
+}
+
+#[test]
+fn host_prefix_requires_secure_root_path_and_no_domain() {
+    assert!(production_settings().validate().is_ok());
+    assert!(CookieSettings { secure: false, ..production_settings() }.validate().is_err());
+    assert!(CookieSettings { path: "/api".to_string(), ..production_settings() }.validate().is_err());
+    assert!(CookieSettings { domain: Some("example.com".to_string()), ..production_settings() }.validate().is_err());
+}
+
+#[test]
+fn same_site_none_requires_secure() {
+    let settings = CookieSettings {
+        secure: false,
+        host_prefix: false,
+        same_site: CookieSameSite::None,
+        ..production_settings()
+    };
+
+    assert!(settings.validate().is_err());
+}
+
+#[test]
+fn removal_cookie_matches_issued_cookie_attributes() {
+    let cookies = AuthCookies::new(CookieSettings {
+        domain: Some("example.com".to_string()),
+        host_prefix: false,
+        path: "/app".to_string(),
+        ..production_settings()
+    });
+
+    let issued = cookies.access_token("token".to_string(), chrono::Duration::minutes(15));
+    let removal = cookies.removal("access_token");
+
+    assert_eq!(issued.name(), removal.name());
+    assert_eq!(issued.domain(), removal.domain());
+    assert_eq!(issued.path(), removal.path());
+    assert_eq!(issued.secure(), removal.secure());
+    assert_eq!(issued.same_site(), removal.same_site());
+    assert_eq!(removal.max_age(), Some(actix_web::cookie::time::Duration::ZERO));
+}
+
+#[test]
+fn host_prefix_is_added_to_cookie_names() {
+    let cookies = AuthCookies::new(production_settings());
+
+    let issued = cookies.access_token("token".to_string(), chrono::Duration::minutes(15));
+
+    assert_eq!(issued.name(), "__Host-access_token");
+    assert_eq!(cookies.removal("refresh_token").name(), "__Host-refresh_token");
+}
+
+#[tokio::test]
+async fn local_auth_cookies_are_not_secure() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Assert - local.yaml은 http이므로 Secure 없이 발급한다.
+    for name in ["access_token", "refresh_token"] {
+        let cookie = response.cookies().find(|c| c.name() == name).expect("Missing auth cookie");
+        assert!(!cookie.secure());
+        assert!(cookie.http_only());
+        assert!(cookie.same_site_lax());
+    }
+}
+
+#[tokio::test]
+async fn configured_domain_is_applied_to_auth_cookies() {
+    //Arrange
+    let app = spawn_app_with(|c| c.cookie.domain = Some("localhost".to_string())).await;
+
+    //Act
+    let response = app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Assert
+    let access = response.cookies().find(|c| c.name() == "access_token").expect("No access_token cookie");
+    assert_eq!(access.domain(), Some("localhost"));
+}
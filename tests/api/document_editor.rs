@@ -0,0 +1,228 @@
+use uuid::Uuid;
+use crate::helpers::{spawn_app, TestApp};
+
+async fn login(app: &TestApp) {
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+}
+
+async fn spawn_editor() -> TestApp {
+    let app = spawn_app().await;
+    app.grant_test_user_permission("content:edit").await;
+    login(&app).await;
+    app
+}
+
+async fn seeded_document(app: &TestApp) -> (Uuid, i32) {
+    let row = sqlx::query!("SELECT id, revision FROM documents WHERE chapter = 'ch1_1' AND slug = 'n1_basic'")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch document");
+    (row.id, row.revision)
+}
+
+async fn put_document(app: &TestApp, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+    app.api_client
+        .put(format!("{}/admin/docs/{}", &app.address, id))
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn get(app: &TestApp, path: &str) -> reqwest::Response {
+    app.api_client
+        .get(format!("{}{}", &app.address, path))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn editor_requires_login() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = get(&app, "/admin/docs").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn editor_requires_content_permission() {
+    //Arrange
+    let app = spawn_app().await;
+    login(&app).await;
+    let (id, revision) = seeded_document(&app).await;
+
+    //Act
+    let response = put_document(&app, id, &serde_json::json!({"title": "제목", "body": "본문", "revision": revision})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn admins_can_use_the_editor_without_a_grant() {
+    //Arrange
+    let app = spawn_app().await;
+    app.promote_test_user_to_admin().await;
+    login(&app).await;
+
+    //Act
+    let response = get(&app, "/admin/docs").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("Tracing 기초"));
+}
+
+#[tokio::test]
+async fn saving_creates_a_draft_revision() {
+    //Arrange
+    let app = spawn_editor().await;
+    let (id, revision) = seeded_document(&app).await;
+
+    //Act
+    let response = put_document(&app, id, &serde_json::json!({
+        "title": "Tracing 기초",
+        "body": "# Tracing 기초\n\n편집기에서 **수정**했습니다.",
+        "revision": revision,
+        "message": "본문 수정",
+    })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let saved: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(saved["revision"], revision + 1);
+
+    let revisions: Vec<serde_json::Value> = get(&app, &format!("/admin/docs/{}/revisions", id)).await.json().await.unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0]["message"], "본문 수정");
+    assert_eq!(revisions[0]["author"], app.test_user.email.as_str());
+
+    //검토를 거치기 전까지는 게시된 내용이 그대로 보인다.
+    let html = get(&app, "/docs/ch1_1/n1_basic").await.text().await.unwrap();
+    assert!(!html.contains("편집기에서 <strong>수정</strong>했습니다."));
+}
+
+#[tokio::test]
+async fn saving_a_stale_revision_returns_409() {
+    //Arrange
+    let app = spawn_editor().await;
+    let (id, revision) = seeded_document(&app).await;
+    let first = serde_json::json!({"title": "첫 번째", "body": "첫 번째 편집", "revision": revision});
+    assert_eq!(put_document(&app, id, &first).await.status().as_u16(), 200);
+
+    //Act
+    let second = serde_json::json!({"title": "두 번째", "body": "두 번째 편집", "revision": revision});
+    let response = put_document(&app, id, &second).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 409);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["current_revision"], revision + 1);
+    let title = sqlx::query_scalar!("SELECT title FROM documents WHERE id = $1", id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(title, "첫 번째");
+}
+
+#[tokio::test]
+async fn diff_shows_changed_lines_side_by_side() {
+    //Arrange
+    let app = spawn_editor().await;
+    let (id, revision) = seeded_document(&app).await;
+    let save = serde_json::json!({"title": "Tracing 기초", "body": "첫 줄\n바뀐 줄\n<script>", "revision": revision});
+    put_document(&app, id, &save).await;
+
+    //Act
+    let response = get(&app, &format!("/admin/docs/{}/diff?from={}", id, revision)).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let html = response.text().await.unwrap();
+    assert!(html.contains("diff-insert") || html.contains("diff-replace"));
+    assert!(html.contains("바뀐 줄"));
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("&#60;script&#62;"));
+}
+
+#[tokio::test]
+async fn rollback_restores_an_older_revision_as_a_new_revision() {
+    //Arrange
+    let app = spawn_editor().await;
+    let (id, revision) = seeded_document(&app).await;
+    let original = sqlx::query_scalar!("SELECT body FROM documents WHERE id = $1", id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    put_document(&app, id, &serde_json::json!({"title": "Tracing 기초", "body": "망가진 본문", "revision": revision})).await;
+
+    //Act
+    let response = app.api_client
+        .post(format!("{}/admin/docs/{}/rollback", &app.address, id))
+        .json(&serde_json::json!({"revision": revision, "expected_revision": revision + 1}))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let row = sqlx::query!("SELECT body, revision FROM documents WHERE id = $1", id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(row.body, original);
+    assert_eq!(row.revision, revision + 2);
+}
+
+#[tokio::test]
+async fn preview_is_sanitized() {
+    //Arrange
+    let app = spawn_editor().await;
+
+    //Act
+    let response = app.api_client
+        .post(format!("{}/admin/docs/preview", &app.address))
+        .json(&serde_json::json!({"body": "## 제목\n\n<script>alert(1)</script>"}))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let html = body["html"].as_str().unwrap();
+    assert!(html.contains(r#"<h2 id="제목">"#));
+    assert!(!html.contains("<script>"));
+}
+
+#[tokio::test]
+async fn created_documents_start_as_unpublished_drafts() {
+    //Arrange
+    let app = spawn_editor().await;
+
+    //Act
+    let response = app.api_client
+        .post(format!("{}/admin/docs", &app.address))
+        .json(&serde_json::json!({
+            "chapter": "ch9_1",
+            "section": "테스트",
+            "slug": "n1_editor",
+            "title": "편집기 문서",
+            "body": "편집기에서 만든 문서",
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 201);
+    assert_eq!(get(&app, "/docs/ch9_1/n1_editor").await.status().as_u16(), 404);
+}
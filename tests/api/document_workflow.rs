@@ -0,0 +1,244 @@
+use chrono::{Duration, Utc};
+use rust_web::documents::publish_due_documents;
+use uuid::Uuid;
+use crate::helpers::{spawn_app, TestApp};
+
+async fn spawn_reviewer() -> TestApp {
+    let app = spawn_app().await;
+    app.grant_test_user_permission("content:edit").await;
+    app.grant_test_user_permission("content:review").await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+    app
+}
+
+async fn post(app: &TestApp, path: &str, body: &serde_json::Value) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}{}", &app.address, path))
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+//편집기에서 새 문서를 만든다. (draft, 리비전 1)
+async fn create_draft(app: &TestApp, slug: &str) -> Uuid {
+    let response = post(app, "/admin/docs", &serde_json::json!({
+        "chapter": "ch9_1",
+        "section": "테스트",
+        "slug": slug,
+        "title": "검토 문서",
+        "body": "검토를 기다리는 문서",
+    })).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["id"].as_str().unwrap().parse().unwrap()
+}
+
+async fn document_status(app: &TestApp, slug: &str) -> u16 {
+    app.api_client
+        .get(format!("{}/docs/ch9_1/{}", &app.address, slug))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .status()
+        .as_u16()
+}
+
+async fn basic_page(app: &TestApp) -> String {
+    app.api_client
+        .get(format!("{}/docs/ch1_1/n1_basic", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn approved_documents_become_visible() {
+    //Arrange
+    let app = spawn_reviewer().await;
+    let id = create_draft(&app, "n1_approve").await;
+    assert_eq!(post(&app, &format!("/admin/docs/{}/submit", id), &serde_json::json!({})).await.status().as_u16(), 200);
+    assert_eq!(document_status(&app, "n1_approve").await, 404);
+
+    //Act
+    let response = post(&app, &format!("/admin/docs/{}/reviews", id), &serde_json::json!({
+        "revision": 1,
+        "decision": "approve",
+    })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "published");
+    assert_eq!(document_status(&app, "n1_approve").await, 200);
+}
+
+#[tokio::test]
+async fn requesting_changes_needs_a_comment_and_returns_to_draft() {
+    //Arrange
+    let app = spawn_reviewer().await;
+    let id = create_draft(&app, "n1_changes").await;
+    post(&app, &format!("/admin/docs/{}/submit", id), &serde_json::json!({})).await;
+    let path = format!("/admin/docs/{}/reviews", id);
+
+    //Act
+    let without_comment = post(&app, &path, &serde_json::json!({"revision": 1, "decision": "request_changes"})).await;
+    let with_comment = post(&app, &path, &serde_json::json!({
+        "revision": 1,
+        "decision": "request_changes",
+        "comment": "예제 코드를 추가해 주세요.",
+    })).await;
+
+    //Assert
+    assert_eq!(without_comment.status().as_u16(), 400);
+    assert_eq!(with_comment.status().as_u16(), 200);
+    let body: serde_json::Value = with_comment.json().await.unwrap();
+    assert_eq!(body["status"], "draft");
+    let reviews: Vec<serde_json::Value> = app.api_client
+        .get(format!("{}{}", &app.address, path))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(reviews.len(), 1);
+    assert_eq!(reviews[0]["decision"], "changes_requested");
+    assert_eq!(reviews[0]["comment"], "예제 코드를 추가해 주세요.");
+}
+
+#[tokio::test]
+async fn reviewing_requires_the_review_permission() {
+    //Arrange
+    let app = spawn_app().await;
+    app.grant_test_user_permission("content:edit").await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+    let id = create_draft(&app, "n1_forbidden").await;
+    post(&app, &format!("/admin/docs/{}/submit", id), &serde_json::json!({})).await;
+
+    //Act
+    let response = post(&app, &format!("/admin/docs/{}/reviews", id), &serde_json::json!({
+        "revision": 1,
+        "decision": "approve",
+    })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn reviewing_a_revision_saved_after_submission_returns_409() {
+    //Arrange
+    let app = spawn_reviewer().await;
+    let id = create_draft(&app, "n1_stale").await;
+    post(&app, &format!("/admin/docs/{}/submit", id), &serde_json::json!({})).await;
+    app.api_client
+        .put(format!("{}/admin/docs/{}", &app.address, id))
+        .json(&serde_json::json!({"title": "검토 문서", "body": "제출 후 수정", "revision": 1}))
+        .send()
+        .await
+        .unwrap();
+
+    //Act
+    let response = post(&app, &format!("/admin/docs/{}/reviews", id), &serde_json::json!({
+        "revision": 1,
+        "decision": "approve",
+    })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 409);
+    assert_eq!(document_status(&app, "n1_stale").await, 404);
+}
+
+#[tokio::test]
+async fn edits_to_published_documents_stay_hidden_until_approved() {
+    //Arrange
+    let app = spawn_reviewer().await;
+    let row = sqlx::query!("SELECT id, revision FROM documents WHERE chapter = 'ch1_1' AND slug = 'n1_basic'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    app.api_client
+        .put(format!("{}/admin/docs/{}", &app.address, row.id))
+        .json(&serde_json::json!({"title": "Tracing 기초", "body": "검토 중인 새 본문", "revision": row.revision}))
+        .send()
+        .await
+        .unwrap();
+    let before = basic_page(&app).await;
+
+    //Act
+    post(&app, &format!("/admin/docs/{}/submit", row.id), &serde_json::json!({})).await;
+    post(&app, &format!("/admin/docs/{}/reviews", row.id), &serde_json::json!({
+        "revision": row.revision + 1,
+        "decision": "approve",
+    })).await;
+    let after = basic_page(&app).await;
+
+    //Assert
+    assert!(before.contains("구조화된 로깅과 분산 추적"));
+    assert!(!before.contains("검토 중인 새 본문"));
+    assert!(after.contains("검토 중인 새 본문"));
+}
+
+#[tokio::test]
+async fn scheduled_documents_are_published_by_the_scheduler() {
+    //Arrange
+    let app = spawn_reviewer().await;
+    let id = create_draft(&app, "n1_scheduled").await;
+    let publish_at = Utc::now() + Duration::hours(1);
+    post(&app, &format!("/admin/docs/{}/submit", id), &serde_json::json!({"publish_at": publish_at})).await;
+    let response = post(&app, &format!("/admin/docs/{}/reviews", id), &serde_json::json!({
+        "revision": 1,
+        "decision": "approve",
+    })).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "scheduled");
+    assert!(publish_due_documents(&app.db_pool).await.unwrap().is_empty());
+    assert_eq!(document_status(&app, "n1_scheduled").await, 404);
+
+    //Act
+    sqlx::query!("UPDATE documents SET publish_at = now() - interval '1 minute' WHERE id = $1", id)
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    let published = publish_due_documents(&app.db_pool).await.unwrap();
+
+    //Assert
+    assert_eq!(published, vec![id]);
+    assert_eq!(document_status(&app, "n1_scheduled").await, 200);
+}
+
+#[tokio::test]
+async fn archived_documents_are_hidden_from_the_toc() {
+    //Arrange
+    let app = spawn_reviewer().await;
+    let id = sqlx::query_scalar!("SELECT id FROM documents WHERE chapter = 'ch1_4' AND slug = 'n1_lifetime'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+
+    //Act
+    let response = post(&app, &format!("/admin/docs/{}/archive", id), &serde_json::json!({})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let toc: serde_json::Value = app.api_client
+        .get(format!("{}/api/contents", &app.address))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(!toc.to_string().contains("ch1_4"));
+    let page = app.api_client.get(format!("{}/docs/ch1_4/n1_lifetime", &app.address)).send().await.unwrap();
+    assert_eq!(page.status().as_u16(), 404);
+}
@@ -0,0 +1,96 @@
+use uuid::Uuid;
+use crate::helpers::{spawn_app, TestApp};
+
+async fn get_document(app: &TestApp, chapter: &str, slug: &str) -> reqwest::Response {
+    app.api_client
+        .get(format!("{}/docs/{}/{}", &app.address, chapter, slug))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn seeded_documents_are_rendered_through_the_shared_layout() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = get_document(&app, "ch1_1", "n1_basic").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let html = response.text().await.unwrap();
+    assert!(html.contains("<title>Tracing 기초 - Rust 학습 문서</title>"));
+    assert!(html.contains("구조화된 로깅과 분산 추적"));
+    assert!(html.contains(r#"href="/docs/ch1_1/n2_settings""#));
+}
+
+#[tokio::test]
+async fn unknown_documents_return_404() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = get_document(&app, "ch9_9", "missing").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn inserted_documents_are_served_without_a_rebuild() {
+    //Arrange
+    let app = spawn_app().await;
+    sqlx::query!(
+        r#"
+        INSERT INTO documents (id, chapter, section, slug, title, body, position, created_at, updated_at)
+        VALUES ($1, 'ch9_1', '테스트', 'n1_new', '새 문서', '새로 추가한 **문서**', 0, now(), now())
+        "#,
+        Uuid::new_v4()
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to insert document");
+
+    //Act
+    let response = get_document(&app, "ch9_1", "n1_new").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("새로 추가한 <strong>문서</strong>"));
+}
+
+#[tokio::test]
+async fn markdown_bodies_are_rendered_with_highlighted_code() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let html = get_document(&app, "ch1_2", "n2_example").await.text().await.unwrap();
+
+    //Assert
+    assert!(html.contains(r#"<h2 id="핵심-코드">"#));
+    assert!(html.contains(r#"class="language-rust""#));
+    assert!(html.contains(r#"<div class="callout callout-key">"#));
+}
+
+#[tokio::test]
+async fn edited_documents_are_rendered_again() {
+    //Arrange
+    let app = spawn_app().await;
+    let before = get_document(&app, "ch1_1", "n3_span_event").await.text().await.unwrap();
+    assert!(before.contains("일회성 로그"));
+
+    //Act
+    sqlx::query!(
+        "UPDATE documents SET body = '## 바뀐 내용', updated_at = now() WHERE chapter = 'ch1_1' AND slug = 'n3_span_event'"
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to update document");
+    let after = get_document(&app, "ch1_1", "n3_span_event").await.text().await.unwrap();
+
+    //Assert
+    assert!(after.contains("바뀐 내용"));
+    assert!(!after.contains("일회성 로그"));
+}
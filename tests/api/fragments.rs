@@ -0,0 +1,90 @@
+use rust_web::routes::Locale;
+use crate::helpers::{spawn_app, TestApp};
+
+async fn get_fragment(app: &TestApp, path: &str, language: &str) -> (u16, String) {
+    let response = app.api_client
+        .get(format!("{}{}", &app.address, path))
+        .header("Accept-Language", language)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    (response.status().as_u16(), response.text().await.unwrap())
+}
+
+#[test]
+fn locale_follows_the_first_supported_language() {
+    assert_eq!(Locale::from_accept_language("en-US,en;q=0.9,ko;q=0.8"), Locale::En);
+    assert_eq!(Locale::from_accept_language("fr-FR,ko;q=0.8,en;q=0.5"), Locale::Ko);
+    assert_eq!(Locale::from_accept_language(""), Locale::Ko);
+}
+
+#[tokio::test]
+async fn raw_template_files_are_not_served() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let (templates, _) = get_fragment(&app, "/templates/table_contents.html", "ko").await;
+    let (unknown, _) = get_fragment(&app, "/fragments/table_contents", "ko").await;
+    let (traversal, _) = get_fragment(&app, "/fragments/..%2F..%2FCargo.toml", "ko").await;
+
+    //Assert
+    assert_eq!(templates, 404);
+    assert_eq!(unknown, 404);
+    assert_eq!(traversal, 404);
+}
+
+#[tokio::test]
+async fn account_fragment_is_rendered_for_the_current_user() {
+    //Arrange
+    let app = spawn_app().await;
+    let (_, guest) = get_fragment(&app, "/fragments/account", "ko").await;
+    app.post_login_session(&serde_json::json!({
+        "email": &app.test_user.email,
+        "password": &app.test_user.password,
+    })).await;
+
+    //Act
+    let (status, html) = get_fragment(&app, "/fragments/account", "ko").await;
+
+    //Assert
+    assert!(guest.contains(r#"href="/home_session""#));
+    assert_eq!(status, 200);
+    assert!(html.contains("popup-overlay"));
+    assert!(html.contains(&app.test_user.nickname));
+    assert!(!html.contains("{%"));
+}
+
+#[tokio::test]
+async fn fragments_use_the_request_locale() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let (_, english) = get_fragment(&app, "/fragments/account", "en-US,en;q=0.9").await;
+    let (_, korean) = get_fragment(&app, "/fragments/account", "ko-KR").await;
+
+    //Assert
+    assert!(english.contains(r#"lang="en""#));
+    assert!(english.contains("Sign in"));
+    assert!(korean.contains("로그인"));
+}
+
+#[tokio::test]
+async fn glossary_term_fragment_needs_a_known_term() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let (status, html) = get_fragment(&app, "/fragments/glossary-term?term=send", "ko").await;
+    let (missing, _) = get_fragment(&app, "/fragments/glossary-term", "ko").await;
+    let (unknown, _) = get_fragment(&app, "/fragments/glossary-term?term=unknown", "ko").await;
+
+    //Assert
+    assert_eq!(status, 200);
+    assert!(html.contains("popup-overlay"));
+    assert!(html.contains(r#"href="/glossary#send""#));
+    assert!(!html.contains("<html"));
+    assert_eq!(missing, 400);
+    assert_eq!(unknown, 404);
+}
@@ -0,0 +1,94 @@
+use rust_web::documents::{link_glossary_terms, GlossaryTerm};
+use crate::helpers::{spawn_app, TestApp};
+
+fn term(slug: &str, term: &str, aliases: &[&str]) -> GlossaryTerm {
+    GlossaryTerm {
+        slug: slug.to_string(),
+        term: term.to_string(),
+        aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        definition: String::new(),
+    }
+}
+
+async fn get_html(app: &TestApp, path: &str) -> (u16, String) {
+    let response = app.api_client
+        .get(format!("{}{}", &app.address, path))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    (response.status().as_u16(), response.text().await.unwrap())
+}
+
+#[test]
+fn only_the_first_occurrence_of_a_term_is_linked() {
+    let terms = vec![term("send", "Send", &[])];
+
+    let html = link_glossary_terms("<p><code>Send</code>는 이동, Send는 다시</p>", &terms);
+
+    assert_eq!(
+        html,
+        r#"<p><code><a class="glossary-term" href="/glossary#send" data-popup="/fragments/glossary-term?term=send">Send</a></code>는 이동, Send는 다시</p>"#
+    );
+}
+
+#[test]
+fn headings_links_and_code_blocks_are_left_alone() {
+    let terms = vec![term("send", "Send", &[])];
+    let html = r##"<h2 id="send"><a class="heading-anchor" href="#send">#</a> Send</h2><pre class="highlight"><code>T: Send</code></pre><a href="/x">Send</a>"##;
+
+    assert_eq!(link_glossary_terms(html, &terms), html);
+}
+
+#[test]
+fn english_terms_match_whole_words_and_aliases_prefer_the_longest() {
+    let terms = vec![
+        term("send", "Send", &[]),
+        term("hrtb", "HRTB", &["Higher-Ranked Trait Bounds"]),
+        term("box-dyn", "Box<dyn Trait>", &["Box<dyn"]),
+    ];
+
+    let html = link_glossary_terms("<p>Sender와 Higher-Ranked Trait Bounds, Box&lt;dyn Trait&gt;</p>", &terms);
+
+    assert!(!html.contains(r#"data-popup="/fragments/glossary-term?term=send""#));
+    assert!(html.contains(r#"data-popup="/fragments/glossary-term?term=hrtb">Higher-Ranked Trait Bounds</a>"#));
+    assert!(html.contains(r#"data-popup="/fragments/glossary-term?term=box-dyn">Box&lt;dyn Trait&gt;</a>"#));
+}
+
+#[test]
+fn korean_terms_are_linked_before_particles() {
+    let terms = vec![term("opaque-type", "불투명 타입", &[])];
+
+    let html = link_glossary_terms("<p>서로 다른 불투명 타입이므로</p>", &terms);
+
+    assert!(html.contains(r#"data-popup="/fragments/glossary-term?term=opaque-type">불투명 타입</a>이므로"#));
+}
+
+#[tokio::test]
+async fn documents_link_known_terms_once() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let (status, html) = get_html(&app, "/docs/ch1_3/n1_impl_trait").await;
+
+    //Assert
+    assert_eq!(status, 200);
+    assert_eq!(html.matches(r#"data-popup="/fragments/glossary-term?term=send""#).count(), 1);
+    assert_eq!(html.matches(r#"data-popup="/fragments/glossary-term?term=opaque-type""#).count(), 1);
+    let heading = &html[html.find(r#"<h2 id="send-sync">"#).expect("heading")..];
+    assert!(!heading[..heading.find("</h2>").unwrap()].contains("glossary-term"));
+}
+
+#[tokio::test]
+async fn glossary_page_lists_terms_with_anchors() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let (status, html) = get_html(&app, "/glossary").await;
+
+    //Assert
+    assert_eq!(status, 200);
+    assert!(html.contains(r#"id="hrtb""#));
+    assert!(html.contains("Higher-Ranked Trait Bounds"));
+}
@@ -1,7 +1,8 @@
 use argon2::{password_hash::SaltString, Algorithm, Argon2, Params, Version, PasswordHasher};
 use once_cell::sync::Lazy;
 use rust_web::{
-    configuration::{get_configuration, DatabaseSettings}, 
+    auth::StoreBackend,
+    configuration::{get_configuration, DatabaseSettings, Settings}, 
     startup::{get_connection_pool, Application}, 
     telemetry::{get_subscriber, init_subscriber}
 };
@@ -28,6 +29,14 @@ static TRACING: Lazy<()> = Lazy::new(|| {
 
 //각 테스트를 위한 완전히 독립적인 애플리케이션 환경 생성
 pub async fn spawn_app() -> TestApp {
+    spawn_app_with(|_| {}).await
+}
+
+//설정을 바꿔서 실행해야 하는 테스트용 (ex. mock OIDC 공급자 등록)
+pub async fn spawn_app_with<F>(customize: F) -> TestApp
+where
+    F: FnOnce(&mut Settings),
+{
     //'initialize'가 첫번째 호출되면 'TRACING'안의 코드가 실행된다. 다른 모든 호출은 실행을 건너뛴다.
     Lazy::force(&TRACING);
 
@@ -37,6 +46,17 @@ pub async fn spawn_app() -> TestApp {
         c.database.database_name = Uuid::new_v4().to_string();
         //무작위 OS 포트 사용
         c.application.port = 0;
+        //테스트 케이스마다 다른 outbox 디렉토리 사용
+        c.email.outbox_dir = format!("target/test_outbox/{}", Uuid::new_v4());
+        //기본은 메모리 저장소 / TEST_STORE_BACKEND=redis 또는 postgres로 실제 저장소를 사용할 수 있다.
+        c.store.backend = match std::env::var("TEST_STORE_BACKEND").as_deref() {
+            Ok("redis") => StoreBackend::Redis,
+            Ok("postgres") => StoreBackend::Postgres,
+            _ => StoreBackend::Memory,
+        };
+        //작업 증명 챌린지는 tests/api/challenge.rs에서만 켠다.
+        c.challenge.enabled = false;
+        customize(&mut c);
         c
     };
     configure_database(&configuration.database).await;
@@ -44,7 +64,7 @@ pub async fn spawn_app() -> TestApp {
     let application = Application::build(configuration.clone())
         .await.expect("Failed to build application");
     let application_port = application.port();
-    let _ = tokio::spawn(application.run_until_stopped());
+    tokio::spawn(application.run_until_stopped());
 
     let client = reqwest::Client::builder()
         .redirect(reqwest::redirect::Policy::none())
@@ -57,7 +77,8 @@ pub async fn spawn_app() -> TestApp {
         //port: application_port,
         db_pool: get_connection_pool(&configuration.database),
         test_user: TestUser::generate(),
-        api_client: client
+        api_client: client,
+        outbox_dir: std::path::PathBuf::from(&configuration.email.outbox_dir),
     };
     test_app.test_user.store(&test_app.db_pool).await;
     test_app
@@ -84,6 +105,7 @@ pub struct TestApp {
     pub db_pool: PgPool,
     pub test_user: TestUser,
     pub api_client: reqwest::Client,
+    pub outbox_dir: std::path::PathBuf,
 }
 
 impl TestApp {
@@ -92,28 +114,286 @@ impl TestApp {
     where 
         Body: serde::Serialize, {
             self.api_client
-                .post(&format!("{}/api/login", &self.address))
+                .post(format!("{}/api/login_session", &self.address))
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    pub async fn post_login_session<Body>(&self, body: &Body) -> reqwest::Response
+    where 
+        Body: serde::Serialize, {
+            self.api_client
+                .post(format!("{}/api/login_session", &self.address))
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    //다른 기기(User-Agent)에서 로그인 / 쿠키 저장소를 공유하지 않는다.
+    pub async fn post_login_session_with_user_agent<Body>(&self, body: &Body, user_agent: &str) -> reqwest::Response
+    where
+        Body: serde::Serialize, {
+            reqwest::Client::new()
+                .post(format!("{}/api/login_session", &self.address))
+                .header("User-Agent", user_agent)
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    //패스키 엔드포인트(/api/webauthn/{path})에 JSON POST
+    pub async fn post_webauthn<Body>(&self, path: &str, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize, {
+            self.api_client
+                .post(format!("{}/api/webauthn/{}", &self.address, path))
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    //JWT 로그인은 form 형식으로 전송
+    pub async fn post_login_jwt<Body>(&self, body: &Body) -> reqwest::Response
+    where 
+        Body: serde::Serialize, {
+            self.api_client
+                .post(format!("{}/api/login_jwt", &self.address))
+                .form(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    //응답 JSON의 redirect 경로를 따라갈 때 사용
+    pub async fn get_path(&self, path: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}{}", &self.address, path))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+    pub async fn get_home_session_html(&self) -> String {
+        self.api_client
+            .get(format!("{}/home_session", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .text()
+            .await
+            .unwrap()
+    }
+    pub async fn get_home_jwt_html(&self) -> String {
+        self.api_client
+            .get(format!("{}/home_jwt", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .text()
+            .await
+            .unwrap()
+    }
+    pub async fn post_change_password<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize, {
+            self.api_client
+                .post(format!("{}/api/password", &self.address))
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    //쿠키 저장소 없이 지정한 access token만 실어서 비밀번호 변경 요청 (폐기된 토큰 확인용)
+    pub async fn post_change_password_with_token<Body>(&self, access_token: &str, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize, {
+            reqwest::Client::new()
+                .post(format!("{}/api/password", &self.address))
+                .header("Cookie", format!("access_token={}", access_token))
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    pub async fn post_reauth<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize, {
+            self.api_client
+                .post(format!("{}/api/reauth", &self.address))
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    pub async fn post_force_logout<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize, {
+            self.api_client
+                .post(format!("{}/admin/force_logout", &self.address))
                 .json(body)
                 .send()
                 .await
                 .expect("Failed to execute request.")
         }
+    pub async fn post_logout(&self) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/logout", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+    //fetch API처럼 JSON 응답을 요청하는 로그아웃
+    pub async fn post_logout_json(&self) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/logout", &self.address))
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+    //쿠키 저장소 없이 지정한 쿠키 헤더로 요청 (이미 폐기된 토큰 등 저장소에 없는 쿠키를 보낼 때 사용)
+    pub async fn request_with_cookies(&self, method: reqwest::Method, path: &str, cookies: &str) -> reqwest::Response {
+        reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap()
+            .request(method, format!("{}{}", &self.address, path))
+            .header("Cookie", cookies)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
     pub async fn post_register<Body>(&self, body: &Body) -> reqwest::Response
     where
         Body: serde::Serialize, {
             self.api_client
-                .post(&format!("{}/api/register", &self.address))
+                .post(format!("{}/api/register", &self.address))
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    pub async fn post_impersonate<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize, {
+            self.api_client
+                .post(format!("{}/admin/impersonate", &self.address))
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    pub async fn post_magic_link<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize, {
+            self.api_client
+                .post(format!("{}/api/login/magic", &self.address))
+                .json(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+    pub async fn get_magic_link(&self, token: &str, mode: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/login/magic", &self.address))
+            .query(&[("token", token), ("mode", mode)])
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+    //outbox에서 해당 수신자에게 마지막으로 보낸 메일 본문을 가져온다.
+    pub fn last_email_to(&self, to: &str) -> Option<String> {
+        let mut files: Vec<_> = std::fs::read_dir(&self.outbox_dir).ok()?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .collect();
+        files.sort();
+        files.iter().rev()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .find(|content| content.contains(&format!("To: {}\n", to)))
+    }
+    pub async fn grant_test_user_permission(&self, permission: &str) {
+        sqlx::query!(
+            "INSERT INTO user_permissions (email, permission, granted_at) VALUES ($1, $2, now())",
+            self.test_user.email,
+            permission
+        )
+        .execute(&self.db_pool)
+        .await
+        .expect("Failed to grant permission");
+    }
+    pub async fn post_invitation<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize, {
+            self.api_client
+                .post(format!("{}/admin/invitations", &self.address))
                 .json(body)
                 .send()
                 .await
                 .expect("Failed to execute request.")
         }
+    pub async fn get_registration_html(&self, query: &str) -> String {
+        self.api_client
+            .get(format!("{}/registration?{}", &self.address, query))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .text()
+            .await
+            .unwrap()
+    }
+    pub async fn get_challenge(&self, purpose: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/api/challenge", &self.address))
+            .query(&[("purpose", purpose)])
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+    pub async fn post_stop_impersonation(&self) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/admin/impersonate/stop", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+    pub async fn get_audit_events(&self, query: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/admin/audit_events?{}", &self.address, query))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+    //테스트 사용자를 관리자로 만든다.
+    pub async fn promote_test_user_to_admin(&self) {
+        sqlx::query!("UPDATE users SET role = 'admin' WHERE email = $1", self.test_user.email)
+            .execute(&self.db_pool)
+            .await
+            .expect("Failed to promote test user");
+    }
+    //감사 로그는 백그라운드에서 기록되므로 나타날 때까지 짧게 여러 번 조회한다. (최대 약 5초)
+    pub async fn wait_for_audit_event(&self, action: &str, actor: &str) -> AuditEventRow {
+        for _ in 0..100 {
+            let row = sqlx::query_as!(
+                AuditEventRow,
+                "SELECT actor, action, target, details FROM audit_events WHERE action = $1 AND actor = $2",
+                action,
+                actor
+            )
+            .fetch_optional(&self.db_pool)
+            .await
+            .expect("Failed to fetch audit event.");
+            if let Some(row) = row {
+                return row;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        panic!("Audit event '{}' for '{}' was not recorded.", action, actor);
+    }
     /* 
     pub async fn post_login_form<Body>(&self, body: &Body) -> reqwest::Response
     where 
         Body: serde::Serialize, {
             self.api_client
-                .post(&format!("{}/api/login", &self.address))
+                .post(format!("{}/api/login", &self.address))
                 .form(body)
                 .send()
                 .await
@@ -123,7 +403,7 @@ impl TestApp {
     //로그인 페이지 HTML 가져오기 / UI테스트나 CSRF토큰 추출 시 사용
     pub async fn get_login_html(&self) -> String {
         self.api_client
-            .get(&format!("{}/home", &self.address))
+            .get(format!("{}/home", &self.address))
             .send()
             .await
             .expect("Failed to execute request.")
@@ -134,6 +414,13 @@ impl TestApp {
     */
 }
 
+pub struct AuditEventRow {
+    pub actor: Option<String>,
+    pub action: String,
+    pub target: Option<String>,
+    pub details: serde_json::Value,
+}
+
 pub struct TestUser {
     pub email: String,
     pub name: String,
@@ -0,0 +1,158 @@
+use rust_web::configuration::RegistrationMode;
+use uuid::Uuid;
+use crate::helpers::{spawn_app_with, TestApp};
+
+async fn spawn_app_in(mode: RegistrationMode) -> TestApp {
+    spawn_app_with(move |c| c.registration.mode = mode).await
+}
+
+fn register_body(email: &str, invite_code: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "email": email,
+        "password": "random_password",
+        "name": "random_name",
+        "nickname": "random_nickname",
+        "invite_code": invite_code,
+    })
+}
+
+async fn insert_invitation(app: &TestApp, email: Option<&str>, max_uses: i32) -> String {
+    let code = Uuid::new_v4().simple().to_string();
+    sqlx::query!(
+        "INSERT INTO invitations (code, email, max_uses, created_by, created_at) VALUES ($1, $2, $3, 'test', now())",
+        code,
+        email,
+        max_uses
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to insert invitation");
+    code
+}
+
+async fn invitation_uses(app: &TestApp, code: &str) -> i32 {
+    sqlx::query_scalar!("SELECT uses FROM invitations WHERE code = $1", code)
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch invitation")
+}
+
+#[tokio::test]
+async fn closed_registration_rejects_sign_up() {
+    //Arrange
+    let app = spawn_app_in(RegistrationMode::Closed).await;
+
+    //Act
+    let response = app.post_register(&register_body(&Uuid::new_v4().to_string(), None)).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn invite_mode_requires_a_code() {
+    //Arrange
+    let app = spawn_app_in(RegistrationMode::Invite).await;
+
+    //Act
+    let response = app.post_register(&register_body(&Uuid::new_v4().to_string(), None)).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn invitation_code_cannot_be_used_beyond_max_uses() {
+    //Arrange
+    let app = spawn_app_in(RegistrationMode::Invite).await;
+    let code = insert_invitation(&app, None, 1).await;
+
+    //Act
+    let first = app.post_register(&register_body(&Uuid::new_v4().to_string(), Some(&code))).await;
+    let second = app.post_register(&register_body(&Uuid::new_v4().to_string(), Some(&code))).await;
+
+    //Assert
+    assert_eq!(first.status().as_u16(), 200);
+    assert_eq!(second.status().as_u16(), 400);
+    assert_eq!(invitation_uses(&app, &code).await, 1);
+}
+
+#[tokio::test]
+async fn invitation_bound_to_email_rejects_other_emails() {
+    //Arrange
+    let app = spawn_app_in(RegistrationMode::Invite).await;
+    let code = insert_invitation(&app, Some("invited@example.com"), 1).await;
+
+    //Act
+    let other = app.post_register(&register_body("someone@example.com", Some(&code))).await;
+    let invited = app.post_register(&register_body("Invited@example.com", Some(&code))).await;
+
+    //Assert
+    assert_eq!(other.status().as_u16(), 400);
+    assert_eq!(invited.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn failed_registration_does_not_consume_invitation() {
+    //Arrange
+    let app = spawn_app_in(RegistrationMode::Invite).await;
+    let code = insert_invitation(&app, None, 1).await;
+
+    //Act - 이미 가입된 이메일
+    let response = app.post_register(&register_body(&app.test_user.email, Some(&code))).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(invitation_uses(&app, &code).await, 0);
+}
+
+#[tokio::test]
+async fn admin_can_create_invitation() {
+    //Arrange
+    let app = spawn_app_in(RegistrationMode::Invite).await;
+    app.promote_test_user_to_admin().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act
+    let response = app.post_invitation(&serde_json::json!({"max_uses": 2, "expires_in_hours": 24})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 201);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let code = body["code"].as_str().unwrap();
+    assert!(body["url"].as_str().unwrap().ends_with(&format!("/registration?invite={}", code)));
+    let response = app.post_register(&register_body(&Uuid::new_v4().to_string(), Some(code))).await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn non_admin_cannot_create_invitation() {
+    //Arrange
+    let app = spawn_app_in(RegistrationMode::Invite).await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act
+    let response = app.post_invitation(&serde_json::json!({})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn registration_page_shows_invite_field_in_invite_mode() {
+    //Arrange
+    let app = spawn_app_in(RegistrationMode::Invite).await;
+
+    //Act
+    let html = app.get_registration_html("invite=ABC123").await;
+
+    //Assert
+    assert!(html.contains(r#"id="inviteCode""#));
+    assert!(html.contains(r#"value="ABC123""#));
+}
@@ -17,7 +17,7 @@ async fn login_try_wrong_data() {
 
     //panic!("Check the ouput above");
     
-    assert_is_redirect(response, 401, "/home").await;
+    assert_is_redirect(response, 401, "/home_jwt").await;
 }
 
 #[tokio::test]
@@ -0,0 +1,77 @@
+use crate::helpers::{spawn_app, TestApp};
+
+const FIREFOX_ON_LINUX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+const SAFARI_ON_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+
+//알림 메일은 백그라운드로 발송되므로 잠시 기다린다.
+async fn wait_for_email(app: &TestApp, to: &str) -> Option<String> {
+    for _ in 0..20 {
+        if let Some(email) = app.last_email_to(to) {
+            return Some(email);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    None
+}
+
+fn credentials(app: &TestApp) -> serde_json::Value {
+    serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })
+}
+
+#[tokio::test]
+async fn successful_login_is_shown_in_login_history() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    app.post_login_session(&credentials(&app)).await;
+    let html = app.get_home_session_html().await;
+
+    //Assert
+    assert!(html.contains("최근 로그인 기록"));
+    assert!(html.contains("password"));
+}
+
+#[tokio::test]
+async fn login_from_known_device_does_not_notify() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_session_with_user_agent(&credentials(&app), FIREFOX_ON_LINUX).await;
+
+    //Act - 버전만 다른 같은 기기
+    let response = app.post_login_session_with_user_agent(
+        &credentials(&app),
+        &FIREFOX_ON_LINUX.replace("128.0", "129.0"),
+    ).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(wait_for_email(&app, &app.test_user.email).await.is_none());
+}
+
+#[tokio::test]
+async fn login_from_new_device_sends_notification() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_session_with_user_agent(&credentials(&app), FIREFOX_ON_LINUX).await;
+
+    //Act
+    app.post_login_session_with_user_agent(&credentials(&app), SAFARI_ON_IPHONE).await;
+
+    //Assert
+    let email = wait_for_email(&app, &app.test_user.email).await.expect("No new device notification");
+    assert!(email.contains("Subject: 새 기기에서 로그인했습니다"));
+    assert!(email.contains("Safari on iOS (mobile)"));
+}
+
+#[test]
+fn device_label_ignores_versions() {
+    use rust_web::login_history::device_label;
+
+    assert_eq!(device_label(FIREFOX_ON_LINUX), "Firefox on Linux (desktop)");
+    assert_eq!(device_label(SAFARI_ON_IPHONE), "Safari on iOS (mobile)");
+    assert_eq!(device_label(""), "Unknown browser on Unknown OS (desktop)");
+}
@@ -0,0 +1,111 @@
+use reqwest::Method;
+use crate::helpers::spawn_app;
+
+//응답 쿠키에서 값을 꺼낸다.
+fn cookie_value(response: &reqwest::Response, name: &str) -> String {
+    response.cookies()
+        .find(|c| c.name() == name)
+        .map(|c| c.value().to_string())
+        .unwrap_or_else(|| panic!("No {} cookie", name))
+}
+
+#[tokio::test]
+async fn logout_without_credentials_does_not_fail() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app.post_logout().await;
+
+    //Assert - 로그인 화면 HTML
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("로그인"));
+}
+
+#[tokio::test]
+async fn logout_with_unusable_refresh_token_does_not_fail() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act - 만료/변조된 refresh token 쿠키
+    let response = app.request_with_cookies(Method::POST, "/logout", "refresh_token=not-a-jwt; access_token=not-a-jwt").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn logout_purges_session() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_session(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+    assert!(app.get_home_session_html().await.contains(&app.test_user.nickname));
+
+    //Act
+    let response = app.post_logout_json().await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["success"], true);
+    assert!(!app.get_home_session_html().await.contains(&app.test_user.nickname));
+
+    //Assert - redirect를 따라가면 로그인 화면이 나온다.
+    let response = app.get_path(body["redirect"].as_str().unwrap()).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("login-form"));
+}
+
+#[tokio::test]
+async fn json_logout_in_jwt_mode_redirects_to_the_login_page() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act
+    let response = app.post_logout_json().await;
+
+    //Assert
+    let body: serde_json::Value = response.json().await.unwrap();
+    let response = app.get_path(body["redirect"].as_str().unwrap()).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let html = response.text().await.unwrap();
+    assert!(html.contains("login-form"));
+    assert!(!html.contains(&app.test_user.nickname));
+}
+
+#[tokio::test]
+async fn logout_revokes_access_and_refresh_tokens() {
+    //Arrange
+    let app = spawn_app().await;
+    let response = app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+    let access_token = cookie_value(&response, "access_token");
+    let refresh_token = cookie_value(&response, "refresh_token");
+    let cookies = format!("access_token={}; refresh_token={}", access_token, refresh_token);
+
+    //Act
+    let response = app.request_with_cookies(Method::POST, "/logout", &cookies).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(cookie_value(&response, "access_token"), "");
+
+    //Assert1 - access token은 폐기 목록에 올라 거부된다.
+    let response = app.post_change_password_with_token(&access_token, &serde_json::json!({
+        "current_password": app.test_user.password,
+        "new_password": "a-brand-new-password",
+    })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    //Assert2 - refresh token으로 새 토큰을 받을 수 없다.
+    let response = app.request_with_cookies(Method::GET, "/home_jwt", &format!("refresh_token={}", refresh_token)).await;
+    assert!(response.cookies().all(|c| c.name() != "access_token"));
+    assert!(!response.text().await.unwrap().contains(&app.test_user.nickname));
+}
@@ -0,0 +1,43 @@
+use crate::helpers::spawn_app;
+
+//메일 본문의 링크에서 token 파라미터를 꺼낸다.
+fn extract_token(email_body: &str) -> String {
+    let start = email_body.find("token=").expect("No token in email") + "token=".len();
+    let rest = &email_body[start..];
+    let end = rest.find('&').unwrap_or(rest.len());
+    urlencoding::decode(&rest[..end]).unwrap().into_owned()
+}
+
+#[tokio::test]
+async fn magic_link_is_sent_only_to_registered_users() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app.post_magic_link(&serde_json::json!({"email": "unknown@example.com"})).await;
+
+    //Assert - 가입 여부와 상관없이 200
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(app.last_email_to("unknown@example.com").is_none());
+}
+
+#[tokio::test]
+async fn magic_link_logs_in_once() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_magic_link(&serde_json::json!({
+        "email": app.test_user.email,
+        "mode": "jwt",
+    })).await;
+    let email = app.last_email_to(&app.test_user.email).expect("Magic link email was not sent");
+    let token = extract_token(&email);
+
+    //Act1 - 첫 번째 사용은 로그인 성공
+    let response = app.get_magic_link(&token, "jwt").await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.cookies().any(|c| c.name() == "access_token"));
+
+    //Act2 - 같은 링크 재사용은 거부
+    let response = app.get_magic_link(&token, "jwt").await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -1,2 +1,26 @@
+mod admin;
+mod auth_policy;
+mod challenge;
+mod cookie_settings;
+mod document_editor;
+mod document_workflow;
+mod documents;
+mod fragments;
+mod glossary;
 mod helpers;
-mod login;
\ No newline at end of file
+mod invitation;
+mod login;
+mod login_history;
+mod logout;
+mod magic_link;
+mod markdown;
+mod oauth;
+mod oidc;
+mod passkey;
+mod reading_progress;
+mod reauth;
+mod search;
+mod table_contents;
+mod tags;
+mod token_revocation;
+mod token_store;
@@ -0,0 +1,77 @@
+use rust_web::documents::{render_markdown, slugify};
+
+#[test]
+fn headings_get_anchors_and_are_collected() {
+    let rendered = render_markdown("## Send + Sync\n\n본문\n\n### 'static 생명주기\n");
+
+    assert!(rendered.html.contains(r#"<h2 id="send-sync">"#));
+    assert!(rendered.html.contains(r##"href="#send-sync""##));
+    assert_eq!(rendered.headings.len(), 2);
+    assert_eq!(rendered.headings[1].level, 3);
+    assert_eq!(rendered.headings[1].anchor, "static-생명주기");
+}
+
+#[test]
+fn duplicate_headings_get_unique_anchors() {
+    let rendered = render_markdown("## 예시\n\n## 예시\n");
+
+    let anchors: Vec<_> = rendered.headings.iter().map(|h| h.anchor.as_str()).collect();
+    assert_eq!(anchors, vec!["예시", "예시-1"]);
+}
+
+#[test]
+fn slugify_keeps_korean_and_drops_symbols() {
+    assert_eq!(slugify("Box<dyn Trait>"), "boxdyn-trait");
+    assert_eq!(slugify("  관심사의 분리  "), "관심사의-분리");
+}
+
+#[test]
+fn scripts_and_event_handlers_are_removed() {
+    let rendered = render_markdown("<script>alert(1)</script>\n\n<img src=\"x.png\" onerror=\"alert(1)\">\n\n[link](javascript:alert(1))");
+
+    assert!(!rendered.html.contains("<script"));
+    assert!(!rendered.html.contains("onerror"));
+    assert!(!rendered.html.contains("javascript:"));
+}
+
+#[test]
+fn rust_toml_yaml_and_sql_code_blocks_are_highlighted() {
+    for (language, code) in [
+        ("rust", "fn main() { let x: u32 = 1; }"),
+        ("toml", "tracing = \"0.1\""),
+        ("yaml", "store:\n  backend: redis"),
+        ("sql", "SELECT id FROM documents"),
+    ] {
+        let rendered = render_markdown(&format!("```{}\n{}\n```", language, code));
+
+        assert!(rendered.html.contains(&format!(r#"class="language-{}""#, language)), "{}", rendered.html);
+        assert!(rendered.html.contains("hl-"), "{} was not highlighted: {}", language, rendered.html);
+    }
+}
+
+#[test]
+fn code_is_escaped_inside_highlighted_blocks() {
+    let rendered = render_markdown("```rust\nlet b: Box<dyn Fn()> = Box::new(|| {});\n```");
+
+    assert!(rendered.html.contains("&lt;"));
+    assert!(!rendered.html.contains("Box<dyn"));
+}
+
+#[test]
+fn core_callouts_render_as_boxes_with_markdown_inside() {
+    let rendered = render_markdown(":::핵심\n**다른 타입**을 반환할 때\n:::\n\n:::주의 Send\n내용\n:::");
+
+    assert!(rendered.html.contains(r#"<div class="callout callout-key">"#));
+    assert!(rendered.html.contains(r#"<p class="callout-title">핵심</p>"#));
+    assert!(rendered.html.contains("<strong>다른 타입</strong>"));
+    assert!(rendered.html.contains(r#"<div class="callout callout-warning">"#));
+    assert!(rendered.html.contains("주의 · Send"));
+}
+
+#[test]
+fn callout_markers_inside_code_blocks_are_left_alone() {
+    let rendered = render_markdown("```text\n:::핵심\n```");
+
+    assert!(!rendered.html.contains("callout"));
+    assert!(rendered.html.contains(":::핵심"));
+}
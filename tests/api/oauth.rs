@@ -0,0 +1,307 @@
+use argon2::{password_hash::SaltString, Algorithm, Argon2, Params, PasswordHasher, Version};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use crate::helpers::{spawn_app, TestApp};
+
+//client_credentials만 허용된 confidential client를 DB에 직접 등록한다.
+async fn store_client(app: &TestApp) -> (String, String) {
+    let client_id = Uuid::new_v4().to_string();
+    let client_secret = Uuid::new_v4().to_string();
+    let salt = SaltString::generate(&mut rand::thread_rng());
+    let secret_hash = Argon2::new(Algorithm::Argon2id, Version::V0x13, Params::new(15000, 2, 1, None).unwrap())
+        .hash_password(client_secret.as_bytes(), &salt)
+        .unwrap()
+        .to_string();
+
+    sqlx::query!(
+        "INSERT INTO oauth_clients (client_id, client_secret_hash, name, redirect_uris, grant_types, scopes, created_at)
+        VALUES ($1, $2, 'dashboard', '{}', '{client_credentials}', '{audit:read}', $3)",
+        client_id,
+        secret_hash,
+        Utc::now()
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to store oauth client.");
+
+    (client_id, client_secret)
+}
+
+const REDIRECT_URI: &str = "http://localhost/callback";
+const CODE_VERIFIER: &str = "test-code-verifier-0123456789-abcdefghijklmnopqrstuvwxyz";
+
+//authorization_code + refresh_token을 쓰는 public client(PKCE 전용, secret 없음)를 등록한다.
+async fn store_public_client(app: &TestApp) -> String {
+    let client_id = Uuid::new_v4().to_string();
+    sqlx::query!(
+        "INSERT INTO oauth_clients (client_id, client_secret_hash, name, redirect_uris, grant_types, scopes, created_at)
+        VALUES ($1, NULL, 'cli', $2, '{authorization_code,refresh_token}', '{profile}', $3)",
+        client_id,
+        &[REDIRECT_URI.to_string()],
+        Utc::now()
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to store oauth client.");
+
+    client_id
+}
+
+fn pkce_challenge(verifier: &str) -> String {
+    base64::encode_config(Sha256::digest(verifier.as_bytes()), base64::URL_SAFE_NO_PAD)
+}
+
+//로그인 -> 동의 화면 -> 승인/거부까지 진행하고 redirect_uri로 돌아가는 Location을 돌려준다.
+async fn run_consent_flow(app: &TestApp, client_id: &str, decision: &str) -> String {
+    app.post_login_json(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    }))
+    .await;
+    let challenge = pkce_challenge(CODE_VERIFIER);
+    let response = app.api_client
+        .get(format!("{}/oauth/authorize", app.address))
+        .query(&[
+            ("response_type", "code"),
+            ("client_id", client_id),
+            ("redirect_uri", REDIRECT_URI),
+            ("scope", "profile"),
+            ("state", "xyz"),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ])
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status().as_u16(), 200);
+    let html = response.text().await.unwrap();
+    assert!(html.contains("consentForm"));
+    let consent_token = html
+        .split("name=\"consent_token\" value=\"").nth(1).unwrap()
+        .split('"').next().unwrap()
+        .to_owned();
+
+    let response = app.api_client
+        .post(format!("{}/oauth/authorize", app.address))
+        .form(&[
+            ("client_id", client_id),
+            ("redirect_uri", REDIRECT_URI),
+            ("scope", "profile"),
+            ("state", "xyz"),
+            ("code_challenge", challenge.as_str()),
+            ("consent_token", consent_token.as_str()),
+            ("decision", decision),
+        ])
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status().as_u16(), 303);
+    response.headers()["Location"].to_str().unwrap().to_owned()
+}
+
+fn query_param(url: &str, name: &str) -> Option<String> {
+    reqwest::Url::parse(url).unwrap()
+        .query_pairs()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.into_owned())
+}
+
+async fn exchange_code(app: &TestApp, client_id: &str, code: &str, code_verifier: &str) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/oauth/token", app.address))
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("client_id", client_id),
+            ("code", code),
+            ("redirect_uri", REDIRECT_URI),
+            ("code_verifier", code_verifier),
+        ])
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn refresh(app: &TestApp, client_id: &str, refresh_token: &str) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/oauth/token", app.address))
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("client_id", client_id),
+            ("refresh_token", refresh_token),
+        ])
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn post_form(app: &TestApp, path: &str, client: &(String, String), form: &[(&str, &str)]) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}{}", app.address, path))
+        .basic_auth(&client.0, Some(&client.1))
+        .form(form)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn client_credentials_token_can_be_introspected_and_revoked() {
+    //Arrange
+    let app = spawn_app().await;
+    let client = store_client(&app).await;
+
+    //Act1 - 토큰 발급
+    let response = post_form(&app, "/oauth/token", &client, &[("grant_type", "client_credentials")]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let access_token = body["access_token"].as_str().unwrap().to_owned();
+    assert_eq!(body["scope"], "audit:read");
+
+    //Act2 - introspection
+    let response = post_form(&app, "/oauth/introspect", &client, &[("token", &access_token)]).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["active"], true);
+    assert_eq!(body["client_id"], client.0.as_str());
+
+    //Act3 - 폐기 후에는 active = false
+    let response = post_form(&app, "/oauth/revoke", &client, &[("token", &access_token)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let response = post_form(&app, "/oauth/introspect", &client, &[("token", &access_token)]).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["active"], false);
+}
+
+#[tokio::test]
+async fn token_endpoint_rejects_wrong_client_secret() {
+    //Arrange
+    let app = spawn_app().await;
+    let (client_id, _) = store_client(&app).await;
+
+    //Act
+    let response = post_form(&app, "/oauth/token", &(client_id, "wrong".to_string()), &[("grant_type", "client_credentials")]).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "invalid_client");
+}
+
+#[tokio::test]
+async fn authorization_code_with_pkce_issues_tokens_after_consent() {
+    //Arrange
+    let app = spawn_app().await;
+    let client_id = store_public_client(&app).await;
+
+    //Act
+    let location = run_consent_flow(&app, &client_id, "approve").await;
+    let code = query_param(&location, "code").expect("No code in redirect");
+    let response = exchange_code(&app, &client_id, &code, CODE_VERIFIER).await;
+
+    //Assert
+    assert!(location.starts_with(REDIRECT_URI));
+    assert_eq!(query_param(&location, "state").as_deref(), Some("xyz"));
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["access_token"].is_string());
+    assert!(body["refresh_token"].is_string());
+    assert_eq!(body["scope"], "profile");
+}
+
+#[tokio::test]
+async fn denied_consent_redirects_with_access_denied() {
+    //Arrange
+    let app = spawn_app().await;
+    let client_id = store_public_client(&app).await;
+
+    //Act
+    let location = run_consent_flow(&app, &client_id, "deny").await;
+
+    //Assert
+    assert_eq!(query_param(&location, "error").as_deref(), Some("access_denied"));
+    assert!(query_param(&location, "code").is_none());
+}
+
+#[tokio::test]
+async fn token_endpoint_rejects_wrong_code_verifier() {
+    //Arrange
+    let app = spawn_app().await;
+    let client_id = store_public_client(&app).await;
+    let location = run_consent_flow(&app, &client_id, "approve").await;
+    let code = query_param(&location, "code").unwrap();
+
+    //Act
+    let response = exchange_code(&app, &client_id, &code, "wrong-code-verifier").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "invalid_grant");
+}
+
+#[tokio::test]
+async fn authorization_code_cannot_be_reused() {
+    //Arrange
+    let app = spawn_app().await;
+    let client_id = store_public_client(&app).await;
+    let location = run_consent_flow(&app, &client_id, "approve").await;
+    let code = query_param(&location, "code").unwrap();
+    let first = exchange_code(&app, &client_id, &code, CODE_VERIFIER).await;
+    assert_eq!(first.status().as_u16(), 200);
+
+    //Act
+    let second = exchange_code(&app, &client_id, &code, CODE_VERIFIER).await;
+
+    //Assert
+    assert_eq!(second.status().as_u16(), 400);
+    let body: serde_json::Value = second.json().await.unwrap();
+    assert_eq!(body["error"], "invalid_grant");
+}
+
+#[tokio::test]
+async fn refresh_token_is_rotated_and_cannot_be_reused() {
+    //Arrange
+    let app = spawn_app().await;
+    let client_id = store_public_client(&app).await;
+    let location = run_consent_flow(&app, &client_id, "approve").await;
+    let code = query_param(&location, "code").unwrap();
+    let body: serde_json::Value = exchange_code(&app, &client_id, &code, CODE_VERIFIER).await.json().await.unwrap();
+    let old_refresh = body["refresh_token"].as_str().unwrap().to_owned();
+
+    //Act1 - rotate
+    let response = refresh(&app, &client_id, &old_refresh).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let new_refresh = body["refresh_token"].as_str().unwrap().to_owned();
+    assert_ne!(new_refresh, old_refresh);
+
+    //Act2 - 이미 사용한 refresh token 재사용
+    let response = refresh(&app, &client_id, &old_refresh).await;
+    assert_eq!(response.status().as_u16(), 400);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "invalid_grant");
+
+    //Act3 - 새 refresh token은 사용할 수 있다.
+    let response = refresh(&app, &client_id, &new_refresh).await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn revoke_does_not_touch_tokens_of_other_clients() {
+    //Arrange
+    let app = spawn_app().await;
+    let owner = store_client(&app).await;
+    let other = store_client(&app).await;
+    let response = post_form(&app, "/oauth/token", &owner, &[("grant_type", "client_credentials")]).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    let access_token = body["access_token"].as_str().unwrap().to_owned();
+
+    //Act - 다른 클라이언트가 폐기 요청 (RFC 7009 : 응답은 200이지만 아무 일도 하지 않는다.)
+    let response = post_form(&app, "/oauth/revoke", &other, &[("token", &access_token)]).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let response = post_form(&app, "/oauth/introspect", &owner, &[("token", &access_token)]).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["active"], true);
+}
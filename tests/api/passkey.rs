@@ -0,0 +1,92 @@
+use webauthn_authenticator_rs::{softpasskey::SoftPasskey, WebauthnAuthenticator};
+use webauthn_rs::prelude::{CreationChallengeResponse, RequestChallengeResponse, Url};
+use crate::helpers::spawn_app;
+
+//configuration/local.yaml의 webauthn.rp_origin과 같아야 한다.
+const ORIGIN: &str = "http://localhost:8000";
+
+#[tokio::test]
+async fn passkey_registration_requires_login() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app.post_webauthn("register/start", &serde_json::json!({})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn registered_passkey_can_log_in() {
+    //Arrange - 비밀번호로 로그인 후 패스키 등록
+    let app = spawn_app().await;
+    let origin = Url::parse(ORIGIN).unwrap();
+    let mut authenticator = WebauthnAuthenticator::new(SoftPasskey::new(true));
+    let response = app.post_login_session(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let challenge: CreationChallengeResponse = app
+        .post_webauthn("register/start", &serde_json::json!({}))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let credential = authenticator.do_registration(origin.clone(), challenge).expect("Registration failed");
+    let response = app.post_webauthn("register/finish", &credential).await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    //Act - 패스키로 JWT 로그인
+    let challenge: RequestChallengeResponse = app
+        .post_webauthn("login/start", &serde_json::json!({"email": app.test_user.email, "mode": "jwt"}))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let credential = authenticator.do_authentication(origin, challenge).expect("Authentication failed");
+    let response = app.post_webauthn("login/finish", &credential).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.cookies().any(|c| c.name() == "access_token"));
+    let last_used = sqlx::query!("SELECT last_used_at FROM webauthn_credentials WHERE email = $1", app.test_user.email)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert!(last_used.last_used_at.is_some());
+}
+
+#[tokio::test]
+async fn passkey_login_without_registered_passkey_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app
+        .post_webauthn("login/start", &serde_json::json!({"email": app.test_user.email, "mode": "jwt"}))
+        .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn passkey_login_does_not_reveal_whether_the_account_exists() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act - 패스키가 없는 기존 계정 / 가입하지 않은 이메일
+    let existing = app
+        .post_webauthn("login/start", &serde_json::json!({"email": app.test_user.email, "mode": "jwt"}))
+        .await;
+    let unknown = app
+        .post_webauthn("login/start", &serde_json::json!({"email": "nobody@example.com", "mode": "jwt"}))
+        .await;
+
+    //Assert
+    assert_eq!(existing.status(), unknown.status());
+    assert_eq!(existing.text().await.unwrap(), unknown.text().await.unwrap());
+}
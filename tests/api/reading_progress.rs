@@ -0,0 +1,184 @@
+use uuid::Uuid;
+use crate::helpers::{spawn_app, TestApp};
+
+async fn document_id(app: &TestApp, chapter: &str, slug: &str) -> Uuid {
+    sqlx::query_scalar!("SELECT id FROM documents WHERE chapter = $1 AND slug = $2", chapter, slug)
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to query document")
+}
+
+async fn open_document(app: &TestApp, path: &str) -> String {
+    app.api_client
+        .get(format!("{}{}", &app.address, path))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .unwrap()
+}
+
+async fn post_progress(app: &TestApp, id: Uuid, body: serde_json::Value) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/api/progress/{}", &app.address, id))
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn login_session(app: &TestApp) {
+    let response = app.post_login_session(&serde_json::json!({
+        "email": &app.test_user.email,
+        "password": &app.test_user.password,
+    })).await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+fn find_chapter<'a>(toc: &'a serde_json::Value, chapter: &str) -> &'a serde_json::Value {
+    toc["sections"].as_array().unwrap().iter()
+        .flat_map(|section| section["chapters"].as_array().unwrap().iter())
+        .find(|c| c["chapter"] == chapter)
+        .unwrap_or_else(|| panic!("chapter {} not in toc", chapter))
+}
+
+#[tokio::test]
+async fn progress_requires_login() {
+    //Arrange
+    let app = spawn_app().await;
+    let id = document_id(&app, "ch1_1", "n1_basic").await;
+
+    //Act
+    let response = post_progress(&app, id, serde_json::json!({ "scroll": 0.5, "seconds": 30 })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn scroll_position_is_replaced_and_time_is_added_up() {
+    //Arrange
+    let app = spawn_app().await;
+    login_session(&app).await;
+    let id = document_id(&app, "ch1_1", "n2_settings").await;
+    let html = open_document(&app, "/docs/ch1_1/n2_settings").await;
+    assert!(html.contains(&format!(r#"data-document-id="{}""#, id)));
+
+    //Act
+    post_progress(&app, id, serde_json::json!({ "scroll": 0.5, "seconds": 30 })).await;
+    //자리를 비운 시간은 한 번에 MAX_REPORTED_SECONDS(300초)까지만 더한다.
+    let response = post_progress(&app, id, serde_json::json!({ "scroll": 0.3, "seconds": 3600 })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let progress: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(progress["seconds_spent"], 330);
+    assert!((progress["scroll_position"].as_f64().unwrap() - 0.3).abs() < 1e-6);
+    assert!(progress["completed_at"].is_null());
+}
+
+#[tokio::test]
+async fn invalid_reports_are_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    login_session(&app).await;
+    let id = document_id(&app, "ch1_1", "n1_basic").await;
+
+    //Act
+    let out_of_range = post_progress(&app, id, serde_json::json!({ "scroll": 1.5 })).await;
+    let unknown = post_progress(&app, Uuid::new_v4(), serde_json::json!({ "scroll": 0.5 })).await;
+
+    //Assert
+    assert_eq!(out_of_range.status().as_u16(), 400);
+    assert_eq!(unknown.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn completed_documents_are_checked_in_the_toc_with_chapter_percentage() {
+    //Arrange
+    let app = spawn_app().await;
+    login_session(&app).await;
+    let id = document_id(&app, "ch1_1", "n3_span_event").await;
+    open_document(&app, "/docs/ch1_1/n2_settings").await;
+
+    //Act
+    post_progress(&app, id, serde_json::json!({ "scroll": 0.95, "seconds": 60 })).await;
+    //다시 위에서부터 읽어도 완료 표시는 남는다.
+    post_progress(&app, id, serde_json::json!({ "scroll": 0.1, "seconds": 10 })).await;
+    let toc: serde_json::Value = app.api_client
+        .get(format!("{}/api/contents", &app.address))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let html = open_document(&app, "/").await;
+
+    //Assert
+    let chapter = find_chapter(&toc, "ch1_1");
+    assert_eq!(chapter["progress"], 33);
+    assert_eq!(chapter["completed"], false);
+    let entries = chapter["entries"].as_array().unwrap();
+    let settings = entries.iter().find(|e| e["path"] == "/docs/ch1_1/n2_settings").unwrap();
+    let span_event = entries.iter().find(|e| e["path"] == "/docs/ch1_1/n3_span_event").unwrap();
+    assert_eq!(settings["read"], true);
+    assert_eq!(settings["completed"], false);
+    assert_eq!(span_event["completed"], true);
+    assert!(html.contains("33%"));
+    assert!(html.contains("toc-reading"));
+}
+
+#[tokio::test]
+async fn home_page_offers_to_continue_reading() {
+    //Arrange
+    let app = spawn_app().await;
+    login_session(&app).await;
+    let id = document_id(&app, "ch1_2", "n1_basic").await;
+    open_document(&app, "/docs/ch1_2/n1_basic").await;
+    post_progress(&app, id, serde_json::json!({ "scroll": 0.4, "seconds": 90 })).await;
+
+    //Act
+    let html = open_document(&app, "/home_session").await;
+
+    //Assert
+    assert!(html.contains("이어서 읽기"));
+    assert!(html.contains(r#"href="/docs/ch1_2/n1_basic#continue""#));
+    assert!(html.contains("40% 읽음 · 2분"));
+}
+
+#[tokio::test]
+async fn next_document_is_suggested_after_finishing_one() {
+    //Arrange
+    let app = spawn_app().await;
+    login_session(&app).await;
+    let id = document_id(&app, "ch1_1", "n1_basic").await;
+    post_progress(&app, id, serde_json::json!({ "scroll": 1.0, "completed": true })).await;
+
+    //Act
+    let html = open_document(&app, "/home_session").await;
+
+    //Assert
+    assert!(html.contains("다음 문서"));
+    assert!(html.contains(r#"href="/docs/ch1_1/n2_settings""#));
+}
+
+#[tokio::test]
+async fn jwt_logins_record_progress_too() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+    let id = document_id(&app, "ch1_3", "n1_impl_trait").await;
+
+    //Act
+    let response = post_progress(&app, id, serde_json::json!({ "scroll": 0.6, "seconds": 30 })).await;
+    let html = open_document(&app, "/home_jwt").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(html.contains(r#"href="/docs/ch1_3/n1_impl_trait#continue""#));
+}
@@ -0,0 +1,110 @@
+use crate::helpers::{spawn_app, spawn_app_with};
+
+#[tokio::test]
+async fn password_change_requires_recent_authentication() {
+    //Arrange - 재인증 허용 시간이 음수면 항상 오래된 인증으로 취급된다.
+    let app = spawn_app_with(|c| c.auth_policy.reauth_minutes = -1).await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act
+    let response = app.post_change_password(&serde_json::json!({
+        "current_password": app.test_user.password,
+        "new_password": "a-brand-new-password",
+    })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "reauth_required");
+    assert_eq!(body["reauth"], "/api/reauth");
+}
+
+#[tokio::test]
+async fn reauth_with_correct_password_refreshes_jwt_cookies() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act
+    let response = app.post_reauth(&serde_json::json!({"password": app.test_user.password})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.cookies().any(|c| c.name() == "access_token"));
+    assert!(response.cookies().any(|c| c.name() == "refresh_token"));
+}
+
+#[tokio::test]
+async fn reauth_keeps_the_role_of_the_user() {
+    //Arrange - 일반 사용자
+    let app = spawn_app().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act
+    let response = app.post_reauth(&serde_json::json!({"password": app.test_user.password})).await;
+
+    //Assert - 재발급된 access token에 관리자 역할이 들어가지 않는다.
+    let access_token = response.cookies()
+        .find(|c| c.name() == "access_token")
+        .expect("No access token cookie")
+        .value()
+        .to_string();
+    let payload = access_token.split('.').nth(1).expect("Malformed jwt");
+    let bytes = base64::decode_config(payload, base64::URL_SAFE_NO_PAD).expect("Malformed jwt payload");
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).expect("Malformed jwt payload");
+    assert_eq!(claims["email"], app.test_user.email.as_str());
+    assert_eq!(claims["role"], "user");
+}
+
+#[tokio::test]
+async fn reauth_with_wrong_password_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_session(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act
+    let response = app.post_reauth(&serde_json::json!({"password": "wrong-password"})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn reauth_in_session_mode_succeeds() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_session(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act
+    let response = app.post_reauth(&serde_json::json!({"password": app.test_user.password})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn reauth_requires_login() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app.post_reauth(&serde_json::json!({"password": app.test_user.password})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,156 @@
+use rust_web::documents::{search_terms, snippet};
+use crate::helpers::{spawn_app, TestApp};
+
+async fn search(app: &TestApp, query: &str) -> serde_json::Value {
+    app.api_client
+        .get(format!("{}/api/search", &app.address))
+        .query(&[("q", query)])
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .unwrap()
+}
+
+fn hit_paths(results: &serde_json::Value) -> Vec<String> {
+    results["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["path"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn korean_words_are_split_into_bigrams() {
+    assert_eq!(search_terms("Send + Sync"), vec!["send", "sync"]);
+    assert_eq!(search_terms("생명주기는"), vec!["생명", "명주", "주기", "기는"]);
+    assert_eq!(search_terms("Span과 Event"), vec!["span", "과", "event"]);
+}
+
+#[test]
+fn snippets_mark_matches_and_escape_the_rest() {
+    let terms = search_terms("생명주기");
+    let html = snippet("<T> 모든 생명주기에 대해", &terms);
+
+    assert_eq!(html, "&lt;T&gt; 모든 <mark>생명주기</mark>에 대해");
+}
+
+#[tokio::test]
+async fn english_terms_find_the_matching_lesson_first() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let results = search(&app, "HRTB").await;
+
+    //Assert
+    assert_eq!(hit_paths(&results)[0], "/docs/ch1_4/n1_lifetime");
+}
+
+#[tokio::test]
+async fn symbols_in_queries_are_ignored() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let results = search(&app, "Send + Sync").await;
+
+    //Assert
+    let paths = hit_paths(&results);
+    assert!(paths.contains(&"/docs/ch1_3/n1_impl_trait".to_string()));
+    assert!(results["hits"][0]["snippet"].as_str().unwrap().contains("<mark>Send</mark>"));
+}
+
+#[tokio::test]
+async fn korean_queries_match_inside_words() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let results = search(&app, "생명주기").await;
+
+    //Assert
+    assert_eq!(hit_paths(&results)[0], "/docs/ch1_4/n1_lifetime");
+    let facets = results["facets"].as_array().unwrap();
+    assert!(facets.iter().any(|facet| facet["chapter"] == "ch1_4"));
+}
+
+#[tokio::test]
+async fn chapter_facets_narrow_the_results() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let results: serde_json::Value = app.api_client
+        .get(format!("{}/api/search", &app.address))
+        .query(&[("q", "Send Sync"), ("chapter", "ch1_4")])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    //Assert
+    assert!(results["total"].as_u64().unwrap() >= 2);
+    assert_eq!(hit_paths(&results), vec!["/docs/ch1_4/n1_lifetime"]);
+}
+
+#[tokio::test]
+async fn saved_and_approved_documents_are_reindexed() {
+    //Arrange
+    let app = spawn_app().await;
+    app.promote_test_user_to_admin().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+    let row = sqlx::query!("SELECT id, revision FROM documents WHERE chapter = 'ch1_1' AND slug = 'n1_basic'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    let post = |path: String, body: serde_json::Value| {
+        app.api_client.post(format!("{}{}", &app.address, path)).json(&body).send()
+    };
+
+    //Act
+    app.api_client
+        .put(format!("{}/admin/docs/{}", &app.address, row.id))
+        .json(&serde_json::json!({"title": "Tracing 기초", "body": "새로운 색인어 zebrafish", "revision": row.revision}))
+        .send()
+        .await
+        .unwrap();
+    let before_approval = search(&app, "zebrafish").await;
+    post(format!("/admin/docs/{}/submit", row.id), serde_json::json!({})).await.unwrap();
+    post(format!("/admin/docs/{}/reviews", row.id), serde_json::json!({"revision": row.revision + 1, "decision": "approve"}))
+        .await
+        .unwrap();
+    let after_approval = search(&app, "zebrafish").await;
+
+    //Assert
+    assert_eq!(before_approval["total"], 0);
+    assert_eq!(hit_paths(&after_approval), vec!["/docs/ch1_1/n1_basic"]);
+}
+
+#[tokio::test]
+async fn search_page_renders_highlighted_results() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let html = app.api_client
+        .get(format!("{}/search", &app.address))
+        .query(&[("q", "MakeWriter")])
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    //Assert
+    assert!(html.contains(r#"href="/docs/ch1_4/n1_lifetime""#));
+    assert!(html.contains("<mark>MakeWriter</mark>"));
+}
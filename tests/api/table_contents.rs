@@ -0,0 +1,112 @@
+use uuid::Uuid;
+use crate::helpers::{spawn_app, TestApp};
+
+async fn get_contents_json(app: &TestApp) -> serde_json::Value {
+    app.api_client
+        .get(format!("{}/api/contents", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .unwrap()
+}
+
+fn find_chapter<'a>(toc: &'a serde_json::Value, chapter: &str) -> &'a serde_json::Value {
+    toc["sections"].as_array().unwrap().iter()
+        .flat_map(|section| section["chapters"].as_array().unwrap().iter())
+        .find(|c| c["chapter"] == chapter)
+        .unwrap_or_else(|| panic!("chapter {} not in toc", chapter))
+}
+
+#[tokio::test]
+async fn contents_are_built_from_stored_documents_with_numbering() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let toc = get_contents_json(&app).await;
+
+    //Assert
+    let sections = toc["sections"].as_array().unwrap();
+    assert_eq!(sections[0]["number"], "0");
+    assert_eq!(sections[0]["title"], "설계 패턴");
+    assert_eq!(sections[0]["chapters"][0]["number"], "0.1");
+    assert_eq!(sections[0]["chapters"][1]["number"], "0.2");
+    assert_eq!(sections[1]["number"], "1");
+    let numbers: Vec<_> = sections[1]["chapters"].as_array().unwrap().iter().map(|c| c["number"].as_str().unwrap()).collect();
+    assert_eq!(numbers, vec!["1.1", "1.2", "1.3", "1.4"]);
+}
+
+#[tokio::test]
+async fn chapter_entries_come_from_headings_and_other_documents() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let toc = get_contents_json(&app).await;
+
+    //Assert
+    let impl_trait = find_chapter(&toc, "ch1_3");
+    let paths: Vec<_> = impl_trait["entries"].as_array().unwrap().iter().map(|e| e["path"].as_str().unwrap()).collect();
+    assert!(paths.contains(&"/docs/ch1_3/n1_impl_trait#send-sync"));
+    let tracing = find_chapter(&toc, "ch1_1");
+    let titles: Vec<_> = tracing["entries"].as_array().unwrap().iter().map(|e| e["title"].as_str().unwrap()).collect();
+    assert_eq!(titles, vec!["기본 설정", "Span과 Event"]);
+    assert_eq!(tracing["entries"][1]["children"][0]["path"], "/docs/ch1_1/n3_span_event#span");
+}
+
+#[tokio::test]
+async fn contents_page_renders_the_generated_toc() {
+    //Arrange
+    let app = spawn_app().await;
+    sqlx::query!(
+        r#"
+        INSERT INTO documents (id, chapter, section, slug, title, body, position, created_at, updated_at)
+        VALUES ($1, 'ch2_1', '실전', 'n1_new', '새 챕터', '## 첫 제목', 0, now(), now())
+        "#,
+        Uuid::new_v4()
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to insert document");
+
+    //Act
+    let html = app.api_client.get(&app.address).send().await.unwrap().text().await.unwrap();
+
+    //Assert
+    assert!(html.contains("2. 실전"));
+    assert!(html.contains(r#"href="/docs/ch2_1/n1_new""#));
+    assert!(html.contains(r##"href="/docs/ch2_1/n1_new#첫-제목""##));
+    assert!(!html.contains("/templates/ch1_1"));
+}
+
+#[tokio::test]
+async fn read_markers_are_tracked_per_user() {
+    //Arrange
+    let app = spawn_app().await;
+    let before = get_contents_json(&app).await;
+    assert_eq!(find_chapter(&before, "ch1_1")["read"], false);
+    let response = app.post_login_session(&serde_json::json!({
+        "email": &app.test_user.email,
+        "password": &app.test_user.password,
+    })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    //Act
+    app.api_client
+        .get(format!("{}/docs/ch1_1/n3_span_event", &app.address))
+        .send()
+        .await
+        .unwrap();
+    let toc = get_contents_json(&app).await;
+
+    //Assert
+    let chapter = find_chapter(&toc, "ch1_1");
+    assert_eq!(chapter["read"], false);
+    assert_eq!(chapter["entries"][1]["read"], true);
+    assert_eq!(chapter["entries"][0]["read"], false);
+    //다른 사용자(로그인하지 않은 클라이언트)에게는 표시되지 않는다.
+    let anonymous: serde_json::Value = reqwest::get(&format!("{}/api/contents", &app.address)).await.unwrap().json().await.unwrap();
+    assert_eq!(find_chapter(&anonymous, "ch1_1")["entries"][1]["read"], false);
+}
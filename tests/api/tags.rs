@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+use rust_web::documents::jaccard;
+use crate::helpers::{spawn_app, TestApp};
+
+async fn get_html(app: &TestApp, path: &str) -> (u16, String) {
+    let response = app.api_client
+        .get(format!("{}{}", &app.address, path))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    (response.status().as_u16(), response.text().await.unwrap())
+}
+
+#[test]
+fn jaccard_is_the_share_of_common_terms() {
+    let a: HashSet<&str> = ["span", "event", "tracing"].into_iter().collect();
+    let b: HashSet<&str> = ["span", "tracing", "subscriber", "layer"].into_iter().collect();
+
+    assert_eq!(jaccard(&a, &b), 2.0 / 5.0);
+    assert_eq!(jaccard(&HashSet::new(), &HashSet::new()), 0.0);
+}
+
+#[tokio::test]
+async fn documents_show_their_tags_and_related_lessons() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let (status, html) = get_html(&app, "/docs/ch1_1/n1_basic").await;
+
+    //Assert
+    assert_eq!(status, 200);
+    assert!(html.contains(r#"href="/tags/tracing""#));
+    let related = &html[html.find("함께 보면 좋은 문서").expect("related box")..];
+    assert!(related.contains(r#"href="/docs/ch1_1/n3_span_event""#));
+    assert!(!related.contains(r#"href="/docs/ch1_1/n1_basic""#));
+}
+
+#[tokio::test]
+async fn tag_pages_list_tagged_documents() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let (status, html) = get_html(&app, "/tags/traits").await;
+    let (missing, _) = get_html(&app, "/tags/missing").await;
+
+    //Assert
+    assert_eq!(status, 200);
+    assert!(html.contains(r#"href="/docs/ch1_3/n1_impl_trait""#));
+    assert!(html.contains(r#"href="/docs/ch1_4/n1_lifetime""#));
+    assert!(!html.contains(r#"href="/docs/ch0_1/n1_separation""#));
+    assert_eq!(missing, 404);
+}
+
+#[tokio::test]
+async fn toc_can_be_filtered_by_tag() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let toc: serde_json::Value = app.api_client
+        .get(format!("{}/api/contents?tag=lifetimes", &app.address))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    //Assert
+    let chapters: Vec<&str> = toc["sections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|section| section["chapters"].as_array().unwrap())
+        .map(|chapter| chapter["chapter"].as_str().unwrap())
+        .collect();
+    assert_eq!(chapters, vec!["ch1_4"]);
+}
+
+#[tokio::test]
+async fn editors_can_change_document_tags() {
+    //Arrange
+    let app = spawn_app().await;
+    app.grant_test_user_permission("content:edit").await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+    let id = sqlx::query_scalar!("SELECT id FROM documents WHERE chapter = 'ch0_1' AND slug = 'n1_separation'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    let put_tags = |tags: serde_json::Value| {
+        app.api_client
+            .put(format!("{}/admin/docs/{}/tags", &app.address, id))
+            .json(&serde_json::json!({ "tags": tags }))
+            .send()
+    };
+
+    //Act
+    let invalid = put_tags(serde_json::json!(["Not Valid"])).await.unwrap();
+    let valid = put_tags(serde_json::json!(["design", "actix"])).await.unwrap();
+
+    //Assert
+    assert_eq!(invalid.status().as_u16(), 400);
+    assert_eq!(valid.status().as_u16(), 200);
+    let (_, html) = get_html(&app, "/tags/actix").await;
+    assert!(html.contains(r#"href="/docs/ch0_1/n1_separation""#));
+}
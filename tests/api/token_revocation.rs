@@ -0,0 +1,70 @@
+use crate::helpers::spawn_app;
+
+//응답 쿠키에서 access token 값을 꺼낸다.
+fn access_token(response: &reqwest::Response) -> String {
+    response.cookies()
+        .find(|c| c.name() == "access_token")
+        .map(|c| c.value().to_string())
+        .expect("No access_token cookie")
+}
+
+#[tokio::test]
+async fn password_change_revokes_existing_access_tokens() {
+    //Arrange
+    let app = spawn_app().await;
+    let response = app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let old_token = access_token(&response);
+
+    //Act
+    let response = app.post_change_password(&serde_json::json!({
+        "current_password": app.test_user.password,
+        "new_password": "a-brand-new-password",
+    })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    //Assert - 변경 전에 발급된 access token은 거부된다.
+    let response = app.post_change_password_with_token(&old_token, &serde_json::json!({
+        "current_password": "a-brand-new-password",
+        "new_password": "another-password",
+    })).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn password_change_with_wrong_current_password_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act
+    let response = app.post_change_password(&serde_json::json!({
+        "current_password": "wrong-password",
+        "new_password": "a-brand-new-password",
+    })).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn force_logout_requires_admin() {
+    //Arrange
+    let app = spawn_app().await;
+    app.post_login_jwt(&serde_json::json!({
+        "email": app.test_user.email,
+        "password": app.test_user.password,
+    })).await;
+
+    //Act - 일반 사용자(role = user)
+    let response = app.post_force_logout(&serde_json::json!({"email": app.test_user.email})).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 403);
+}
@@ -0,0 +1,56 @@
+use rust_web::auth::{MemoryTokenStore, TokenStore};
+
+#[tokio::test]
+async fn memory_store_set_get_and_delete() {
+    let store = MemoryTokenStore::new();
+
+    store.set_ex("refresh_token:a@test.com:1", "token", 60).await.unwrap();
+    assert_eq!(store.get("refresh_token:a@test.com:1").await.unwrap().as_deref(), Some("token"));
+    assert!(store.exists("refresh_token:a@test.com:1").await.unwrap());
+
+    //1회용 토큰처럼 두 번째 삭제는 false
+    assert!(store.del("refresh_token:a@test.com:1").await.unwrap());
+    assert!(!store.del("refresh_token:a@test.com:1").await.unwrap());
+    assert_eq!(store.get("refresh_token:a@test.com:1").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn memory_store_treats_expired_keys_as_missing() {
+    let store = MemoryTokenStore::new();
+
+    store.set_ex("magic_link:1", "a@test.com", 0).await.unwrap();
+
+    assert!(!store.exists("magic_link:1").await.unwrap());
+    assert!(store.keys_with_prefix("magic_link:").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn memory_store_lists_keys_by_prefix() {
+    let store = MemoryTokenStore::new();
+
+    store.set_ex("refresh_token:a@test.com:1", "1", 60).await.unwrap();
+    store.set_ex("refresh_token:a@test.com:2", "2", 60).await.unwrap();
+    store.set_ex("refresh_token:b@test.com:3", "3", 60).await.unwrap();
+
+    let mut keys = store.keys_with_prefix("refresh_token:a@test.com:").await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["refresh_token:a@test.com:1", "refresh_token:a@test.com:2"]);
+}
+
+#[tokio::test]
+async fn memory_store_counter_increments() {
+    let store = MemoryTokenStore::new();
+
+    assert_eq!(store.incr("login_failures:a@test.com", 60).await.unwrap(), 1);
+    assert_eq!(store.incr("login_failures:a@test.com", 60).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn memory_store_get_del_returns_the_value_only_once() {
+    let store = MemoryTokenStore::new();
+
+    store.set_ex("oauth_refresh:1", "client", 60).await.unwrap();
+
+    assert_eq!(store.get_del("oauth_refresh:1").await.unwrap().as_deref(), Some("client"));
+    assert_eq!(store.get_del("oauth_refresh:1").await.unwrap(), None);
+}